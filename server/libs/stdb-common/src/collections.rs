@@ -0,0 +1,114 @@
+use crate::error::{ServiceError, ServiceResult};
+use std::slice::Iter;
+
+/// A `Vec` holding at most `max_len` items, to build rows with "at most N" limits before inserting them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundedVec<T> {
+    items: Vec<T>,
+    max_len: usize,
+}
+
+impl<T> BoundedVec<T> {
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            items: Vec::new(),
+            max_len,
+        }
+    }
+
+    /// Appends an item.
+    ///
+    /// # Errors
+    /// Returns `ServiceError::BadRequest` if the vec already holds `max_len` items.
+    pub fn push(&mut self, item: T) -> ServiceResult<()> {
+        if self.is_full() {
+            return Err(ServiceError::BadRequest("limit exceeded".into()));
+        }
+
+        self.items.push(item);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.items.len() >= self.max_len
+    }
+
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Whether any item has `key`, as extracted by `key_fn`.
+    pub fn contains_key<K: PartialEq>(&self, key: &K, key_fn: impl Fn(&T) -> K) -> bool {
+        self.items.iter().any(|item| key_fn(item) == *key)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.items.iter()
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.items
+    }
+}
+
+impl<T> IntoIterator for BoundedVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BoundedVec<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_beyond_limit() {
+        let mut items = BoundedVec::new(2);
+        assert!(items.push(1).is_ok());
+        assert!(items.push(2).is_ok());
+        assert!(items.is_full());
+
+        let error = items.push(3).unwrap_err();
+        assert!(matches!(&error, ServiceError::BadRequest(message) if message == "limit exceeded"));
+        assert_eq!(items.len(), 2);
+        assert_eq!(items.into_vec(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_zero_max_len() {
+        let mut items = BoundedVec::new(0);
+        assert!(items.is_empty());
+        assert!(items.push("item").is_err());
+    }
+
+    #[test]
+    fn test_contains_key() {
+        let mut items = BoundedVec::new(3);
+        items.push(("sword", 1)).unwrap();
+        items.push(("shield", 2)).unwrap();
+
+        assert!(items.contains_key(&"shield", |(name, _)| *name));
+        assert!(!items.contains_key(&"bow", |(name, _)| *name));
+        assert_eq!(items.iter().map(|(_, count)| count).sum::<i32>(), 3);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::uuid::Uuid;
+use spacetimedb::{ReducerContext, Timestamp, table};
+use std::time::Duration;
+
+/// Cooldown table - last time a player performed a rate-limited action.
+///
+/// Only visible to the server. There is at most one row per `(player_id, action_key)`,
+/// enforced when the cooldown is updated since multi-column unique constraints aren't available.
+#[table(
+    name = stdb_cooldown_v1,
+    index(name = player_action_index, btree(columns = [player_id, action_key])),
+)]
+#[derive(Debug, Clone)]
+pub struct StdbCooldownV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub cooldown_id: u64,
+
+    pub player_id: Uuid,
+
+    pub action_key: String,
+
+    pub last_used_at: Timestamp,
+}
+
+pub(crate) fn find_cooldown(ctx: &ReducerContext, player_id: &Uuid, action_key: &str) -> Option<StdbCooldownV1> {
+    ctx.db
+        .stdb_cooldown_v1()
+        .player_action_index()
+        .filter((player_id, action_key))
+        .next()
+}
+
+/// Time left before the action can be performed again, `None` if the cooldown expired.
+pub fn cooldown_remaining(last_used_at: Timestamp, now: Timestamp, duration: Duration) -> Option<Duration> {
+    let elapsed = now.duration_since(last_used_at).unwrap_or_default();
+    duration.checked_sub(elapsed).filter(|remaining| !remaining.is_zero())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cooldown_remaining_active() {
+        let last_used_at = Timestamp::from_micros_since_unix_epoch(1_000_000);
+        let now = Timestamp::from_micros_since_unix_epoch(3_000_000);
+
+        assert_eq!(
+            cooldown_remaining(last_used_at, now, Duration::from_secs(5)),
+            Some(Duration::from_secs(3))
+        );
+    }
+
+    #[test]
+    fn test_cooldown_remaining_expired() {
+        let last_used_at = Timestamp::from_micros_since_unix_epoch(1_000_000);
+        let now = Timestamp::from_micros_since_unix_epoch(6_000_000);
+
+        assert_eq!(cooldown_remaining(last_used_at, now, Duration::from_secs(5)), None);
+        assert_eq!(cooldown_remaining(last_used_at, now, Duration::from_secs(4)), None);
+    }
+
+    #[test]
+    fn test_cooldown_remaining_clock_skew() {
+        // A last use in the future is treated as if it just happened.
+        let last_used_at = Timestamp::from_micros_since_unix_epoch(6_000_000);
+        let now = Timestamp::from_micros_since_unix_epoch(1_000_000);
+
+        assert_eq!(
+            cooldown_remaining(last_used_at, now, Duration::from_secs(5)),
+            Some(Duration::from_secs(5))
+        );
+    }
+}
@@ -19,6 +19,20 @@ pub trait DiceExt {
         self.random_d16() == 16
     }
 
+    /// 1/20 (5%) chance
+    fn random_d20(&self) -> u32;
+    fn is_critical_hit_d20(&self) -> bool {
+        self.random_d20() == 20
+    }
+    fn is_critical_miss_d20(&self) -> bool {
+        self.random_d20() == 1
+    }
+
+    /// Rolls a d20 and adds `modifier`, never below 1.
+    fn roll_d20_with_modifier(&self, modifier: i32) -> i32 {
+        apply_modifier(self.random_d20(), modifier)
+    }
+
     /// 1/32 (3.125%) chance
     fn random_d32(&self) -> u32;
     fn is_random_d32(&self) -> bool {
@@ -42,6 +56,29 @@ pub trait DiceExt {
     fn is_random_d16_384(&self) -> bool {
         self.random_d16_384() == 16_384
     }
+
+    /// 1/2 (50%) chance
+    fn random_bool(&self) -> bool;
+
+    /// Uniform value in `[0.0, 1.0)`.
+    fn random_f32(&self) -> f32;
+
+    /// Uniform value in `[0.0, 1.0)`.
+    fn random_f64(&self) -> f64;
+
+    /// Uniform value in `0..max`, `max` must be greater than 0.
+    fn random_below(&self, max: u32) -> u32;
+
+    /// Shuffles the items in place with Fisher-Yates, e.g. a deck of cards or a spawn order.
+    fn random_shuffle<T>(&self, items: &mut [T]);
+
+    /// Returns `count` distinct items in random order, or all of them shuffled if `count >= items.len()`.
+    fn random_sample<T: Clone>(&self, items: &[T], count: usize) -> Vec<T>;
+
+    /// Returns `min(count, slice.len())` elements at distinct indices in random order, e.g. a starter item set.
+    ///
+    /// Only the picked elements are cloned. Equal elements at different indices can all be picked.
+    fn random_unique_elements<T: Clone>(&self, slice: &[T], count: usize) -> Vec<T>;
 }
 
 impl DiceExt for ReducerContext {
@@ -57,6 +94,10 @@ impl DiceExt for ReducerContext {
         self.random::<u32>() % 16 + 1
     }
 
+    fn random_d20(&self) -> u32 {
+        d20_from(self.random::<u32>())
+    }
+
     fn random_d32(&self) -> u32 {
         self.random::<u32>() % 32 + 1
     }
@@ -72,4 +113,201 @@ impl DiceExt for ReducerContext {
     fn random_d16_384(&self) -> u32 {
         self.random::<u32>() % 16_384 + 1
     }
+
+    fn random_bool(&self) -> bool {
+        self.random::<u8>() < 128
+    }
+
+    fn random_f32(&self) -> f32 {
+        unit_f32(self.random::<u32>())
+    }
+
+    fn random_f64(&self) -> f64 {
+        unit_f64(self.random::<u64>())
+    }
+
+    fn random_below(&self, max: u32) -> u32 {
+        self.random::<u32>() % max
+    }
+
+    fn random_shuffle<T>(&self, items: &mut [T]) {
+        shuffle_with(items, || self.random::<usize>());
+    }
+
+    fn random_sample<T: Clone>(&self, items: &[T], count: usize) -> Vec<T> {
+        sample_with(items, count, || self.random::<usize>())
+    }
+
+    fn random_unique_elements<T: Clone>(&self, slice: &[T], count: usize) -> Vec<T> {
+        unique_elements_with(slice, count, || self.random::<usize>())
+    }
+}
+
+/// Keeps the 24 bits an `f32` can represent exactly, dividing by `u32::MAX` could round up to 1.0.
+pub(crate) fn unit_f32(random: u32) -> f32 {
+    (random >> 8) as f32 / (1u32 << 24) as f32
+}
+
+/// Keeps the 53 bits an `f64` can represent exactly.
+pub(crate) fn unit_f64(random: u64) -> f64 {
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+pub(crate) fn d20_from(random: u32) -> u32 {
+    random % 20 + 1
+}
+
+pub(crate) fn apply_modifier(roll: u32, modifier: i32) -> i32 {
+    (roll as i32).saturating_add(modifier).max(1)
+}
+
+pub(crate) fn shuffle_with<T>(items: &mut [T], mut random: impl FnMut() -> usize) {
+    for i in (1..items.len()).rev() {
+        items.swap(i, random() % (i + 1));
+    }
+}
+
+/// Same as [`unique_elements_with`], which only clones the picked elements.
+pub(crate) fn sample_with<T: Clone>(items: &[T], count: usize, random: impl FnMut() -> usize) -> Vec<T> {
+    unique_elements_with(items, count, random)
+}
+
+/// Partial Fisher-Yates on the indices of `slice`, so elements that aren't picked are never cloned.
+pub(crate) fn unique_elements_with<T: Clone>(slice: &[T], count: usize, mut random: impl FnMut() -> usize) -> Vec<T> {
+    let len = slice.len();
+    let count = count.min(len);
+    let mut indices: Vec<usize> = (0..len).collect();
+    for i in 0..count {
+        indices.swap(i, i + random() % (len - i));
+    }
+    indices[..count].iter().map(|&index| slice[index].clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xorshift(mut state: u64) -> impl FnMut() -> usize {
+        move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as usize
+        }
+    }
+
+    #[test]
+    fn test_unit_floats_in_range() {
+        assert_eq!(unit_f32(0), 0.0);
+        assert!(unit_f32(u32::MAX) < 1.0);
+        assert_eq!(unit_f64(0), 0.0);
+        assert!(unit_f64(u64::MAX) < 1.0);
+
+        let mut random = xorshift(0x2545_f491_4f6c_dd1d);
+        let samples: Vec<f64> = (0..10_000).map(|_| unit_f64(random() as u64)).collect();
+        assert!(samples.iter().all(|sample| (0.0..1.0).contains(sample)));
+
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        assert!((mean - 0.5).abs() < 0.02, "mean {}", mean);
+
+        let below_half = samples.iter().filter(|sample| **sample < 0.5).count();
+        assert!((4_700..=5_300).contains(&below_half), "{} below half", below_half);
+    }
+
+    #[test]
+    fn test_d20_in_range() {
+        let mut random = xorshift(0x2545_f491_4f6c_dd1d);
+        for _ in 0..100 {
+            let roll = d20_from(random() as u32);
+            assert!((1..=20).contains(&roll), "rolled {}", roll);
+        }
+        assert_eq!(d20_from(19), 20);
+        assert_eq!(d20_from(20), 1);
+    }
+
+    #[test]
+    fn test_apply_modifier() {
+        assert_eq!(apply_modifier(12, 3), 15);
+        assert_eq!(apply_modifier(2, -5), 1);
+        assert_eq!(apply_modifier(20, i32::MAX), i32::MAX);
+    }
+
+    #[test]
+    fn test_shuffle_keeps_every_item() {
+        let mut items: Vec<u32> = (0..52).collect();
+        shuffle_with(&mut items, xorshift(0x2545_f491_4f6c_dd1d));
+
+        assert_ne!(items, (0..52).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..52).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_shuffle_empty_and_single() {
+        let mut empty: Vec<u32> = vec![];
+        shuffle_with(&mut empty, xorshift(1));
+        assert!(empty.is_empty());
+
+        let mut single = vec![7];
+        shuffle_with(&mut single, xorshift(1));
+        assert_eq!(single, [7]);
+    }
+
+    #[test]
+    fn test_sample_distinct_items() {
+        let items: Vec<u32> = (0..20).collect();
+        let mut sample = sample_with(&items, 5, xorshift(0x2545_f491_4f6c_dd1d));
+        assert_eq!(sample.len(), 5);
+
+        sample.sort();
+        sample.dedup();
+        assert_eq!(sample.len(), 5);
+        assert!(sample.iter().all(|item| items.contains(item)));
+    }
+
+    #[test]
+    fn test_sample_count_above_len() {
+        let items = vec!["a", "b", "c"];
+        let mut sample = sample_with(&items, 10, xorshift(3));
+
+        sample.sort();
+        assert_eq!(sample, items);
+        assert!(sample_with(&items, 0, xorshift(3)).is_empty());
+    }
+
+    #[test]
+    fn test_unique_elements_distinct_indices() {
+        let items: Vec<u32> = (0..20).collect();
+        let mut picked = unique_elements_with(&items, 5, xorshift(0x2545_f491_4f6c_dd1d));
+        assert_eq!(picked.len(), 5);
+
+        picked.sort();
+        picked.dedup();
+        assert_eq!(picked.len(), 5);
+
+        // Duplicated values are distinct elements
+        let picked = unique_elements_with(&["a", "a", "b"], 3, xorshift(7));
+        assert_eq!(picked.iter().filter(|item| **item == "a").count(), 2);
+    }
+
+    #[test]
+    fn test_unique_elements_count_above_len() {
+        let items = vec!["a", "b", "c"];
+        let mut picked = unique_elements_with(&items, 10, xorshift(3));
+
+        picked.sort();
+        assert_eq!(picked, items);
+    }
+
+    #[test]
+    fn test_unique_elements_zero_count() {
+        assert!(unique_elements_with(&[1, 2, 3], 0, xorshift(3)).is_empty());
+        assert!(unique_elements_with::<u32>(&[], 3, xorshift(3)).is_empty());
+    }
+
+    #[test]
+    fn test_unique_elements_single() {
+        assert_eq!(unique_elements_with(&[7], 1, xorshift(3)), [7]);
+        assert_eq!(unique_elements_with(&[7], 5, xorshift(3)), [7]);
+    }
 }
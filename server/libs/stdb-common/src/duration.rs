@@ -5,12 +5,16 @@ const SECS_PER_MINUTE: u64 = 60;
 const MINS_PER_HOUR: u64 = 60;
 const HOURS_PER_DAY: u64 = 24;
 const DAYS_PER_WEEK: u64 = 7;
+const MICROS_PER_MINUTE: u64 = SECS_PER_MINUTE * 1_000_000;
 
 pub trait DurationExt {
     fn from_weeks_ext(weeks: u64) -> Self;
     fn from_days_ext(days: u64) -> Self;
     fn from_hours_ext(hours: u64) -> Self;
     fn from_mins_ext(minutes: u64) -> Self;
+
+    /// Formats as `"1w 2d 3h 4m 5s"`, skipping zero components except the seconds.
+    fn to_human_readable(&self) -> String;
 }
 
 impl DurationExt for Duration {
@@ -49,10 +53,54 @@ impl DurationExt for Duration {
 
         Duration::from_secs(mins * SECS_PER_MINUTE)
     }
+
+    fn to_human_readable(&self) -> String {
+        let (weeks, days, hours, minutes, seconds) = to_secs_parts(*self);
+        let parts = [(weeks, "w"), (days.into(), "d"), (hours.into(), "h"), (minutes.into(), "m")];
+
+        let mut formatted: Vec<String> = parts
+            .iter()
+            .filter(|(value, _)| *value > 0)
+            .map(|(value, unit)| format!("{}{}", value, unit))
+            .collect();
+        formatted.push(format!("{}s", seconds));
+        formatted.join(" ")
+    }
+}
+
+/// Splits a duration into `(weeks, days, hours, minutes, seconds)`, sub-second precision is dropped.
+pub fn to_secs_parts(duration: Duration) -> (u64, u32, u32, u32, u32) {
+    let secs = duration.as_secs();
+    (
+        secs / (SECS_PER_MINUTE * MINS_PER_HOUR * HOURS_PER_DAY * DAYS_PER_WEEK),
+        (secs / (SECS_PER_MINUTE * MINS_PER_HOUR * HOURS_PER_DAY) % DAYS_PER_WEEK) as u32,
+        (secs / (SECS_PER_MINUTE * MINS_PER_HOUR) % HOURS_PER_DAY) as u32,
+        (secs / SECS_PER_MINUTE % MINS_PER_HOUR) as u32,
+        (secs % SECS_PER_MINUTE) as u32,
+    )
 }
 
 pub trait TimestampExt {
     fn into_midnight(self) -> Self;
+
+    /// Truncates the seconds and microseconds.
+    fn into_nearest_minute(self) -> Self;
+
+    /// Truncates the minutes, seconds and microseconds.
+    fn into_nearest_hour(self) -> Self;
+
+    /// Rounds down to a multiple of `interval` minutes since the epoch, e.g. 15 for quarter hours.
+    /// An interval of 0 behaves like 1.
+    fn round_to_minutes(self, interval: u64) -> Self;
+
+    /// Whole days elapsed since `earlier`, 0 if `earlier` is in the future.
+    fn days_since(self, earlier: Timestamp) -> u64;
+
+    /// Whole hours elapsed since `earlier`, 0 if `earlier` is in the future.
+    fn hours_since(self, earlier: Timestamp) -> u64;
+
+    /// Whole minutes elapsed since `earlier`, 0 if `earlier` is in the future.
+    fn minutes_since(self, earlier: Timestamp) -> u64;
 }
 
 impl TimestampExt for Timestamp {
@@ -68,6 +116,45 @@ impl TimestampExt for Timestamp {
         let secs_since_epoch = secs_since_epoch - secs_today;
         Timestamp::from_micros_since_unix_epoch(secs_since_epoch * micros_per_sec)
     }
+
+    fn into_nearest_minute(self) -> Self {
+        self.round_to_minutes(1)
+    }
+
+    fn into_nearest_hour(self) -> Self {
+        self.round_to_minutes(MINS_PER_HOUR)
+    }
+
+    fn round_to_minutes(self, interval: u64) -> Self {
+        let micros_per_minute = MICROS_PER_MINUTE as i64;
+        let interval_micros = i64::try_from(interval.max(1))
+            .ok()
+            .and_then(|interval| interval.checked_mul(micros_per_minute))
+            .unwrap_or(i64::MAX);
+
+        let micros_since_epoch = self.to_micros_since_unix_epoch();
+        let micros = micros_since_epoch - micros_since_epoch.rem_euclid(interval_micros);
+        Timestamp::from_micros_since_unix_epoch(micros)
+    }
+
+    fn days_since(self, earlier: Timestamp) -> u64 {
+        micros_since(self, earlier) / (MICROS_PER_MINUTE * MINS_PER_HOUR * HOURS_PER_DAY)
+    }
+
+    fn hours_since(self, earlier: Timestamp) -> u64 {
+        micros_since(self, earlier) / (MICROS_PER_MINUTE * MINS_PER_HOUR)
+    }
+
+    fn minutes_since(self, earlier: Timestamp) -> u64 {
+        micros_since(self, earlier) / MICROS_PER_MINUTE
+    }
+}
+
+fn micros_since(later: Timestamp, earlier: Timestamp) -> u64 {
+    let micros = later
+        .to_micros_since_unix_epoch()
+        .saturating_sub(earlier.to_micros_since_unix_epoch());
+    micros.max(0) as u64
 }
 
 #[cfg(test)]
@@ -75,6 +162,42 @@ mod tests {
     use super::*;
     use spacetimedb::Timestamp;
 
+    #[test]
+    fn test_to_human_readable() {
+        assert_eq!(Duration::ZERO.to_human_readable(), "0s");
+        assert_eq!(Duration::from_secs(59).to_human_readable(), "59s");
+        assert_eq!(Duration::from_mins_ext(90).to_human_readable(), "1h 30m 0s");
+        assert_eq!(Duration::from_days_ext(8).to_human_readable(), "1w 1d 0s");
+
+        let duration = Duration::from_weeks_ext(1)
+            + Duration::from_days_ext(2)
+            + Duration::from_hours_ext(3)
+            + Duration::from_mins_ext(4)
+            + Duration::from_secs(5);
+        assert_eq!(duration.to_human_readable(), "1w 2d 3h 4m 5s");
+    }
+
+    #[test]
+    fn test_to_secs_parts() {
+        assert_eq!(to_secs_parts(Duration::ZERO), (0, 0, 0, 0, 0));
+        assert_eq!(to_secs_parts(Duration::from_millis(59_999)), (0, 0, 0, 0, 59));
+        assert_eq!(to_secs_parts(Duration::from_mins_ext(60)), (0, 0, 1, 0, 0));
+        assert_eq!(to_secs_parts(Duration::from_days_ext(1)), (0, 1, 0, 0, 0));
+        assert_eq!(to_secs_parts(Duration::from_weeks_ext(3)), (3, 0, 0, 0, 0));
+        assert_eq!(
+            to_secs_parts(Duration::from_weeks_ext(1) + Duration::from_days_ext(6) + Duration::from_hours_ext(23)),
+            (1, 6, 23, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_to_human_readable_round_durations() {
+        assert_eq!(Duration::from_mins_ext(1).to_human_readable(), "1m 0s");
+        assert_eq!(Duration::from_hours_ext(1).to_human_readable(), "1h 0s");
+        assert_eq!(Duration::from_days_ext(1).to_human_readable(), "1d 0s");
+        assert_eq!(Duration::from_weeks_ext(2).to_human_readable(), "2w 0s");
+    }
+
     #[test]
     fn test_into_midnight_basic() {
         // April 27, 2025 10:00:00 UTC (in micros)
@@ -127,4 +250,94 @@ mod tests {
 
         assert_eq!(current_timestamp.into_midnight(), expected_timestamp);
     }
+
+    #[test]
+    fn test_into_nearest_minute() {
+        // April 27, 2025 10:30:45.123456 UTC (in micros)
+        let current_timestamp = Timestamp::from_micros_since_unix_epoch(1745749845123456);
+
+        // Expected: April 27, 2025 10:30:00 UTC (in micros)
+        let expected_timestamp = Timestamp::from_micros_since_unix_epoch(1745749800000000);
+
+        assert_eq!(current_timestamp.into_nearest_minute(), expected_timestamp);
+        assert_eq!(expected_timestamp.into_nearest_minute(), expected_timestamp);
+    }
+
+    #[test]
+    fn test_into_nearest_hour() {
+        // April 27, 2025 10:30:45.123456 UTC (in micros)
+        let current_timestamp = Timestamp::from_micros_since_unix_epoch(1745749845123456);
+
+        // Expected: April 27, 2025 10:00:00 UTC (in micros)
+        let expected_timestamp = Timestamp::from_micros_since_unix_epoch(1745748000000000);
+
+        assert_eq!(current_timestamp.into_nearest_hour(), expected_timestamp);
+    }
+
+    #[test]
+    fn test_into_nearest_hour_at_midday() {
+        // April 27, 2025 12:00:00 UTC (in micros)
+        let midday = Timestamp::from_micros_since_unix_epoch(1745755200000000);
+
+        assert_eq!(midday.into_nearest_hour(), midday);
+        assert_eq!(midday.into_nearest_minute(), midday);
+    }
+
+    #[test]
+    fn test_into_nearest_hour_just_before_midnight() {
+        // April 27, 2025 23:59:59.999999 UTC (in micros)
+        let current_timestamp = Timestamp::from_micros_since_unix_epoch(1745798399999999);
+
+        // Expected: April 27, 2025 23:00:00 UTC (in micros)
+        let expected_timestamp = Timestamp::from_micros_since_unix_epoch(1745794800000000);
+
+        assert_eq!(current_timestamp.into_nearest_hour(), expected_timestamp);
+        assert_eq!(
+            current_timestamp.into_nearest_minute(),
+            Timestamp::from_micros_since_unix_epoch(1745798340000000)
+        );
+    }
+
+    #[test]
+    fn test_round_to_minutes() {
+        // April 27, 2025 10:44:59 UTC (in micros)
+        let current_timestamp = Timestamp::from_micros_since_unix_epoch(1745750699000000);
+
+        // Expected: April 27, 2025 10:30:00 UTC (in micros)
+        let expected_timestamp = Timestamp::from_micros_since_unix_epoch(1745749800000000);
+
+        assert_eq!(current_timestamp.round_to_minutes(15), expected_timestamp);
+        assert_eq!(current_timestamp.round_to_minutes(0), current_timestamp.into_nearest_minute());
+        assert_eq!(current_timestamp.round_to_minutes(24 * 60), current_timestamp.into_midnight());
+    }
+
+    #[test]
+    fn test_days_since() {
+        // April 27, 2025 00:00:00 UTC (in micros)
+        let midnight = Timestamp::from_micros_since_unix_epoch(1745712000000000);
+        let just_before_next_midnight = Timestamp::from_micros_since_unix_epoch(1745798399999999);
+        let next_midnight = Timestamp::from_micros_since_unix_epoch(1745798400000000);
+
+        assert_eq!(midnight.days_since(midnight), 0);
+        assert_eq!(just_before_next_midnight.days_since(midnight), 0);
+        assert_eq!(next_midnight.days_since(midnight), 1);
+        assert_eq!(midnight.days_since(next_midnight), 0);
+    }
+
+    #[test]
+    fn test_hours_and_minutes_since() {
+        // April 27, 2025 10:00:00 UTC (in micros)
+        let ten = Timestamp::from_micros_since_unix_epoch(1745748000000000);
+        // April 27, 2025 10:59:59.999999 UTC (in micros)
+        let almost_eleven = Timestamp::from_micros_since_unix_epoch(1745751599999999);
+        // April 27, 2025 11:00:00 UTC (in micros)
+        let eleven = Timestamp::from_micros_since_unix_epoch(1745751600000000);
+
+        assert_eq!(almost_eleven.hours_since(ten), 0);
+        assert_eq!(eleven.hours_since(ten), 1);
+        assert_eq!(almost_eleven.minutes_since(ten), 59);
+        assert_eq!(eleven.minutes_since(ten), 60);
+        assert_eq!(ten.hours_since(eleven), 0);
+        assert_eq!(ten.minutes_since(eleven), 0);
+    }
 }
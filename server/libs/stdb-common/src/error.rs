@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::{error::Error as StdError, fmt::Display};
 use thiserror::Error;
 
@@ -7,47 +8,227 @@ pub type ServiceResult<T> = Result<T, ServiceError>;
 pub enum ServiceError {
     /// 400 Bad Request - Client sent an invalid request
     #[error("E400: {0}")]
-    BadRequest(String),
+    BadRequest(ErrorMessage),
 
     /// 401 Unauthorized - Client needs to authenticate
     #[error("E401: {0}")]
-    Unauthorized(String),
+    Unauthorized(ErrorMessage),
 
     /// 403 Forbidden - Client doesn't have access rights
     #[error("E403: {0}")]
-    Forbidden(String),
+    Forbidden(ErrorMessage),
 
     /// 404 Not Found - Resource not found
     #[error("E404: {0}")]
-    NotFound(String),
+    NotFound(ErrorMessage),
 
     /// 409 Conflict - Request couldn't be completed due to conflict
     #[error("E409: {0}")]
-    Conflict(String),
+    Conflict(ErrorMessage),
 
     /// 418 I'm a teapot - Used for validation errors
     #[error("E418: {0}")]
-    Validation(String),
+    Validation(ErrorMessage),
 
     /// 429 Too Many Requests - Rate limiting
     #[error("E429: {0}")]
-    RateLimited(String),
+    RateLimited(ErrorMessage),
 
     /// 500 Internal Server Error - Server encountered an unexpected condition
     #[error("E500: {0}")]
-    Internal(String),
+    Internal(ErrorMessage),
+}
+
+/// Message of a [`ServiceError`], keeping the contexts added by [`ServiceError::chain`] apart from the
+/// original message so they can be listed with [`ServiceError::unwrap_chain`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorMessage {
+    /// Contexts outermost first, ending with the original message.
+    segments: Vec<String>,
+    /// Segments joined with `": "`.
+    text: String,
+}
+
+impl ErrorMessage {
+    /// Message with a single context, e.g. from the `map_*_ctx` mappers.
+    pub fn with_context(context: impl Display, message: impl Into<String>) -> Self {
+        Self::from(message.into()).chain(context)
+    }
+
+    /// Full message, contexts included.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    fn chain(mut self, context: impl Display) -> Self {
+        let context = context.to_string();
+        self.text = format!("{}: {}", context, self.text);
+        self.segments.insert(0, context);
+        self
+    }
+}
+
+impl From<String> for ErrorMessage {
+    fn from(message: String) -> Self {
+        Self {
+            segments: vec![message.clone()],
+            text: message,
+        }
+    }
+}
+
+impl From<&str> for ErrorMessage {
+    fn from(message: &str) -> Self {
+        Self::from(message.to_string())
+    }
+}
+
+impl Display for ErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl PartialEq<str> for ErrorMessage {
+    fn eq(&self, other: &str) -> bool {
+        self.text == other
+    }
 }
 
 impl ServiceError {
     pub fn unauthorized() -> Self {
-        ServiceError::Unauthorized("Unauthorized".to_string())
+        ServiceError::Unauthorized("Unauthorized".into())
     }
 
     pub fn internal(message: impl Into<String>) -> Self {
-        ServiceError::Internal(message.into())
+        ServiceError::Internal(message.into().into())
+    }
+
+    /// Maps an HTTP status code, e.g. from an external platform API, to the matching variant.
+    ///
+    /// Codes without a matching variant map to `Internal` with the code kept in the message.
+    pub fn from_http_code(code: u16, message: impl Into<String>) -> Self {
+        let message = ErrorMessage::from(message.into());
+        match code {
+            400 => ServiceError::BadRequest(message),
+            401 => ServiceError::Unauthorized(message),
+            403 => ServiceError::Forbidden(message),
+            404 => ServiceError::NotFound(message),
+            409 => ServiceError::Conflict(message),
+            418 => ServiceError::Validation(message),
+            429 => ServiceError::RateLimited(message),
+            500 => ServiceError::Internal(message),
+            _ => ServiceError::Internal(format!("HTTP {}: {}", code, message).into()),
+        }
+    }
+
+    /// HTTP status code of the variant, e.g. 404 for `NotFound`.
+    pub fn code(&self) -> u16 {
+        match self {
+            ServiceError::BadRequest(_) => 400,
+            ServiceError::Unauthorized(_) => 401,
+            ServiceError::Forbidden(_) => 403,
+            ServiceError::NotFound(_) => 404,
+            ServiceError::Conflict(_) => 409,
+            ServiceError::Validation(_) => 418,
+            ServiceError::RateLimited(_) => 429,
+            ServiceError::Internal(_) => 500,
+        }
+    }
+
+    /// Name of the variant, e.g. `"NotFound"`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ServiceError::BadRequest(_) => "BadRequest",
+            ServiceError::Unauthorized(_) => "Unauthorized",
+            ServiceError::Forbidden(_) => "Forbidden",
+            ServiceError::NotFound(_) => "NotFound",
+            ServiceError::Conflict(_) => "Conflict",
+            ServiceError::Validation(_) => "Validation",
+            ServiceError::RateLimited(_) => "RateLimited",
+            ServiceError::Internal(_) => "Internal",
+        }
+    }
+
+    /// Serializes the error as an [`ErrorResponse`] JSON object.
+    pub fn into_json_string(self) -> String {
+        let response = ErrorResponse::from(self);
+        serde_json::to_string(&response).unwrap_or_else(|_| format!("E{}: {}", response.code, response.message))
+    }
+
+    /// Message of the error, without the `E4xx`/`E5xx` code.
+    pub fn message(&self) -> &str {
+        self.error_message().as_str()
+    }
+
+    fn error_message(&self) -> &ErrorMessage {
+        match self {
+            ServiceError::BadRequest(message)
+            | ServiceError::Unauthorized(message)
+            | ServiceError::Forbidden(message)
+            | ServiceError::NotFound(message)
+            | ServiceError::Conflict(message)
+            | ServiceError::Validation(message)
+            | ServiceError::RateLimited(message)
+            | ServiceError::Internal(message) => message,
+        }
+    }
+
+    /// Prepends `context` to the message of `source` as `"context: message"`, keeping its variant.
+    pub fn chain(source: ServiceError, context: impl Display) -> ServiceError {
+        let chained = |message: ErrorMessage| message.chain(context);
+        match source {
+            ServiceError::BadRequest(message) => ServiceError::BadRequest(chained(message)),
+            ServiceError::Unauthorized(message) => ServiceError::Unauthorized(chained(message)),
+            ServiceError::Forbidden(message) => ServiceError::Forbidden(chained(message)),
+            ServiceError::NotFound(message) => ServiceError::NotFound(chained(message)),
+            ServiceError::Conflict(message) => ServiceError::Conflict(chained(message)),
+            ServiceError::Validation(message) => ServiceError::Validation(chained(message)),
+            ServiceError::RateLimited(message) => ServiceError::RateLimited(chained(message)),
+            ServiceError::Internal(message) => ServiceError::Internal(chained(message)),
+        }
+    }
+
+    /// Contexts added with [`ServiceError::chain`] or the `map_*_ctx` mappers, outermost first,
+    /// ending with the original message.
+    pub fn unwrap_chain(&self) -> Vec<&str> {
+        self.error_message().segments.iter().map(String::as_str).collect()
+    }
+}
+
+/// Structured form of a [`ServiceError`] for game clients, e.g.
+/// `{"code":404,"kind":"NotFound","message":"Player 'x' not found"}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub code: u16,
+    pub kind: String,
+    pub message: String,
+}
+
+impl From<ServiceError> for ErrorResponse {
+    fn from(error: ServiceError) -> Self {
+        Self {
+            code: error.code(),
+            kind: error.kind().to_string(),
+            message: error.message().to_string(),
+        }
     }
 }
 
+impl From<ErrorResponse> for ServiceError {
+    fn from(response: ErrorResponse) -> Self {
+        ServiceError::from_http_code(response.code, response.message)
+    }
+}
+
+/// Formats a reducer error for game clients as JSON instead of the `"E400: ..."` display string.
+///
+/// Reducers return `ServiceResult` directly, so games opt in at their own reducer boundary,
+/// e.g. `result.map_err(format_error_for_client)`.
+pub fn format_error_for_client(error: ServiceError) -> String {
+    error.into_json_string()
+}
+
 /// Trait to provide a fluent API for mapping domain-specific errors to ServiceError
 pub trait ErrorMapper {
     /// Maps the error to ServiceError::BadRequest
@@ -88,67 +269,67 @@ where
     E: StdError + Send + Sync + 'static,
 {
     fn map_bad_request(self) -> ServiceError {
-        ServiceError::BadRequest(self.to_string())
+        ServiceError::BadRequest(self.to_string().into())
     }
 
     fn map_bad_request_ctx(self, error_ctx: impl Display) -> ServiceError {
-        ServiceError::BadRequest(format!("{}: {}", error_ctx, self))
+        ServiceError::BadRequest(ErrorMessage::with_context(error_ctx, self.to_string()))
     }
 
     fn map_unauthorized(self) -> ServiceError {
-        ServiceError::Unauthorized(self.to_string())
+        ServiceError::Unauthorized(self.to_string().into())
     }
 
     fn map_unauthorized_ctx(self, error_ctx: impl Display) -> ServiceError {
-        ServiceError::Unauthorized(format!("{}: {}", error_ctx, self))
+        ServiceError::Unauthorized(ErrorMessage::with_context(error_ctx, self.to_string()))
     }
 
     fn map_forbidden(self) -> ServiceError {
-        ServiceError::Forbidden(self.to_string())
+        ServiceError::Forbidden(self.to_string().into())
     }
 
     fn map_forbidden_ctx(self, error_ctx: impl Display) -> ServiceError {
-        ServiceError::Forbidden(format!("{}: {}", error_ctx, self))
+        ServiceError::Forbidden(ErrorMessage::with_context(error_ctx, self.to_string()))
     }
 
     fn map_not_found(self) -> ServiceError {
-        ServiceError::NotFound(self.to_string())
+        ServiceError::NotFound(self.to_string().into())
     }
 
     fn map_not_found_ctx(self, error_ctx: impl Display) -> ServiceError {
-        ServiceError::NotFound(format!("{}: {}", error_ctx, self))
+        ServiceError::NotFound(ErrorMessage::with_context(error_ctx, self.to_string()))
     }
 
     fn map_conflict(self) -> ServiceError {
-        ServiceError::Conflict(self.to_string())
+        ServiceError::Conflict(self.to_string().into())
     }
 
     fn map_conflict_ctx(self, error_ctx: impl Display) -> ServiceError {
-        ServiceError::Conflict(format!("{}: {}", error_ctx, self))
+        ServiceError::Conflict(ErrorMessage::with_context(error_ctx, self.to_string()))
     }
 
     fn map_validation(self) -> ServiceError {
-        ServiceError::Validation(self.to_string())
+        ServiceError::Validation(self.to_string().into())
     }
 
     fn map_validation_ctx(self, error_ctx: impl Display) -> ServiceError {
-        ServiceError::Validation(format!("{}: {}", error_ctx, self))
+        ServiceError::Validation(ErrorMessage::with_context(error_ctx, self.to_string()))
     }
 
     fn map_rate_limited(self) -> ServiceError {
-        ServiceError::RateLimited(self.to_string())
+        ServiceError::RateLimited(self.to_string().into())
     }
 
     fn map_rate_limited_ctx(self, error_ctx: impl Display) -> ServiceError {
-        ServiceError::RateLimited(format!("{}: {}", error_ctx, self))
+        ServiceError::RateLimited(ErrorMessage::with_context(error_ctx, self.to_string()))
     }
 
     fn map_internal(self) -> ServiceError {
-        ServiceError::Internal(self.to_string())
+        ServiceError::Internal(self.to_string().into())
     }
 
     fn map_internal_ctx(self, error_ctx: impl Display) -> ServiceError {
-        ServiceError::Internal(format!("{}: {}", error_ctx, self))
+        ServiceError::Internal(ErrorMessage::with_context(error_ctx, self.to_string()))
     }
 }
 
@@ -185,6 +366,18 @@ pub trait ResultExt<T, E: StdError + Send + Sync + 'static> {
     /// Maps an error to ServiceError::Internal
     fn map_internal(self) -> ServiceResult<T>;
     fn map_internal_ctx(self, error_ctx: impl Display) -> ServiceResult<T>;
+
+    /// Runs `validator` on the successful value, returning the value only if it passes.
+    fn and_validate<F>(self, validator: F) -> ServiceResult<T>
+    where
+        F: FnOnce(&T) -> ServiceResult<()>,
+        E: Into<ServiceError>;
+
+    /// Chains another fallible operation on the successful value.
+    fn and_then_service<U, F>(self, f: F) -> ServiceResult<U>
+    where
+        F: FnOnce(T) -> ServiceResult<U>,
+        E: Into<ServiceError>;
 }
 
 impl<T, E> ResultExt<T, E> for Result<T, E>
@@ -254,4 +447,168 @@ where
     fn map_internal_ctx(self, error_ctx: impl Display) -> ServiceResult<T> {
         self.map_err(|e| e.map_internal_ctx(error_ctx))
     }
+
+    fn and_validate<F>(self, validator: F) -> ServiceResult<T>
+    where
+        F: FnOnce(&T) -> ServiceResult<()>,
+        E: Into<ServiceError>,
+    {
+        let value = self.map_err(Into::into)?;
+        validator(&value)?;
+        Ok(value)
+    }
+
+    fn and_then_service<U, F>(self, f: F) -> ServiceResult<U>
+    where
+        F: FnOnce(T) -> ServiceResult<U>,
+        E: Into<ServiceError>,
+    {
+        self.map_err(Into::into).and_then(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_response_json_round_trip() {
+        let errors = [
+            ServiceError::BadRequest("bad".into()),
+            ServiceError::unauthorized(),
+            ServiceError::Forbidden("forbidden".into()),
+            ServiceError::NotFound("Player 'x' not found".into()),
+            ServiceError::Conflict("conflict".into()),
+            ServiceError::Validation("Field 'name' is required".into()),
+            ServiceError::RateLimited("slow down".into()),
+            ServiceError::internal("boom"),
+        ];
+
+        for error in errors {
+            let expected = error.to_string();
+            let json = format_error_for_client(error);
+            let response: ErrorResponse = serde_json::from_str(&json).unwrap();
+            assert_eq!(ServiceError::from(response).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_error_response_json_shape() {
+        let json = ServiceError::NotFound("Player 'x' not found".into()).into_json_string();
+        assert_eq!(json, r#"{"code":404,"kind":"NotFound","message":"Player 'x' not found"}"#);
+    }
+
+    #[test]
+    fn test_chain_keeps_variant() {
+        let source = ServiceError::Validation("Field 'receiver_id' must be a valid UUID".into());
+        let error = ServiceError::chain(ServiceError::chain(source, "failed to insert VIP"), "insert_vip_v1");
+
+        assert!(matches!(error, ServiceError::Validation(_)));
+        assert_eq!(
+            error.to_string(),
+            "E418: insert_vip_v1: failed to insert VIP: Field 'receiver_id' must be a valid UUID"
+        );
+        assert_eq!(
+            error.unwrap_chain(),
+            [
+                "insert_vip_v1",
+                "failed to insert VIP",
+                "Field 'receiver_id' must be a valid UUID"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_http_code() {
+        let cases = [
+            (400, "E400: message"),
+            (401, "E401: message"),
+            (403, "E403: message"),
+            (404, "E404: message"),
+            (409, "E409: message"),
+            (418, "E418: message"),
+            (429, "E429: message"),
+            (500, "E500: message"),
+        ];
+        for (code, expected) in cases {
+            assert_eq!(ServiceError::from_http_code(code, "message").to_string(), expected);
+        }
+
+        assert!(matches!(ServiceError::from_http_code(400, ""), ServiceError::BadRequest(_)));
+        assert!(matches!(ServiceError::from_http_code(401, ""), ServiceError::Unauthorized(_)));
+        assert!(matches!(ServiceError::from_http_code(403, ""), ServiceError::Forbidden(_)));
+        assert!(matches!(ServiceError::from_http_code(404, ""), ServiceError::NotFound(_)));
+        assert!(matches!(ServiceError::from_http_code(409, ""), ServiceError::Conflict(_)));
+        assert!(matches!(ServiceError::from_http_code(418, ""), ServiceError::Validation(_)));
+        assert!(matches!(ServiceError::from_http_code(429, ""), ServiceError::RateLimited(_)));
+        assert!(matches!(ServiceError::from_http_code(500, ""), ServiceError::Internal(_)));
+    }
+
+    #[test]
+    fn test_from_http_code_unknown() {
+        for code in [0, 200, 402, 502, 503, u16::MAX] {
+            let error = ServiceError::from_http_code(code, "message");
+            assert!(matches!(error, ServiceError::Internal(_)));
+            assert_eq!(error.message(), format!("HTTP {}: message", code));
+        }
+    }
+
+    fn validate_even(value: &u32) -> ServiceResult<()> {
+        if value.is_multiple_of(2) {
+            Ok(())
+        } else {
+            Err(ServiceError::Validation("odd".into()))
+        }
+    }
+
+    #[test]
+    fn test_and_validate() {
+        assert_eq!(ServiceResult::Ok(2).and_validate(validate_even).unwrap(), 2);
+        assert!(matches!(
+            ServiceResult::Ok(3).and_validate(validate_even),
+            Err(ServiceError::Validation(_))
+        ));
+        assert!(matches!(
+            ServiceResult::<u32>::Err(ServiceError::unauthorized()).and_validate(validate_even),
+            Err(ServiceError::Unauthorized(_))
+        ));
+    }
+
+    #[test]
+    fn test_and_then_service() {
+        let doubled = ServiceResult::Ok(2).and_then_service(|value| Ok(value * 2));
+        assert_eq!(doubled.unwrap(), 4);
+
+        let failed: ServiceResult<u32> = ServiceResult::Ok(2).and_then_service(|_| Err(ServiceError::internal("boom")));
+        assert!(matches!(failed, Err(ServiceError::Internal(_))));
+    }
+
+    #[test]
+    fn test_unwrap_chain_keeps_colons_in_messages() {
+        let source = ServiceError::Forbidden("Account suspended: cheating: speed hacks".into());
+        let error = ServiceError::chain(source, "failed to sign in: banned");
+
+        assert_eq!(
+            error.message(),
+            "failed to sign in: banned: Account suspended: cheating: speed hacks"
+        );
+        assert_eq!(
+            error.unwrap_chain(),
+            ["failed to sign in: banned", "Account suspended: cheating: speed hacks"]
+        );
+    }
+
+    #[test]
+    fn test_unwrap_chain_with_mapper_context() {
+        let error = std::fmt::Error.map_conflict_ctx("failed to insert player");
+        assert_eq!(
+            error.unwrap_chain(),
+            ["failed to insert player", "an error occurred when formatting an argument"]
+        );
+    }
+
+    #[test]
+    fn test_unwrap_chain_without_context() {
+        assert_eq!(ServiceError::unauthorized().unwrap_chain(), ["Unauthorized"]);
+    }
 }
@@ -1,10 +1,37 @@
+pub mod collections;
+pub mod cooldown;
 pub mod dice;
 pub mod duration;
+pub mod loot;
+pub mod matchmaking;
+pub mod math;
+pub mod pagination;
+pub mod probability;
+pub mod progression;
+pub mod rate_limiter;
+pub mod retry;
+pub mod uuid;
 
 pub(crate) mod error;
-pub(crate) mod uuid;
 pub(crate) mod validate;
 
 pub mod prelude {
-    pub use crate::{error::*, uuid::*, validate::*};
+    pub use crate::{
+        collections::BoundedVec,
+        duration::{DurationExt, TimestampExt, to_secs_parts},
+        error::*,
+        loot::*,
+        matchmaking::*,
+        math::*,
+        pagination::*,
+        probability::*,
+        progression::*,
+        rate_limiter::*,
+        retry::*,
+        uuid::{
+            TypedUuid, Uuid, UuidExt, deserialize_uuid, parse_uuid, parse_uuid_or_error, parse_uuid_or_nil, serialize_uuid,
+            uuid_is_max, uuid_is_nil, uuid_v7_timestamp,
+        },
+        validate::*,
+    };
 }
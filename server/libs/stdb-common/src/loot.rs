@@ -0,0 +1,97 @@
+use crate::{dice::DiceExt, error::ServiceResult, validate::ValidationError};
+use spacetimedb::ReducerContext;
+
+/// An item of a [`LootTable`] and its relative chance of being rolled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LootEntry<T> {
+    pub item: T,
+
+    /// Entries with weight 0 are never rolled.
+    pub weight: u32,
+}
+
+/// Weighted table of random drops, the chance of each entry is `weight / total_weight`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LootTable<T> {
+    entries: Vec<LootEntry<T>>,
+    total_weight: u32,
+}
+
+impl<T> LootTable<T> {
+    /// Creates a loot table from its entries.
+    ///
+    /// # Errors
+    /// Returns error if the sum of all weights overflows `u32::MAX`.
+    pub fn new(entries: Vec<LootEntry<T>>) -> ServiceResult<Self> {
+        let total_weight = entries
+            .iter()
+            .try_fold(0u32, |total, entry| total.checked_add(entry.weight))
+            .ok_or_else(|| ValidationError::field_too_large("weights", u32::MAX))?;
+        Ok(Self { entries, total_weight })
+    }
+
+    pub fn entries(&self) -> &[LootEntry<T>] {
+        &self.entries
+    }
+
+    pub fn total_weight(&self) -> u32 {
+        self.total_weight
+    }
+
+    /// Rolls a single drop, `None` when the table is empty or all weights are 0.
+    pub fn roll(&self, ctx: &ReducerContext) -> Option<&T> {
+        if self.total_weight == 0 {
+            return None;
+        }
+        self.pick(ctx.random_below(self.total_weight))
+    }
+
+    /// Rolls `count` independent drops, empty when the table is empty or all weights are 0.
+    pub fn roll_n(&self, ctx: &ReducerContext, count: u32) -> Vec<&T> {
+        (0..count).filter_map(|_| self.roll(ctx)).collect()
+    }
+
+    /// Item covering `roll` once the weights are laid end to end, `roll` must be below the total weight.
+    pub(crate) fn pick(&self, mut roll: u32) -> Option<&T> {
+        for entry in &self.entries {
+            if roll < entry.weight {
+                return Some(&entry.item);
+            }
+            roll -= entry.weight;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(item: &'static str, weight: u32) -> LootEntry<&'static str> {
+        LootEntry { item, weight }
+    }
+
+    #[test]
+    fn test_loot_table_pick_by_weight() {
+        let table = LootTable::new(vec![entry("common", 7), entry("never", 0), entry("rare", 3)]).unwrap();
+        assert_eq!(table.total_weight(), 10);
+        assert_eq!(table.pick(0), Some(&"common"));
+        assert_eq!(table.pick(6), Some(&"common"));
+        assert_eq!(table.pick(7), Some(&"rare"));
+        assert_eq!(table.pick(9), Some(&"rare"));
+        assert_eq!(table.pick(10), None);
+    }
+
+    #[test]
+    fn test_loot_table_empty() {
+        let table = LootTable::<&str>::new(vec![]).unwrap();
+        assert_eq!(table.total_weight(), 0);
+        assert_eq!(table.pick(0), None);
+    }
+
+    #[test]
+    fn test_loot_table_weight_overflow() {
+        assert!(LootTable::new(vec![entry("a", u32::MAX), entry("b", 0)]).is_ok());
+        assert!(LootTable::new(vec![entry("a", u32::MAX), entry("b", 1)]).is_err());
+    }
+}
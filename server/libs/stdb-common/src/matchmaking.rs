@@ -0,0 +1,45 @@
+/// Rating difference at which the higher rated player is 10 times more likely to win.
+pub const ELO_RATING_SCALE: f64 = 400.0;
+
+/// Probability of `rating_a` beating `rating_b`: `1 / (1 + 10 ^ ((rating_b - rating_a) / 400))`.
+pub fn elo_expected_score(rating_a: i32, rating_b: i32) -> f64 {
+    let diff = rating_b as f64 - rating_a as f64;
+    1.0 / (1.0 + 10f64.powf(diff / ELO_RATING_SCALE))
+}
+
+/// Updated `(winner, loser)` ratings after a match.
+///
+/// Both players move by the same rounded amount, `k_factor * (1 - expected winner score)`,
+/// so the total rating is preserved unless a rating saturates at the `i32` bounds.
+pub fn elo_update(winner_rating: i32, loser_rating: i32, k_factor: f64) -> (i32, i32) {
+    let delta = (k_factor * (1.0 - elo_expected_score(winner_rating, loser_rating))).round() as i32;
+    (winner_rating.saturating_add(delta), loser_rating.saturating_sub(delta))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elo_expected_score() {
+        assert_eq!(elo_expected_score(1500, 1500), 0.5);
+        assert!((elo_expected_score(1600, 1400) - 0.7597).abs() < 0.0001);
+        assert!((elo_expected_score(1400, 1600) - 0.2403).abs() < 0.0001);
+        assert!((elo_expected_score(2000, 1600) - 10.0 / 11.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_elo_update() {
+        assert_eq!(elo_update(1500, 1500, 32.0), (1516, 1484));
+        assert_eq!(elo_update(1600, 1400, 32.0), (1608, 1392));
+        assert_eq!(elo_update(1400, 1600, 32.0), (1424, 1576));
+        assert_eq!(elo_update(1500, 1500, 0.0), (1500, 1500));
+    }
+
+    #[test]
+    fn test_elo_update_max_rating_difference() {
+        assert_eq!(elo_expected_score(i32::MAX, i32::MIN), 1.0);
+        assert_eq!(elo_update(i32::MAX, i32::MIN, 32.0), (i32::MAX, i32::MIN));
+        assert_eq!(elo_update(i32::MIN, i32::MAX, 32.0), (i32::MIN + 32, i32::MAX - 32));
+    }
+}
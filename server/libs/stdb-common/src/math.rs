@@ -0,0 +1,77 @@
+/// Restricts `value` to `min..=max`. Works with floats, unlike `Ord::clamp`.
+pub fn clamp<T: PartialOrd>(value: T, min: T, max: T) -> T {
+    if value < min {
+        min
+    } else if value > max {
+        max
+    } else {
+        value
+    }
+}
+
+/// Linear interpolation from `a` to `b`, `t` is clamped to `0..=1`.
+pub fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    let t = clamp(t, 0.0, 1.0);
+    a + (b - a) * t
+}
+
+/// Position of `value` between `min` and `max`, from 0.0 to 1.0.
+///
+/// Values outside the range are clamped, and an empty range returns 0.0.
+pub fn normalize(value: f64, min: f64, max: f64) -> f64 {
+    if max == min {
+        return 0.0;
+    }
+
+    clamp((value - min) / (max - min), 0.0, 1.0)
+}
+
+/// Maps `value` from the `from` range onto the `to` range, clamped to `to`, e.g. a level into a reward multiplier.
+pub fn map_range(value: f64, from: (f64, f64), to: (f64, f64)) -> f64 {
+    lerp(to.0, to.1, normalize(value, from.0, from.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp() {
+        assert_eq!(clamp(5, 0, 10), 5);
+        assert_eq!(clamp(-1, 0, 10), 0);
+        assert_eq!(clamp(11, 0, 10), 10);
+        assert_eq!(clamp(0, 0, 10), 0);
+        assert_eq!(clamp(10, 0, 10), 10);
+        assert_eq!(clamp(-7.5, -5.0, -1.0), -5.0);
+    }
+
+    #[test]
+    fn test_lerp() {
+        assert_eq!(lerp(10.0, 20.0, 0.0), 10.0);
+        assert_eq!(lerp(10.0, 20.0, 0.5), 15.0);
+        assert_eq!(lerp(10.0, 20.0, 1.0), 20.0);
+        assert_eq!(lerp(10.0, 20.0, 2.0), 20.0);
+        assert_eq!(lerp(10.0, 20.0, -1.0), 10.0);
+        assert_eq!(lerp(-10.0, -20.0, 0.5), -15.0);
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(0.0, 0.0, 10.0), 0.0);
+        assert_eq!(normalize(5.0, 0.0, 10.0), 0.5);
+        assert_eq!(normalize(10.0, 0.0, 10.0), 1.0);
+        assert_eq!(normalize(15.0, 0.0, 10.0), 1.0);
+        assert_eq!(normalize(-5.0, -10.0, 0.0), 0.5);
+        assert_eq!(normalize(3.0, 3.0, 3.0), 0.0);
+    }
+
+    #[test]
+    fn test_map_range() {
+        assert_eq!(map_range(50.0, (0.0, 100.0), (1.0, 2.0)), 1.5);
+        assert_eq!(map_range(-50.0, (-100.0, 0.0), (0.0, 10.0)), 5.0);
+        assert_eq!(map_range(200.0, (0.0, 100.0), (1.0, 2.0)), 2.0);
+
+        // Reversed target range
+        assert_eq!(map_range(25.0, (0.0, 100.0), (100.0, 0.0)), 75.0);
+    }
+}
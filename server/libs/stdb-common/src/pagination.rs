@@ -0,0 +1,167 @@
+use crate::{
+    error::ServiceResult,
+    validate::{ValidationError, validate_u32},
+};
+use spacetimedb::SpacetimeType;
+use std::cmp::Reverse;
+
+/// Maximum number of items a single page can hold.
+pub const MAX_PAGE_LIMIT: u32 = 100;
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, SpacetimeType)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// Stable sort of `items` by `key_fn` in the given order. Items with the same key keep their relative order.
+pub fn sort_vec_by<T, K: Ord>(items: &mut [T], key_fn: impl Fn(&T) -> K, order: SortOrder) {
+    match order {
+        SortOrder::Ascending => items.sort_by_key(|item| key_fn(item)),
+        SortOrder::Descending => items.sort_by_key(|item| Reverse(key_fn(item))),
+    }
+}
+
+/// A page of items and the cursor to fetch the next one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+
+    /// `None` when this is the last page.
+    pub next_cursor: Option<String>,
+
+    /// Total number of items across all pages, when cheap to compute.
+    pub total_count: Option<u32>,
+}
+
+/// Requests a page of at most `limit` items, starting at `cursor`.
+///
+/// The cursor is opaque to clients, they only pass back the `next_cursor` of the previous page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRequest {
+    pub cursor: Option<String>,
+    pub limit: u32,
+}
+
+impl PageRequest {
+    /// Requests the first page.
+    pub fn first(limit: u32) -> Self {
+        Self { cursor: None, limit }
+    }
+
+    /// Validates limit (1-100) and the cursor format.
+    pub fn validate(&self) -> ServiceResult<()> {
+        validate_u32("limit", self.limit, 1, MAX_PAGE_LIMIT)?;
+        self.offset().map(|_| ())
+    }
+
+    fn offset(&self) -> ServiceResult<usize> {
+        match &self.cursor {
+            None => Ok(0),
+            Some(cursor) => cursor.parse().map_err(|_| ValidationError::invalid_cursor("cursor")),
+        }
+    }
+}
+
+/// Sorts `items` by `key_fn` and returns the requested page.
+///
+/// The sort is stable, so items with the same key keep their relative order between pages.
+///
+/// # Errors
+/// Returns error if the request is invalid.
+pub fn paginate_vec<T, K: Ord>(mut items: Vec<T>, request: PageRequest, key_fn: impl Fn(&T) -> K) -> ServiceResult<Page<T>> {
+    request.validate()?;
+    let offset = request.offset()?;
+
+    let total_count = items.len();
+    items.sort_by_key(|item| key_fn(item));
+
+    let end = offset.saturating_add(request.limit as usize).min(total_count);
+    let items: Vec<_> = items.into_iter().skip(offset).take(request.limit as usize).collect();
+    let next_cursor = (end < total_count).then(|| end.to_string());
+
+    Ok(Page {
+        items,
+        next_cursor,
+        total_count: Some(total_count as u32),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_vec_by_ascending_is_stable() {
+        let mut items = vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+        sort_vec_by(&mut items, |item| item.0, SortOrder::Ascending);
+
+        assert_eq!(items, vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+    }
+
+    #[test]
+    fn test_sort_vec_by_descending_is_stable() {
+        let mut items = vec![(1, 'a'), (2, 'b'), (1, 'c'), (2, 'd')];
+        sort_vec_by(&mut items, |item| item.0, SortOrder::Descending);
+
+        assert_eq!(items, vec![(2, 'b'), (2, 'd'), (1, 'a'), (1, 'c')]);
+    }
+
+    #[test]
+    fn test_paginate_vec_walks_all_pages() {
+        let items = vec![5, 3, 1, 4, 2];
+
+        let page = paginate_vec(items.clone(), PageRequest::first(2), |item| *item).unwrap();
+        assert_eq!(page.items, vec![1, 2]);
+        assert_eq!(page.total_count, Some(5));
+
+        let page = paginate_vec(
+            items.clone(),
+            PageRequest {
+                cursor: page.next_cursor,
+                limit: 2,
+            },
+            |item| *item,
+        )
+        .unwrap();
+        assert_eq!(page.items, vec![3, 4]);
+
+        let page = paginate_vec(
+            items,
+            PageRequest {
+                cursor: page.next_cursor,
+                limit: 2,
+            },
+            |item| *item,
+        )
+        .unwrap();
+        assert_eq!(page.items, vec![5]);
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[test]
+    fn test_paginate_vec_past_the_end() {
+        let request = PageRequest {
+            cursor: Some("10".to_string()),
+            limit: 2,
+        };
+        let page = paginate_vec(vec![1, 2, 3], request, |item| *item).unwrap();
+
+        assert!(page.items.is_empty());
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[test]
+    fn test_page_request_validate() {
+        assert!(PageRequest::first(1).validate().is_ok());
+        assert!(PageRequest::first(MAX_PAGE_LIMIT).validate().is_ok());
+        assert!(PageRequest::first(0).validate().is_err());
+        assert!(PageRequest::first(MAX_PAGE_LIMIT + 1).validate().is_err());
+
+        let request = PageRequest {
+            cursor: Some("not a cursor".to_string()),
+            limit: 10,
+        };
+        assert!(request.validate().is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{error::ServiceResult, validate::ValidationError};
+use spacetimedb::ReducerContext;
+
+/// Largest `lambda` sampled in a single step of [`poisson_sample`], `e ^ -lambda` must not underflow.
+const POISSON_STEP_LAMBDA: f64 = 30.0;
+
+/// Number of successes in `n` independent trials with a `p` chance of success each.
+///
+/// Runs one random draw per trial.
+///
+/// # Errors
+/// Returns error if `p` isn't in `(0, 1]`.
+pub fn binomial_sample(ctx: &ReducerContext, n: u32, p: f64) -> ServiceResult<u32> {
+    binomial_sample_with(|| ctx.random::<f64>(), n, p)
+}
+
+/// Number of trials until the first success, at least 1, with a `p` chance of success each.
+///
+/// # Errors
+/// Returns error if `p` isn't in `(0, 1]`.
+pub fn geometric_sample(ctx: &ReducerContext, p: f64) -> ServiceResult<u32> {
+    geometric_sample_with(|| ctx.random::<f64>(), p)
+}
+
+/// Number of events in an interval where `lambda` events happen on average.
+///
+/// Runs about `lambda` random draws.
+///
+/// # Errors
+/// Returns error if `lambda` isn't greater than 0.
+pub fn poisson_sample(ctx: &ReducerContext, lambda: f64) -> ServiceResult<u32> {
+    poisson_sample_with(|| ctx.random::<f64>(), lambda)
+}
+
+fn validate_probability(name: &str, p: f64) -> ServiceResult<()> {
+    if p > 0.0 && p <= 1.0 {
+        Ok(())
+    } else {
+        Err(ValidationError::invalid_probability(name))
+    }
+}
+
+/// `random` returns uniform values in `[0, 1)`.
+pub(crate) fn binomial_sample_with(mut random: impl FnMut() -> f64, n: u32, p: f64) -> ServiceResult<u32> {
+    validate_probability("p", p)?;
+    Ok((0..n).filter(|_| random() < p).count() as u32)
+}
+
+/// `random` returns uniform values in `[0, 1)`.
+pub(crate) fn geometric_sample_with(mut random: impl FnMut() -> f64, p: f64) -> ServiceResult<u32> {
+    validate_probability("p", p)?;
+    if p == 1.0 {
+        return Ok(1);
+    }
+
+    // Inverse transform: the smallest k where 1 - (1 - p) ^ k > u. Casting saturates at u32::MAX.
+    let trials = ((1.0 - random()).ln() / (1.0 - p).ln()).ceil();
+    Ok((trials as u32).max(1))
+}
+
+/// `random` returns uniform values in `[0, 1)`.
+pub(crate) fn poisson_sample_with(mut random: impl FnMut() -> f64, lambda: f64) -> ServiceResult<u32> {
+    if lambda <= 0.0 || !lambda.is_finite() {
+        return Err(ValidationError::not_positive("lambda"));
+    }
+
+    // Knuth's algorithm on chunks of lambda, the sum of Poisson samples is a Poisson sample of the summed lambdas.
+    let mut count = 0u32;
+    let mut remaining = lambda;
+    while remaining > 0.0 {
+        let step = remaining.min(POISSON_STEP_LAMBDA);
+        remaining -= step;
+
+        let limit = (-step).exp();
+        let mut product = random();
+        while product > limit {
+            count = count.saturating_add(1);
+            product *= random();
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: u32 = 20_000;
+
+    /// Deterministic xorshift64 source of uniform values in `[0, 1)`.
+    fn random_source(mut state: u64) -> impl FnMut() -> f64 {
+        move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        }
+    }
+
+    fn sample_mean(mut sample: impl FnMut() -> u32) -> f64 {
+        (0..SAMPLES).map(|_| sample() as f64).sum::<f64>() / SAMPLES as f64
+    }
+
+    #[test]
+    fn test_binomial_sample_mean() {
+        let mut random = random_source(42);
+        let mean = sample_mean(|| binomial_sample_with(&mut random, 20, 0.3).unwrap());
+        assert!((mean - 6.0).abs() < 0.1, "mean {mean}");
+    }
+
+    #[test]
+    fn test_geometric_sample_mean() {
+        let mut random = random_source(42);
+        let mean = sample_mean(|| geometric_sample_with(&mut random, 0.25).unwrap());
+        assert!((mean - 4.0).abs() < 0.1, "mean {mean}");
+
+        assert_eq!(geometric_sample_with(random_source(7), 1.0).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_poisson_sample_mean() {
+        let mut random = random_source(42);
+        let mean = sample_mean(|| poisson_sample_with(&mut random, 3.5).unwrap());
+        assert!((mean - 3.5).abs() < 0.1, "mean {mean}");
+
+        let mean = sample_mean(|| poisson_sample_with(&mut random, 100.0).unwrap());
+        assert!((mean - 100.0).abs() < 0.5, "mean {mean}");
+    }
+
+    #[test]
+    fn test_sample_validation() {
+        for p in [0.0, -0.5, 1.5, f64::NAN] {
+            assert!(binomial_sample_with(random_source(1), 10, p).is_err());
+            assert!(geometric_sample_with(random_source(1), p).is_err());
+        }
+        for lambda in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(poisson_sample_with(random_source(1), lambda).is_err());
+        }
+    }
+}
@@ -0,0 +1,122 @@
+/// Highest level a player can reach.
+pub const MAX_LEVEL: u32 = 100;
+
+/// XP scale of the default curve: `level = floor(sqrt(xp / XP_LEVEL_FACTOR))`.
+pub const XP_LEVEL_FACTOR: u64 = 100;
+
+/// `(base, exponent)` of a curve where every level costs the same XP.
+pub const PROGRESSION_LINEAR: (u64, f64) = (XP_LEVEL_FACTOR, 1.0);
+
+/// `(base, exponent)` of the default curve used by [`calculate_level`].
+pub const PROGRESSION_QUADRATIC: (u64, f64) = (XP_LEVEL_FACTOR, 2.0);
+
+/// `(base, exponent)` of a curve where levels get cheaper the higher they are.
+pub const PROGRESSION_SQRT: (u64, f64) = (XP_LEVEL_FACTOR * 10, 0.5);
+
+/// Level reached with the given amount of XP on the default curve, capped at [`MAX_LEVEL`].
+pub fn calculate_level(xp: u64) -> u32 {
+    let level = (xp / XP_LEVEL_FACTOR).isqrt();
+    level.min(MAX_LEVEL as u64) as u32
+}
+
+/// Minimum amount of XP needed to reach `level` on the curve `xp = base * level ^ exponent`.
+///
+/// `exponent` must be positive. Saturates at `u64::MAX`.
+pub fn xp_for_level(level: u32, base: u64, exponent: f64) -> u64 {
+    (base as f64 * (level as f64).powf(exponent)).round() as u64
+}
+
+/// Level reached with the given amount of XP on the curve `xp = base * level ^ exponent`,
+/// the inverse of [`xp_for_level`]. Not capped.
+pub fn level_for_xp(xp: u64, base: u64, exponent: f64) -> u32 {
+    // Highest level whose threshold is still reachable
+    let (mut low, mut high) = (0u32, u32::MAX);
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        if xp_for_level(mid, base, exponent) <= xp {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    low
+}
+
+/// Progress from the current level to the next one, from 0.0 to 1.0.
+pub fn progress_to_next_level(xp: u64, base: u64, exponent: f64) -> f32 {
+    let level = level_for_xp(xp, base, exponent);
+    let current = xp_for_level(level, base, exponent);
+    let next = xp_for_level(level.saturating_add(1), base, exponent);
+    if next <= current {
+        return 1.0;
+    }
+
+    ((xp - current) as f64 / (next - current) as f64) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_level_basic() {
+        assert_eq!(calculate_level(0), 0);
+        assert_eq!(calculate_level(99), 0);
+        assert_eq!(calculate_level(100), 1);
+        assert_eq!(calculate_level(399), 1);
+        assert_eq!(calculate_level(400), 2);
+        assert_eq!(calculate_level(10_000), 10);
+    }
+
+    #[test]
+    fn test_calculate_level_capped() {
+        let (base, exponent) = PROGRESSION_QUADRATIC;
+        assert_eq!(calculate_level(xp_for_level(MAX_LEVEL, base, exponent)), MAX_LEVEL);
+        assert_eq!(calculate_level(xp_for_level(MAX_LEVEL + 1, base, exponent)), MAX_LEVEL);
+        assert_eq!(calculate_level(u64::MAX), MAX_LEVEL);
+    }
+
+    #[test]
+    fn test_calculate_level_matches_quadratic_curve() {
+        let (base, exponent) = PROGRESSION_QUADRATIC;
+        for level in 0..=MAX_LEVEL {
+            let xp = xp_for_level(level, base, exponent);
+            assert_eq!(calculate_level(xp), level);
+            if level > 0 {
+                assert_eq!(calculate_level(xp - 1), level - 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_level_for_xp_is_inverse() {
+        for (base, exponent) in [PROGRESSION_LINEAR, PROGRESSION_QUADRATIC, PROGRESSION_SQRT] {
+            for level in 0..=200 {
+                let xp = xp_for_level(level, base, exponent);
+                assert_eq!(level_for_xp(xp, base, exponent), level);
+                if level > 0 {
+                    assert_eq!(level_for_xp(xp - 1, base, exponent), level - 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_level_for_xp_extremes() {
+        let (base, exponent) = PROGRESSION_LINEAR;
+        assert_eq!(level_for_xp(0, base, exponent), 0);
+        assert_eq!(level_for_xp(u64::MAX, base, exponent), u32::MAX);
+    }
+
+    #[test]
+    fn test_progress_to_next_level() {
+        let (base, exponent) = PROGRESSION_LINEAR;
+        assert_eq!(progress_to_next_level(0, base, exponent), 0.0);
+        assert_eq!(progress_to_next_level(150, base, exponent), 0.5);
+        assert_eq!(progress_to_next_level(200, base, exponent), 0.0);
+
+        let (base, exponent) = PROGRESSION_QUADRATIC;
+        // Level 1 at 100 XP, level 2 at 400 XP
+        assert_eq!(progress_to_next_level(250, base, exponent), 0.5);
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    error::{ResultExt, ServiceResult},
+    uuid::Uuid,
+    validate::{ValidateExt, ValidationError, validate_u32, validate_u64},
+};
+use spacetimedb::{ReducerContext, ScheduleAt, Table, Timestamp, reducer, table};
+use std::time::Duration;
+
+/// Interval between two runs of `prune_rate_limiter_buckets_v1`.
+pub const RATE_LIMITER_CLEANUP_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Schedules the cleanup of expired buckets, call it from the `stdb_init` of the game.
+///
+/// # Errors
+/// Returns error if database operations fail.
+pub fn stdb_init(ctx: &ReducerContext) -> ServiceResult<()> {
+    if ctx.db.stdb_priv_rate_limiter_cleanup_schedule_v1().count() == 0 {
+        ctx.db
+            .stdb_priv_rate_limiter_cleanup_schedule_v1()
+            .try_insert(StdbPrivRateLimiterCleanupScheduleV1 {
+                scheduled_id: 0,
+                scheduled_at: Duration::from_secs(RATE_LIMITER_CLEANUP_INTERVAL_SECS).into(),
+            })
+            .map_conflict_ctx("failed to schedule rate limiter buckets cleanup")?;
+    }
+
+    Ok(())
+}
+
+/// Rate limiter bucket table - number of times a player performed an action during one bucket of time.
+///
+/// Only visible to the server. There is at most one row per `(player_id, action_key, bucket_ts)`,
+/// buckets that fell out of the window are deleted the next time the action is checked, or by
+/// `prune_rate_limiter_buckets_v1` once they expire.
+#[table(
+    name = stdb_rate_limiter_bucket_v1,
+    index(name = player_action_index, btree(columns = [player_id, action_key])),
+)]
+#[derive(Debug, Clone)]
+pub struct StdbRateLimiterBucketV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub id: u64,
+
+    pub player_id: Uuid,
+
+    pub action_key: String,
+
+    /// Start of the bucket.
+    pub bucket_ts: Timestamp,
+
+    /// When the bucket falls out of the window it was recorded with.
+    pub expires_at: Timestamp,
+
+    pub count: u32,
+}
+
+/// Schedule table driving `prune_rate_limiter_buckets_v1`.
+#[table(name = stdb_priv_rate_limiter_cleanup_schedule_v1, scheduled(prune_rate_limiter_buckets_v1))]
+#[derive(Debug, Clone)]
+pub struct StdbPrivRateLimiterCleanupScheduleV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub scheduled_id: u64,
+
+    pub scheduled_at: ScheduleAt,
+}
+
+#[reducer]
+pub fn prune_rate_limiter_buckets_v1(
+    ctx: &ReducerContext,
+    _schedule: StdbPrivRateLimiterCleanupScheduleV1,
+) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+
+    let expired: Vec<_> = ctx
+        .db
+        .stdb_rate_limiter_bucket_v1()
+        .iter()
+        .filter(|bucket| bucket.expires_at <= ctx.timestamp)
+        .map(|bucket| bucket.id)
+        .collect();
+
+    for id in expired {
+        ctx.db.stdb_rate_limiter_bucket_v1().id().delete(id);
+    }
+    Ok(())
+}
+
+/// Sliding-window rate limiter, the window of `window_secs` is split in `buckets` buckets of equal size.
+///
+/// Unlike a cooldown, it allows bursts of up to `limit` actions within the window. The window slides
+/// one bucket at a time, so more buckets make it more precise at the cost of more rows per player.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SlidingWindow {
+    pub buckets: u32,
+    pub window_secs: u64,
+}
+
+impl SlidingWindow {
+    /// Records one use of the action if the player performed it less than `limit` times within the window.
+    ///
+    /// # Errors
+    /// Returns `ServiceError::Validation` if the window is invalid, `ServiceError::RateLimited` if the
+    /// limit was reached, or error if database operations fail.
+    pub fn check_and_record(&self, ctx: &ReducerContext, player_id: &Uuid, action_key: &str, limit: u32) -> ServiceResult<()> {
+        validate_u32("buckets", self.buckets, 1, u32::MAX)?;
+        validate_u64("window_secs", self.window_secs, 1, u64::MAX / 1_000_000)?;
+
+        let current_bucket_ts = self.bucket_start(ctx.timestamp);
+        let (buckets, stale): (Vec<_>, Vec<_>) = ctx
+            .db
+            .stdb_rate_limiter_bucket_v1()
+            .player_action_index()
+            .filter((player_id, action_key))
+            .partition(|bucket| self.is_in_window(bucket.bucket_ts, current_bucket_ts));
+
+        for bucket in &stale {
+            ctx.db.stdb_rate_limiter_bucket_v1().id().delete(bucket.id);
+        }
+
+        if self.count_in_window(&buckets, current_bucket_ts) >= limit {
+            return Err(ValidationError::rate_limit_exceeded(action_key));
+        }
+
+        let current_bucket = buckets.into_iter().find(|bucket| bucket.bucket_ts == current_bucket_ts);
+        let bucket = match current_bucket {
+            Some(bucket) => StdbRateLimiterBucketV1 {
+                count: bucket.count.saturating_add(1),
+                ..bucket
+            },
+            None => StdbRateLimiterBucketV1 {
+                id: 0,
+                player_id: player_id.clone(),
+                action_key: action_key.to_string(),
+                bucket_ts: current_bucket_ts,
+                expires_at: self.bucket_expires_at(current_bucket_ts),
+                count: 1,
+            },
+        };
+
+        ctx.db
+            .stdb_rate_limiter_bucket_v1()
+            .id()
+            .try_insert_or_update(bucket)
+            .map_conflict_ctx("failed to update rate limiter bucket")?;
+
+        Ok(())
+    }
+
+    fn bucket_micros(&self) -> i64 {
+        let window_micros = self.window_secs.saturating_mul(1_000_000);
+        (window_micros / u64::from(self.buckets.max(1))).clamp(1, i64::MAX as u64) as i64
+    }
+
+    /// Start of the bucket containing `now`, `floor(now / bucket_size) * bucket_size`.
+    pub(crate) fn bucket_start(&self, now: Timestamp) -> Timestamp {
+        let bucket_micros = self.bucket_micros();
+        let micros = now.to_micros_since_unix_epoch().div_euclid(bucket_micros) * bucket_micros;
+        Timestamp::from_micros_since_unix_epoch(micros)
+    }
+
+    fn window_micros(&self) -> i64 {
+        self.bucket_micros().saturating_mul(i64::from(self.buckets))
+    }
+
+    /// Whether the bucket starting at `bucket_ts` is one of the last `buckets` buckets up to `current_bucket_ts`.
+    pub(crate) fn is_in_window(&self, bucket_ts: Timestamp, current_bucket_ts: Timestamp) -> bool {
+        let elapsed = current_bucket_ts.to_micros_since_unix_epoch() - bucket_ts.to_micros_since_unix_epoch();
+        elapsed < self.window_micros()
+    }
+
+    /// First instant the bucket starting at `bucket_ts` is out of the window.
+    pub(crate) fn bucket_expires_at(&self, bucket_ts: Timestamp) -> Timestamp {
+        let micros = bucket_ts.to_micros_since_unix_epoch().saturating_add(self.window_micros());
+        Timestamp::from_micros_since_unix_epoch(micros)
+    }
+
+    /// Total uses recorded in the buckets still in the window up to `current_bucket_ts`.
+    pub(crate) fn count_in_window(&self, buckets: &[StdbRateLimiterBucketV1], current_bucket_ts: Timestamp) -> u32 {
+        buckets
+            .iter()
+            .filter(|bucket| self.is_in_window(bucket.bucket_ts, current_bucket_ts))
+            .fold(0u32, |count, bucket| count.saturating_add(bucket.count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: SlidingWindow = SlidingWindow {
+        buckets: 6,
+        window_secs: 60,
+    };
+
+    fn at(secs: i64) -> Timestamp {
+        Timestamp::from_micros_since_unix_epoch(secs * 1_000_000)
+    }
+
+    #[test]
+    fn test_bucket_start() {
+        assert_eq!(WINDOW.bucket_start(at(0)), at(0));
+        assert_eq!(WINDOW.bucket_start(at(9)), at(0));
+        assert_eq!(WINDOW.bucket_start(at(10)), at(10));
+        assert_eq!(WINDOW.bucket_start(at(125)), at(120));
+    }
+
+    #[test]
+    fn test_is_in_window() {
+        let current = WINDOW.bucket_start(at(125));
+        assert!(WINDOW.is_in_window(at(120), current));
+        assert!(WINDOW.is_in_window(at(70), current));
+        assert!(!WINDOW.is_in_window(at(60), current));
+        assert!(!WINDOW.is_in_window(at(0), current));
+    }
+
+    fn bucket(bucket_ts: Timestamp, count: u32) -> StdbRateLimiterBucketV1 {
+        StdbRateLimiterBucketV1 {
+            id: 0,
+            player_id: "01890a5d-ac96-774b-bcce-b302099a8057".to_string(),
+            action_key: "chat".to_string(),
+            bucket_ts,
+            expires_at: WINDOW.bucket_expires_at(bucket_ts),
+            count,
+        }
+    }
+
+    #[test]
+    fn test_count_in_window() {
+        let current = WINDOW.bucket_start(at(125));
+        assert_eq!(WINDOW.count_in_window(&[], current), 0);
+
+        let buckets = [bucket(at(0), 7), bucket(at(60), 5), bucket(at(70), 2), bucket(at(120), 3)];
+        assert_eq!(WINDOW.count_in_window(&buckets, current), 5);
+
+        let saturated = [bucket(at(110), u32::MAX), bucket(at(120), 1)];
+        assert_eq!(WINDOW.count_in_window(&saturated, current), u32::MAX);
+    }
+
+    #[test]
+    fn test_bucket_expires_at() {
+        assert_eq!(WINDOW.bucket_expires_at(at(120)), at(180));
+
+        // Expires exactly when it falls out of the window
+        let expires_at = WINDOW.bucket_expires_at(at(70));
+        assert!(WINDOW.is_in_window(at(70), WINDOW.bucket_start(at(129))));
+        assert!(!WINDOW.is_in_window(at(70), WINDOW.bucket_start(expires_at)));
+    }
+
+    #[test]
+    fn test_single_bucket_window() {
+        let window = SlidingWindow {
+            buckets: 1,
+            window_secs: 60,
+        };
+        let current = window.bucket_start(at(90));
+        assert_eq!(current, at(60));
+        assert!(window.is_in_window(at(60), current));
+        assert!(!window.is_in_window(at(0), current));
+    }
+}
@@ -0,0 +1,74 @@
+use crate::error::{ServiceError, ServiceResult};
+use log::debug;
+
+/// Runs `f` until it succeeds or fails with anything other than `ServiceError::Conflict`,
+/// at most `max_attempts` times. Returns the last error once all attempts failed.
+///
+/// A `max_attempts` of 0 still runs `f` once. Reducers run in a single transaction and can't sleep,
+/// so attempts aren't delayed: retrying only helps when `f` picks new values on each attempt,
+/// e.g. a new random ID.
+pub fn with_retry<T, F>(max_attempts: u8, f: F) -> ServiceResult<T>
+where
+    F: Fn() -> ServiceResult<T>,
+{
+    let max_attempts = max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match f() {
+            Err(ServiceError::Conflict(message)) if attempt < max_attempts => {
+                debug!("retrying after conflict, attempt {}/{}: {}", attempt, max_attempts, message);
+                attempt += 1;
+            },
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn failing(failures: u8, error: fn() -> ServiceError) -> (Cell<u8>, impl Fn(&Cell<u8>) -> ServiceResult<u8>) {
+        (Cell::new(0), move |calls: &Cell<u8>| {
+            calls.set(calls.get() + 1);
+            if calls.get() <= failures {
+                Err(error())
+            } else {
+                Ok(calls.get())
+            }
+        })
+    }
+
+    fn conflict() -> ServiceError {
+        ServiceError::Conflict("conflict".into())
+    }
+
+    #[test]
+    fn test_with_retry_succeeds_after_conflicts() {
+        let (calls, f) = failing(2, conflict);
+        assert_eq!(with_retry(3, || f(&calls)).unwrap(), 3);
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_with_retry_returns_last_conflict() {
+        let (calls, f) = failing(5, conflict);
+        assert!(matches!(with_retry(3, || f(&calls)), Err(ServiceError::Conflict(_))));
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_with_retry_doesnt_retry_other_errors() {
+        let (calls, f) = failing(5, ServiceError::unauthorized);
+        assert!(matches!(with_retry(3, || f(&calls)), Err(ServiceError::Unauthorized(_))));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_with_retry_runs_at_least_once() {
+        let (calls, f) = failing(0, conflict);
+        assert_eq!(with_retry(0, || f(&calls)).unwrap(), 1);
+        assert_eq!(calls.get(), 1);
+    }
+}
@@ -1,14 +1,97 @@
-use spacetimedb::ReducerContext;
+use crate::{
+    error::ServiceResult,
+    validate::{ValidationError, validate_uuid},
+};
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+use spacetimedb::{ReducerContext, SpacetimeType, Timestamp};
+use std::fmt::{self, Display};
 
 /// Using String because we can't provide a custom Uuid SpacetimeType that can be used as primary_key.
+///
+/// Table keys and indexed columns have to keep using this alias, SpacetimeDB only filters on built-in
+/// types. Prefer [`TypedUuid`] in function signatures that don't touch indexes.
 pub type Uuid = String;
 
+/// UUID that can't be mixed up with arbitrary strings like display names.
+///
+/// Always lowercase when built with [`TypedUuid::parse`]. Converts to and from the [`Uuid`] alias,
+/// so it can be used at the edges while tables keep the alias.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, SpacetimeType, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TypedUuid {
+    value: String,
+}
+
+impl TypedUuid {
+    /// Random uuid v4
+    pub fn new_v4(ctx: &ReducerContext) -> Self {
+        Self {
+            value: ctx.new_uuid_v4(),
+        }
+    }
+
+    /// Timestamp based uuid v7
+    pub fn new_v7(ctx: &ReducerContext) -> Self {
+        Self {
+            value: ctx.new_uuid_v7(),
+        }
+    }
+
+    /// Parses and lowercases a UUID, see [`parse_uuid`].
+    ///
+    /// # Errors
+    /// Returns a validation error if the format is invalid.
+    pub fn parse(value: &str) -> ServiceResult<Self> {
+        parse_uuid(value).map(|value| Self { value })
+    }
+
+    pub fn nil() -> Self {
+        Self {
+            value: NIL_UUID.to_string(),
+        }
+    }
+
+    pub fn is_nil(&self) -> bool {
+        uuid_is_nil(&self.value)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Display for TypedUuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+/// Unchecked, kept for compatibility with code passing the [`Uuid`] alias around.
+/// Use [`TypedUuid::parse`] for untrusted input.
+impl From<String> for TypedUuid {
+    fn from(value: String) -> Self {
+        Self { value }
+    }
+}
+
+impl From<TypedUuid> for String {
+    fn from(value: TypedUuid) -> Self {
+        value.value
+    }
+}
+
+const NIL_UUID: &str = "00000000-0000-0000-0000-000000000000";
+const MAX_UUID: &str = "ffffffff-ffff-ffff-ffff-ffffffffffff";
+
 pub trait UuidExt {
     /// Random uuid v4
     fn new_uuid_v4(&self) -> Uuid;
 
     /// Timestamp based uuid v7
     fn new_uuid_v7(&self) -> Uuid;
+
+    /// Uuid v7 based on the given timestamp instead of the reducer's one
+    fn new_uuid_v7_at(&self, timestamp: Timestamp) -> Uuid;
 }
 
 impl UuidExt for ReducerContext {
@@ -18,13 +101,17 @@ impl UuidExt for ReducerContext {
     }
 
     fn new_uuid_v7(&self) -> Uuid {
-        let millis = (self.timestamp.to_micros_since_unix_epoch() / 1000) as u64;
+        self.new_uuid_v7_at(self.timestamp)
+    }
+
+    fn new_uuid_v7_at(&self, timestamp: Timestamp) -> Uuid {
+        let millis = (timestamp.to_micros_since_unix_epoch() / 1000) as u64;
         let bytes = inner_new_uuid_v7(millis, || self.random());
         uuid_to_string(bytes)
     }
 }
 
-fn inner_new_uuid_v4<R>(mut rng: R) -> [u8; 16]
+pub(crate) fn inner_new_uuid_v4<R>(mut rng: R) -> [u8; 16]
 where
     R: FnMut() -> u8,
 {
@@ -40,7 +127,7 @@ where
     uuid_bytes
 }
 
-fn inner_new_uuid_v7<R>(timestamp_millis: u64, mut rng: R) -> [u8; 16]
+pub(crate) fn inner_new_uuid_v7<R>(timestamp_millis: u64, mut rng: R) -> [u8; 16]
 where
     R: FnMut() -> u8,
 {
@@ -48,13 +135,11 @@ where
     let timestamp_millis = u64::to_be_bytes(timestamp_millis << 16);
 
     // First 48 bits are allocated to timestamp
-    for index in 0..6 {
-        uuid_bytes[index] = timestamp_millis[index];
-    }
+    uuid_bytes[..6].copy_from_slice(&timestamp_millis[..6]);
 
     // Next are random
-    for index in 6..16 {
-        uuid_bytes[index] = rng();
+    for byte in &mut uuid_bytes[6..] {
+        *byte = rng();
     }
 
     // Set version to 7 and variant same as uuidv4
@@ -64,6 +149,101 @@ where
     uuid_bytes
 }
 
+/// Parses a UUID in the `8-4-4-4-12` hex format and returns it lowercased.
+///
+/// Nil and max UUIDs are valid here, see [`uuid_is_nil`] and [`uuid_is_max`].
+///
+/// # Errors
+/// Returns a validation error if the format is invalid.
+pub fn parse_uuid(value: &str) -> ServiceResult<Uuid> {
+    parse_named_uuid("uuid", value)
+}
+
+/// Parses a UUID like [`parse_uuid`], falling back to the nil UUID when it's malformed,
+/// e.g. for optional IDs coming from external sources.
+///
+/// The max UUID also falls back to nil, as it's rejected by [`validate_uuid`].
+pub fn parse_uuid_or_nil(value: &str) -> Uuid {
+    parse_uuid_or_error(value, "uuid").unwrap_or_else(|_| NIL_UUID.to_string())
+}
+
+/// Parses a UUID like [`parse_uuid`], also rejecting the nil and max UUIDs like [`validate_uuid`].
+///
+/// # Errors
+/// Returns a validation error against `field_name` if the UUID is malformed, nil or max.
+pub fn parse_uuid_or_error(value: &str, field_name: &str) -> ServiceResult<Uuid> {
+    let uuid = parse_named_uuid(field_name, value)?;
+    validate_uuid(field_name, &uuid)?;
+    Ok(uuid)
+}
+
+/// Same as [`parse_uuid`], reporting errors against the field `name`.
+pub(crate) fn parse_named_uuid(name: impl Display, value: &str) -> ServiceResult<Uuid> {
+    // 36 characters: 8-4-4-4-12
+    if value.len() != 36 {
+        return Err(ValidationError::invalid_uuid(name));
+    }
+
+    for (i, byte) in value.bytes().enumerate() {
+        let valid = match i {
+            8 | 13 | 18 | 23 => byte == b'-',
+            _ => byte.is_ascii_hexdigit(),
+        };
+        if !valid {
+            return Err(ValidationError::invalid_uuid(name));
+        }
+    }
+
+    Ok(value.to_ascii_lowercase())
+}
+
+/// Extracts the 48-bit millisecond timestamp embedded in a uuid v7.
+///
+/// # Errors
+/// Returns a validation error if the value isn't a valid uuid v7.
+pub fn uuid_v7_timestamp(uuid: &Uuid) -> ServiceResult<Timestamp> {
+    let uuid = parse_uuid(uuid)?;
+    if uuid.as_bytes()[14] != b'7' {
+        return Err(ValidationError::invalid_uuid("uuid"));
+    }
+
+    let hex = format!("{}{}", &uuid[..8], &uuid[9..13]);
+    let millis = i64::from_str_radix(&hex, 16).map_err(|_| ValidationError::invalid_uuid("uuid"))?;
+    Ok(Timestamp::from_micros_since_unix_epoch(millis * 1000))
+}
+
+/// Whether the UUID is the nil UUID (all zeros).
+pub fn uuid_is_nil(uuid: &Uuid) -> bool {
+    uuid.eq_ignore_ascii_case(NIL_UUID)
+}
+
+/// Whether the UUID is the max UUID (all f's).
+pub fn uuid_is_max(uuid: &Uuid) -> bool {
+    uuid.eq_ignore_ascii_case(MAX_UUID)
+}
+
+/// Serializes a [`Uuid`] as a JSON string, for fields declared with `#[serde(serialize_with = "serialize_uuid")]`.
+///
+/// # Errors
+/// Returns the serializer error.
+pub fn serialize_uuid<S: Serializer>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(uuid)
+}
+
+/// Deserializes a [`Uuid`], validating and lowercasing it like [`parse_uuid`].
+///
+/// # Errors
+/// Returns a deserializer error if the value isn't a string in the UUID format.
+pub fn deserialize_uuid<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Uuid, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_uuid(&value).map_err(D::Error::custom)
+}
+
+/// Use with `#[serde(with = "stdb_common::uuid::serde")]` on [`Uuid`] fields exchanged with external services.
+pub mod serde {
+    pub use super::{deserialize_uuid as deserialize, serialize_uuid as serialize};
+}
+
 fn uuid_to_string(uuid_bytes: [u8; 16]) -> Uuid {
     format!(
         "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
@@ -84,6 +264,25 @@ fn uuid_to_string(uuid_bytes: [u8; 16]) -> Uuid {
     )
 }
 
+/// Internals measured by `benches/uuid_bench.rs`, not part of the public API.
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench {
+    use super::Uuid;
+
+    pub fn inner_new_uuid_v4(rng: impl FnMut() -> u8) -> [u8; 16] {
+        super::inner_new_uuid_v4(rng)
+    }
+
+    pub fn inner_new_uuid_v7(timestamp_millis: u64, rng: impl FnMut() -> u8) -> [u8; 16] {
+        super::inner_new_uuid_v7(timestamp_millis, rng)
+    }
+
+    pub fn uuid_to_string(uuid_bytes: [u8; 16]) -> Uuid {
+        super::uuid_to_string(uuid_bytes)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,4 +311,186 @@ mod tests {
 
         assert_eq!(uuid_to_string(uuid_bytes), "0197f231-554c-7001-8203-040506070809");
     }
+
+    /// Byte generator seeded with `state`, so the generated UUIDs are reproducible.
+    fn random_bytes(mut state: u64) -> impl FnMut() -> u8 {
+        move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 32) as u8
+        }
+    }
+
+    #[test]
+    fn test_generated_uuid_v4_unique_and_parsable() {
+        let mut random = random_bytes(0x2545_f491_4f6c_dd1d);
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..10_000 {
+            let uuid = uuid_to_string(inner_new_uuid_v4(&mut random));
+            assert_eq!(parse_uuid(&uuid).unwrap(), uuid);
+            assert_eq!(uuid.as_bytes()[14], b'4');
+            assert!(seen.insert(uuid));
+        }
+    }
+
+    #[test]
+    fn test_generated_uuid_v7_unique_and_parsable() {
+        let mut random = random_bytes(0x9e37_79b9_7f4a_7c15);
+        let mut seen = std::collections::HashSet::new();
+        // Ten UUIDs per millisecond, only the random bits tell them apart
+        for i in 0..10_000u64 {
+            let timestamp_millis = 1_745_712_000_000 + i / 10;
+            let uuid = uuid_to_string(inner_new_uuid_v7(timestamp_millis, &mut random));
+            assert_eq!(parse_uuid(&uuid).unwrap(), uuid);
+            assert_eq!(
+                uuid_v7_timestamp(&uuid).unwrap(),
+                Timestamp::from_micros_since_unix_epoch(timestamp_millis as i64 * 1000)
+            );
+            assert!(seen.insert(uuid));
+        }
+    }
+
+    #[test]
+    fn test_typed_uuid() {
+        let uuid = TypedUuid::parse("0197F231-554C-7001-8203-040506070809").unwrap();
+        assert_eq!(uuid.as_str(), "0197f231-554c-7001-8203-040506070809");
+        assert_eq!(uuid.to_string(), "0197f231-554c-7001-8203-040506070809");
+        assert!(!uuid.is_nil());
+        assert_eq!(String::from(uuid.clone()), uuid.as_str());
+
+        assert!(TypedUuid::nil().is_nil());
+        assert!(TypedUuid::parse("player name").is_err());
+    }
+
+    #[test]
+    fn test_uuid_v7_timestamp() {
+        let timestamp_millis = 1752115008844;
+        let mut random = 0u8..255;
+        let uuid = uuid_to_string(inner_new_uuid_v7(timestamp_millis, move || random.next().unwrap()));
+
+        assert_eq!(
+            uuid_v7_timestamp(&uuid).unwrap(),
+            Timestamp::from_micros_since_unix_epoch(timestamp_millis as i64 * 1000)
+        );
+    }
+
+    #[test]
+    fn test_uuid_v7_timestamp_rejects_other_versions() {
+        assert!(uuid_v7_timestamp(&"00010203-0405-4607-8809-0a0b0c0d0e0f".to_string()).is_err());
+        assert!(uuid_v7_timestamp(&"not a uuid".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_parse_uuid_normalizes() {
+        assert_eq!(
+            parse_uuid("0197F231-554C-7001-8203-040506070809").unwrap(),
+            "0197f231-554c-7001-8203-040506070809"
+        );
+        assert_eq!(parse_uuid(NIL_UUID).unwrap(), NIL_UUID);
+    }
+
+    #[test]
+    fn test_parse_uuid_invalid() {
+        assert!(parse_uuid("").is_err());
+        assert!(parse_uuid("0197f231554c70018203040506070809").is_err());
+        assert!(parse_uuid("0197f231-554c-7001-8203-04050607080").is_err());
+        assert!(parse_uuid("0197f231_554c_7001_8203_040506070809").is_err());
+        assert!(parse_uuid("0197f231-554c-7001-8203-04050607080g").is_err());
+        assert!(parse_uuid("0197f231-554c-7001-8203-0405060708ñ").is_err());
+    }
+
+    #[test]
+    fn test_parse_uuid_or_nil() {
+        assert_eq!(
+            parse_uuid_or_nil("0197F231-554C-7001-8203-040506070809"),
+            "0197f231-554c-7001-8203-040506070809"
+        );
+        assert_eq!(parse_uuid_or_nil(""), NIL_UUID);
+        assert_eq!(parse_uuid_or_nil(MAX_UUID), NIL_UUID);
+        assert_eq!(parse_uuid_or_nil("not a uuid"), NIL_UUID);
+    }
+
+    #[test]
+    fn test_parse_uuid_or_error() {
+        assert_eq!(
+            parse_uuid_or_error("0197F231-554C-7001-8203-040506070809", "player_id").unwrap(),
+            "0197f231-554c-7001-8203-040506070809"
+        );
+        assert!(parse_uuid_or_error(NIL_UUID, "player_id").is_err());
+        assert!(parse_uuid_or_error(MAX_UUID, "player_id").is_err());
+
+        let error = parse_uuid_or_error("", "player_id").unwrap_err();
+        assert!(error.to_string().contains("player_id"), "{}", error);
+    }
+
+    #[test]
+    fn test_parse_uuid_malformed_inputs() {
+        let valid = "0197f231-554c-7001-8203-040506070809";
+        let mut inputs = vec![
+            String::new(),
+            " ".repeat(36),
+            "-".repeat(36),
+            format!(" {valid}"),
+            format!("{valid} "),
+            format!("{{{valid}}}"),
+            valid.replace('-', ""),
+            valid.replace('-', ":"),
+            "é".repeat(18),
+            "\0".repeat(18),
+        ];
+
+        // Every single-byte corruption of a valid UUID
+        for (index, _) in valid.char_indices() {
+            for replacement in ['g', 'Z', '-', ' ', '_', '0'] {
+                let mut input = valid.to_string();
+                input.replace_range(index..=index, &replacement.to_string());
+                if input != valid && parse_uuid(&input).is_err() {
+                    inputs.push(input);
+                }
+            }
+            let mut truncated = valid.to_string();
+            truncated.remove(index);
+            inputs.push(truncated);
+        }
+
+        for input in &inputs {
+            assert!(parse_uuid(input).is_err(), "{:?} parsed", input);
+            assert!(parse_uuid_or_error(input, "uuid").is_err(), "{:?} parsed", input);
+            assert_eq!(parse_uuid_or_nil(input), NIL_UUID, "{:?} parsed", input);
+        }
+    }
+
+    #[test]
+    fn test_uuid_is_nil_and_max() {
+        assert!(uuid_is_nil(&NIL_UUID.to_string()));
+        assert!(uuid_is_max(&MAX_UUID.to_uppercase()));
+        assert!(!uuid_is_nil(&MAX_UUID.to_string()));
+        assert!(!uuid_is_max(&"0197f231-554c-7001-8203-040506070809".to_string()));
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Payload {
+        #[serde(with = "crate::uuid::serde")]
+        player_id: Uuid,
+    }
+
+    #[test]
+    fn test_uuid_serde() {
+        let payload = Payload {
+            player_id: "01890a5d-ac96-774b-bcce-b302099a8057".to_string(),
+        };
+        let json = serde_json::to_string(&payload).unwrap();
+        assert_eq!(json, r#"{"player_id":"01890a5d-ac96-774b-bcce-b302099a8057"}"#);
+        assert_eq!(serde_json::from_str::<Payload>(&json).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_uuid_serde_validates_and_lowercases() {
+        let payload: Payload = serde_json::from_str(r#"{"player_id":"01890A5D-AC96-774B-BCCE-B302099A8057"}"#).unwrap();
+        assert_eq!(payload.player_id, "01890a5d-ac96-774b-bcce-b302099a8057");
+
+        assert!(serde_json::from_str::<Payload>(r#"{"player_id":"not-a-uuid"}"#).is_err());
+        assert!(serde_json::from_str::<Payload>(r#"{"player_id":42}"#).is_err());
+    }
 }
@@ -1,12 +1,12 @@
 use crate::{
-    error::{ErrorMapper, ServiceError, ServiceResult},
-    uuid::Uuid,
+    cooldown::{StdbCooldownV1, cooldown_remaining, find_cooldown, stdb_cooldown_v1},
+    error::{ErrorMapper, ResultExt, ServiceError, ServiceResult},
+    uuid::{Uuid, parse_named_uuid, uuid_is_max, uuid_is_nil},
 };
 use spacetimedb::ReducerContext;
-use std::fmt::Display;
+use std::{fmt::Display, ops::Deref, time::Duration};
 use thiserror::Error;
 
-#[must_use]
 pub fn validate_str(name: impl Display, value: &str, min_length: u64, max_length: u64) -> ServiceResult<()> {
     let len = value.len() as u64;
     if min_length > 0 && value.is_empty() {
@@ -20,45 +20,115 @@ pub fn validate_str(name: impl Display, value: &str, min_length: u64, max_length
     }
 }
 
-#[must_use]
-pub fn validate_uuid(name: impl Display, uuid: &Uuid) -> ServiceResult<()> {
-    // Check if UUID has correct length (36 characters: 8-4-4-4-12)
-    if uuid.len() != 36 {
-        return Err(ValidationError::invalid_uuid(name));
+/// A value that was sanitized before being validated, e.g. by [`sanitize_str`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sanitized<T>(T);
+
+impl<T> Sanitized<T> {
+    pub fn into_inner(self) -> T {
+        self.0
     }
+}
 
-    // Check if dashes are in correct positions
-    let chars: Vec<char> = uuid.to_lowercase().chars().collect();
-    if chars[8] != '-' || chars[13] != '-' || chars[18] != '-' || chars[23] != '-' {
-        return Err(ValidationError::invalid_uuid(name));
+impl<T> Deref for Sanitized<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
     }
+}
 
-    // Check if all other characters are valid hex digits
-    for (i, &ch) in chars.iter().enumerate() {
-        if i == 8 || i == 13 || i == 18 || i == 23 {
-            continue; // Skip dash positions
-        }
-        if !ch.is_ascii_hexdigit() {
-            return Err(ValidationError::invalid_uuid(name));
-        }
+/// Trims leading and trailing whitespace, then validates the trimmed value like [`validate_str`].
+pub fn sanitize_str(name: impl Display, value: String, min_length: u64, max_length: u64) -> ServiceResult<Sanitized<String>> {
+    let trimmed = value.trim();
+    let value = if trimmed.len() == value.len() {
+        value
+    } else {
+        trimmed.to_string()
+    };
+
+    validate_str(name, &value, min_length, max_length)?;
+    Ok(Sanitized(value))
+}
+
+/// Validates that `value` is one of the `allowed` values, e.g. avatar asset names or game modes.
+pub fn validate_enum(name: impl Display, value: &str, allowed: &[&str]) -> ServiceResult<()> {
+    if allowed.contains(&value) {
+        Ok(())
+    } else {
+        Err(ValidationError::field_error(
+            name,
+            format!("must be one of: {}", allowed.join(", ")),
+        ))
     }
+}
 
-    // Check if it's not nil UUID (all zeros)
-    if uuid == "00000000-0000-0000-0000-000000000000" {
-        return Err(ValidationError::invalid_uuid(name));
+/// Validates free text such as a bio, counting Unicode chars instead of bytes.
+///
+/// Control characters are rejected, except for line breaks.
+pub fn validate_text(name: impl Display, value: &str, min_chars: u64, max_chars: u64) -> ServiceResult<()> {
+    let len = value.chars().count() as u64;
+    if min_chars > 0 && value.is_empty() {
+        Err(ValidationError::required_field(name))
+    } else if len < min_chars {
+        Err(ValidationError::field_too_small(name, min_chars))
+    } else if len > max_chars {
+        Err(ValidationError::field_too_large(name, max_chars))
+    } else if value.chars().any(|ch| ch.is_control() && ch != '\n') {
+        Err(ValidationError::invalid_characters(name))
+    } else {
+        Ok(())
     }
+}
 
-    // Check if it's not max UUID (all f's)
-    if uuid == "ffffffff-ffff-ffff-ffff-ffffffffffff" {
+pub fn validate_uuid(name: impl Display, uuid: &Uuid) -> ServiceResult<()> {
+    let uuid = parse_named_uuid(&name, uuid)?;
+    if uuid_is_nil(&uuid) || uuid_is_max(&uuid) {
         return Err(ValidationError::invalid_uuid(name));
     }
 
     Ok(())
 }
 
+/// Validates a semver-like version string such as `1.2.3`, `1.0.0-beta` or `2.1+42`.
+///
+/// Must be 1-16 chars, start with a digit and only contain ASCII alphanumerics, `.`, `-` or `+`.
+pub fn validate_version(name: impl Display, value: &str) -> ServiceResult<()> {
+    validate_str(&name, value, 1, 16)?;
+
+    let starts_with_digit = value.starts_with(|ch: char| ch.is_ascii_digit());
+    let valid_chars = value
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-' | '+'));
+    if !starts_with_digit || !valid_chars {
+        return Err(ValidationError::invalid_version(name));
+    }
+
+    Ok(())
+}
+
+/// Validates a simplified BCP-47 language tag such as `en`, `pt-BR` or `zh-HANT`.
+///
+/// Must be a 2-3 lowercase letter language, optionally followed by `-` and a 2-4 uppercase letter region or script.
+pub fn validate_locale(name: impl Display, locale: &str) -> ServiceResult<()> {
+    validate_str(&name, locale, 2, 10)?;
+
+    let (language, subtag) = match locale.split_once('-') {
+        Some((language, subtag)) => (language, Some(subtag)),
+        None => (locale, None),
+    };
+    let valid_language = (2..=3).contains(&language.len()) && language.bytes().all(|byte| byte.is_ascii_lowercase());
+    let valid_subtag =
+        subtag.is_none_or(|subtag| (2..=4).contains(&subtag.len()) && subtag.bytes().all(|byte| byte.is_ascii_uppercase()));
+    if !valid_language || !valid_subtag {
+        return Err(ValidationError::invalid_locale(name));
+    }
+
+    Ok(())
+}
+
 macro_rules! impl_validate_numeric {
     ($display:tt, $type:ty) => {
-        #[must_use]
         pub fn $display(name: impl Display, value: $type, min_value: $type, max_value: $type) -> ServiceResult<()> {
             if value < min_value {
                 Err(ValidationError::field_too_small(name, min_value))
@@ -86,8 +156,15 @@ impl_validate_numeric!(validate_i128, i128);
 impl_validate_numeric!(validate_isize, isize);
 
 pub trait ValidateExt {
-    #[must_use]
     fn require_private_access(&self) -> ServiceResult<()>;
+
+    /// Requires that the player didn't perform `action_key` in the last `duration`.
+    ///
+    /// When the cooldown expired, records the current timestamp as the last use of the action.
+    ///
+    /// # Errors
+    /// Returns `ServiceError::RateLimited` with the remaining seconds if the cooldown is still active.
+    fn require_cooldown(&self, player_id: &Uuid, action_key: &str, duration: Duration) -> ServiceResult<()>;
 }
 
 impl ValidateExt for ReducerContext {
@@ -98,6 +175,34 @@ impl ValidateExt for ReducerContext {
 
         Ok(())
     }
+
+    fn require_cooldown(&self, player_id: &Uuid, action_key: &str, duration: Duration) -> ServiceResult<()> {
+        let cooldown = match find_cooldown(self, player_id, action_key) {
+            Some(cooldown) => {
+                if let Some(remaining) = cooldown_remaining(cooldown.last_used_at, self.timestamp, duration) {
+                    return Err(ValidationError::cooldown_active(action_key, remaining));
+                }
+                cooldown
+            },
+            None => StdbCooldownV1 {
+                cooldown_id: 0,
+                player_id: player_id.clone(),
+                action_key: action_key.to_string(),
+                last_used_at: self.timestamp,
+            },
+        };
+
+        self.db
+            .stdb_cooldown_v1()
+            .cooldown_id()
+            .try_insert_or_update(StdbCooldownV1 {
+                last_used_at: self.timestamp,
+                ..cooldown
+            })
+            .map_conflict_ctx("failed to update cooldown")?;
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Error)]
@@ -113,6 +218,36 @@ pub enum ValidationError {
 
     #[error("Field '{0}' must be a valid UUID")]
     InvalidUuid(String),
+
+    #[error("Field '{0}' contains invalid characters")]
+    InvalidCharacters(String),
+
+    #[error("Field '{0}' must be a valid version")]
+    InvalidVersion(String),
+
+    #[error("Field '{0}' must be a language tag like 'en' or 'pt-BR'")]
+    InvalidLocale(String),
+
+    #[error("Action '{0}' is on cooldown, try again in {1}s")]
+    CooldownActive(String, u64),
+
+    #[error("Field '{0}' must be a cursor returned by a previous page")]
+    InvalidCursor(String),
+
+    #[error("Field '{0}' must be a probability greater than 0 and at most 1")]
+    InvalidProbability(String),
+
+    #[error("Field '{0}' must be greater than 0")]
+    NotPositive(String),
+
+    #[error("Field '{0}' value already taken")]
+    DuplicateValue(String),
+
+    #[error("Action '{0}' was performed too many times, try again later")]
+    RateLimitExceeded(String),
+
+    #[error("Field '{0}' is invalid: {1}")]
+    FieldError(String, String),
 }
 
 impl ValidationError {
@@ -131,4 +266,156 @@ impl ValidationError {
     pub fn invalid_uuid(name: impl Display) -> ServiceError {
         ValidationError::InvalidUuid(name.to_string()).map_validation()
     }
+
+    pub fn invalid_characters(name: impl Display) -> ServiceError {
+        ValidationError::InvalidCharacters(name.to_string()).map_validation()
+    }
+
+    pub fn invalid_version(name: impl Display) -> ServiceError {
+        ValidationError::InvalidVersion(name.to_string()).map_validation()
+    }
+
+    pub fn invalid_locale(name: impl Display) -> ServiceError {
+        ValidationError::InvalidLocale(name.to_string()).map_validation()
+    }
+
+    pub fn cooldown_active(action_key: impl Display, remaining: Duration) -> ServiceError {
+        // Round up so that "try again in 0s" is never reported
+        let remaining_secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        ValidationError::CooldownActive(action_key.to_string(), remaining_secs).map_rate_limited()
+    }
+
+    pub fn invalid_cursor(name: impl Display) -> ServiceError {
+        ValidationError::InvalidCursor(name.to_string()).map_validation()
+    }
+
+    pub fn invalid_probability(name: impl Display) -> ServiceError {
+        ValidationError::InvalidProbability(name.to_string()).map_validation()
+    }
+
+    pub fn not_positive(name: impl Display) -> ServiceError {
+        ValidationError::NotPositive(name.to_string()).map_validation()
+    }
+
+    pub fn duplicate_value(name: impl Display) -> ServiceError {
+        ValidationError::DuplicateValue(name.to_string()).map_conflict()
+    }
+
+    pub fn rate_limit_exceeded(action_key: impl Display) -> ServiceError {
+        ValidationError::RateLimitExceeded(action_key.to_string()).map_rate_limited()
+    }
+
+    /// Validation failure that doesn't fit the other variants, e.g. rejected content.
+    pub fn field_error(name: impl Display, message: impl Display) -> ServiceError {
+        ValidationError::FieldError(name.to_string(), message.to_string()).map_validation()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cooldown_active_message() {
+        let error = ValidationError::cooldown_active("insert_vip", Duration::from_secs(3));
+        assert_eq!(error.to_string(), "E429: Action 'insert_vip' is on cooldown, try again in 3s");
+    }
+
+    #[test]
+    fn test_cooldown_active_message_rounds_up() {
+        let error = ValidationError::cooldown_active("insert_vip", Duration::from_millis(2_001));
+        assert_eq!(error.to_string(), "E429: Action 'insert_vip' is on cooldown, try again in 3s");
+
+        let error = ValidationError::cooldown_active("insert_vip", Duration::from_millis(1));
+        assert_eq!(error.to_string(), "E429: Action 'insert_vip' is on cooldown, try again in 1s");
+        assert!(matches!(error, ServiceError::RateLimited(_)));
+    }
+
+    #[test]
+    fn test_sanitize_str_trims_before_validation() {
+        let value = sanitize_str("display_name", "  Swift Wolf  ".to_string(), 8, 10).unwrap();
+        assert_eq!(*value, "Swift Wolf");
+        assert_eq!(value.len(), 10);
+
+        // Long enough only because of the padding
+        assert!(sanitize_str("display_name", "  Wolf  ".to_string(), 8, 64).is_err());
+        assert!(sanitize_str("display_name", "   ".to_string(), 1, 64).is_err());
+        assert_eq!(
+            sanitize_str("avatar", "default_avatar".to_string(), 8, 64)
+                .unwrap()
+                .into_inner(),
+            "default_avatar"
+        );
+    }
+
+    #[test]
+    fn test_validate_enum() {
+        let allowed = ["ranked", "casual"];
+        assert!(validate_enum("game_mode", "ranked", &allowed).is_ok());
+        assert!(validate_enum("game_mode", "Ranked", &allowed).is_err());
+
+        let error = validate_enum("game_mode", "arcade", &allowed).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Field 'game_mode' is invalid: must be one of: ranked, casual")
+        );
+        assert!(validate_enum("game_mode", "", &[]).is_err());
+    }
+
+    #[test]
+    fn test_validate_text_counts_chars() {
+        // 4 chars but 8 bytes
+        assert!(validate_text("bio", "ñãõé", 0, 4).is_ok());
+        assert!(validate_text("bio", "ñãõé", 0, 3).is_err());
+        assert!(validate_text("bio", "", 0, 4).is_ok());
+        assert!(validate_text("bio", "", 1, 4).is_err());
+    }
+
+    #[test]
+    fn test_validate_text_control_chars() {
+        assert!(validate_text("bio", "line one\nline two", 0, 256).is_ok());
+        assert!(validate_text("bio", "null\0byte", 0, 256).is_err());
+        assert!(validate_text("bio", "tab\tchar", 0, 256).is_err());
+        assert!(validate_text("bio", "bell\u{7}", 0, 256).is_err());
+    }
+
+    #[test]
+    fn test_validate_version_valid() {
+        assert!(validate_version("client_version", "1").is_ok());
+        assert!(validate_version("client_version", "1.2.3").is_ok());
+        assert!(validate_version("client_version", "1.0.0-beta.1").is_ok());
+        assert!(validate_version("client_version", "2.1+42").is_ok());
+    }
+
+    #[test]
+    fn test_validate_version_invalid() {
+        assert!(validate_version("client_version", "").is_err());
+        assert!(validate_version("client_version", "v1.2.3").is_err());
+        assert!(validate_version("client_version", "1.2 3").is_err());
+        assert!(validate_version("client_version", "1.2.3-beta.123456").is_err());
+    }
+
+    #[test]
+    fn test_validate_locale_valid() {
+        assert!(validate_locale("locale", "en").is_ok());
+        assert!(validate_locale("locale", "fil").is_ok());
+        assert!(validate_locale("locale", "pt-BR").is_ok());
+        assert!(validate_locale("locale", "zh-HANT").is_ok());
+    }
+
+    #[test]
+    fn test_validate_locale_invalid() {
+        assert!(validate_locale("locale", "").is_err());
+        assert!(validate_locale("locale", "e").is_err());
+        assert!(validate_locale("locale", "EN").is_err());
+        assert!(validate_locale("locale", "engl").is_err());
+        assert!(validate_locale("locale", "pt_BR").is_err());
+        assert!(validate_locale("locale", "pt-br").is_err());
+        assert!(validate_locale("locale", "pt-").is_err());
+        assert!(validate_locale("locale", "pt-B").is_err());
+        assert!(validate_locale("locale", "pt-BRAZL").is_err());
+        assert!(validate_locale("locale", "pt-BR-x").is_err());
+        assert!(validate_locale("locale", "ñu").is_err());
+    }
 }
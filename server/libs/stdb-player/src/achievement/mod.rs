@@ -0,0 +1,78 @@
+use crate::achievement::repository::AchievementRepository;
+use spacetimedb::{Filter, ReducerContext, Timestamp, client_visibility_filter, reducer, table};
+use stdb_common::prelude::{ServiceResult, Uuid, ValidateExt};
+
+pub mod repository;
+
+pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+#[client_visibility_filter]
+const STDB_PLAYER_ACHIEVEMENT_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select a.*
+    from stdb_player_achievement_v1 a
+    join stdb_own_player_session_v1 s
+        on s.player_id = a.player_id
+    where s.session_id = :sender
+"#,
+);
+
+/// Achievement definition table - the catalog of achievements of the game.
+///
+/// Hidden achievements should not be revealed by clients until they are unlocked.
+#[table(name = stdb_achievement_def_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbAchievementDefV1 {
+    #[primary_key]
+    pub achievement_id: Uuid,
+
+    pub name: String,
+    pub description: String,
+
+    pub points: u32,
+
+    /// Progress needed to unlock the achievement, copied into each player's progress row.
+    pub required: u32,
+
+    pub is_hidden: bool,
+}
+
+/// Player achievement table - progress of a player towards an achievement.
+///
+/// `unlocked_at` is set the first time `progress` reaches `required`.
+#[table(
+    name = stdb_player_achievement_v1,
+    public,
+    index(name = player_achievement_index, btree(columns = [player_id, achievement_id])),
+)]
+#[derive(Debug, Clone)]
+pub struct StdbPlayerAchievementV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub id: u64,
+
+    pub player_id: Uuid,
+
+    pub achievement_id: Uuid,
+
+    pub progress: u32,
+    pub required: u32,
+
+    pub unlocked_at: Option<Timestamp>,
+}
+
+/// Server-side grant of an achievement, unlocking it regardless of the current progress.
+#[reducer]
+pub fn unlock_achievement_v1(ctx: &ReducerContext, player_id: Uuid, achievement_id: Uuid) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.unlock_achievement(player_id, achievement_id)?;
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use crate::{
+    achievement::{StdbAchievementDefV1, StdbPlayerAchievementV1, stdb_achievement_def_v1, stdb_player_achievement_v1},
+    error::PlayerError,
+    notification::{NotificationKindV1, repository::NotificationRepository},
+};
+use spacetimedb::{ReducerContext, Table};
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, UuidExt, validate_str, validate_u32, validate_uuid};
+
+/// Repository trait for managing achievement definitions and player progress.
+///
+/// Progress rows are created lazily on the first progress update of a player.
+pub trait AchievementRepository {
+    /// Finds an achievement definition by its ID.
+    ///
+    /// Returns `None` if no achievement exists with the given ID.
+    fn find_achievement_def(&self, achievement_id: &Uuid) -> Option<StdbAchievementDefV1>;
+
+    /// Creates a new achievement definition with a random achievement ID.
+    ///
+    /// # Errors
+    /// Returns error if validation fails or database operations fail.
+    fn insert_achievement_def(
+        &self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        points: u32,
+        required: u32,
+        is_hidden: bool,
+    ) -> ServiceResult<StdbAchievementDefV1>;
+
+    /// Finds the progress of a player towards an achievement.
+    ///
+    /// Returns `None` if the player never made progress on the achievement.
+    fn find_progress(&self, player_id: &Uuid, achievement_id: &Uuid) -> Option<StdbPlayerAchievementV1>;
+
+    /// Adds `delta` to the progress of a player, capped at the required progress.
+    ///
+    /// Returns `true` only when this update unlocked the achievement for the first time.
+    ///
+    /// # Errors
+    /// Returns error if the achievement doesn't exist, validation fails or database operations fail.
+    fn update_progress(&self, player_id: Uuid, achievement_id: Uuid, delta: u32) -> ServiceResult<bool>;
+
+    /// Unlocks an achievement for a player by completing its progress.
+    ///
+    /// Returns `true` if the achievement was not unlocked before.
+    ///
+    /// # Errors
+    /// Returns error if the achievement doesn't exist, validation fails or database operations fail.
+    fn unlock_achievement(&self, player_id: Uuid, achievement_id: Uuid) -> ServiceResult<bool>;
+
+    /// Returns all achievements unlocked by a player.
+    fn find_unlocked_for_player(&self, player_id: &Uuid) -> Vec<StdbPlayerAchievementV1>;
+
+    /// Returns the progress of a player on every achievement they started.
+    fn find_all_progress(&self, player_id: &Uuid) -> Vec<StdbPlayerAchievementV1>;
+}
+
+impl AchievementRepository for ReducerContext {
+    fn find_achievement_def(&self, achievement_id: &Uuid) -> Option<StdbAchievementDefV1> {
+        self.db.stdb_achievement_def_v1().achievement_id().find(achievement_id)
+    }
+
+    fn insert_achievement_def(
+        &self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        points: u32,
+        required: u32,
+        is_hidden: bool,
+    ) -> ServiceResult<StdbAchievementDefV1> {
+        let name = name.into();
+        let description = description.into();
+
+        validate_str("name", &name, 1, 64)?;
+        validate_str("description", &description, 0, 256)?;
+        validate_u32("required", required, 1, u32::MAX)?;
+
+        self.db
+            .stdb_achievement_def_v1()
+            .try_insert(StdbAchievementDefV1 {
+                achievement_id: self.new_uuid_v7(),
+                name,
+                description,
+                points,
+                required,
+                is_hidden,
+            })
+            .map_conflict_ctx("failed to insert achievement")
+    }
+
+    fn find_progress(&self, player_id: &Uuid, achievement_id: &Uuid) -> Option<StdbPlayerAchievementV1> {
+        self.db
+            .stdb_player_achievement_v1()
+            .player_achievement_index()
+            .filter((player_id, achievement_id))
+            .next()
+    }
+
+    fn update_progress(&self, player_id: Uuid, achievement_id: Uuid, delta: u32) -> ServiceResult<bool> {
+        validate_uuid("player_id", &player_id)?;
+        validate_uuid("achievement_id", &achievement_id)?;
+
+        let Some(def) = self.find_achievement_def(&achievement_id) else {
+            return Err(PlayerError::achievement_not_found(achievement_id));
+        };
+
+        let mut row = self
+            .find_progress(&player_id, &achievement_id)
+            .unwrap_or(StdbPlayerAchievementV1 {
+                id: 0,
+                player_id,
+                achievement_id,
+                progress: 0,
+                required: def.required,
+                unlocked_at: None,
+            });
+
+        if row.unlocked_at.is_some() {
+            return Ok(false);
+        }
+
+        row.progress = apply_progress(row.progress, row.required, delta);
+        let unlocked = row.progress >= row.required;
+        if unlocked {
+            row.unlocked_at = Some(self.timestamp);
+        }
+
+        let row = self
+            .db
+            .stdb_player_achievement_v1()
+            .id()
+            .try_insert_or_update(row)
+            .map_conflict_ctx("failed to update achievement progress")?;
+
+        if unlocked {
+            self.notify_player(row.player_id, NotificationKindV1::AchievementUnlocked, row.achievement_id)?;
+        }
+
+        Ok(unlocked)
+    }
+
+    fn unlock_achievement(&self, player_id: Uuid, achievement_id: Uuid) -> ServiceResult<bool> {
+        self.update_progress(player_id, achievement_id, u32::MAX)
+    }
+
+    fn find_unlocked_for_player(&self, player_id: &Uuid) -> Vec<StdbPlayerAchievementV1> {
+        self.find_all_progress(player_id)
+            .into_iter()
+            .filter(|row| row.unlocked_at.is_some())
+            .collect()
+    }
+
+    fn find_all_progress(&self, player_id: &Uuid) -> Vec<StdbPlayerAchievementV1> {
+        self.db
+            .stdb_player_achievement_v1()
+            .player_achievement_index()
+            .filter(player_id)
+            .collect()
+    }
+}
+
+/// Adds `delta` to `progress` without overflowing nor going past `required`.
+pub(crate) fn apply_progress(progress: u32, required: u32, delta: u32) -> u32 {
+    progress.saturating_add(delta).min(required)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_progress_below_required() {
+        assert_eq!(apply_progress(2, 10, 3), 5);
+    }
+
+    #[test]
+    fn test_apply_progress_capped_at_required() {
+        assert_eq!(apply_progress(8, 10, 5), 10);
+        assert_eq!(apply_progress(8, 10, u32::MAX), 10);
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{block::repository::BlockRepository, prelude::PlayerExt};
+use spacetimedb::{Filter, ReducerContext, Timestamp, client_visibility_filter, reducer, table};
+use stdb_common::prelude::{ServiceResult, Uuid};
+
+pub mod repository;
+
+pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+#[client_visibility_filter]
+const STDB_PLAYER_BLOCK_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select b.*
+    from stdb_player_block_v1 b
+    join stdb_own_player_session_v1 s
+        on s.player_id = b.blocker_id
+    where s.session_id = :sender
+"#,
+);
+
+/// Player block table - players someone doesn't want to hear from.
+///
+/// Only the blocker sees their rows, the blocked player is never told.
+#[table(
+    name = stdb_player_block_v1,
+    public,
+    index(name = blocker_blocked_index, btree(columns = [blocker_id, blocked_id])),
+)]
+#[derive(Debug, Clone)]
+pub struct StdbPlayerBlockV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub block_id: u64,
+
+    pub blocker_id: Uuid,
+    pub blocked_id: Uuid,
+
+    pub blocked_at: Timestamp,
+}
+
+#[reducer]
+pub fn block_player_v1(ctx: &ReducerContext, blocked_id: Uuid) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.block_player(session.player_id, blocked_id)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn unblock_player_v1(ctx: &ReducerContext, blocked_id: Uuid) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.unblock_player(&session.player_id, &blocked_id);
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::{
+    block::{StdbPlayerBlockV1, stdb_player_block_v1},
+    error::PlayerError,
+};
+use spacetimedb::{ReducerContext, Table};
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, validate_uuid};
+
+/// Repository trait for managing player blocks.
+pub trait BlockRepository {
+    /// Whether `blocker_id` blocked `blocked_id`.
+    fn is_blocked(&self, blocker_id: &Uuid, blocked_id: &Uuid) -> bool;
+
+    /// Blocks a player. Blocking an already blocked player returns the existing block.
+    ///
+    /// # Errors
+    /// Returns error if a player tries to block themselves, validation fails or database operations fail.
+    fn block_player(&self, blocker_id: Uuid, blocked_id: Uuid) -> ServiceResult<StdbPlayerBlockV1>;
+
+    /// Lifts a block. Returns `false` if the player wasn't blocked.
+    fn unblock_player(&self, blocker_id: &Uuid, blocked_id: &Uuid) -> bool;
+}
+
+impl BlockRepository for ReducerContext {
+    fn is_blocked(&self, blocker_id: &Uuid, blocked_id: &Uuid) -> bool {
+        find_block(self, blocker_id, blocked_id).is_some()
+    }
+
+    fn block_player(&self, blocker_id: Uuid, blocked_id: Uuid) -> ServiceResult<StdbPlayerBlockV1> {
+        validate_uuid("blocker_id", &blocker_id)?;
+        validate_uuid("blocked_id", &blocked_id)?;
+        if blocker_id == blocked_id {
+            return Err(PlayerError::cannot_block_self(blocker_id));
+        }
+
+        if let Some(block) = find_block(self, &blocker_id, &blocked_id) {
+            return Ok(block);
+        }
+
+        self.db
+            .stdb_player_block_v1()
+            .try_insert(StdbPlayerBlockV1 {
+                block_id: 0,
+                blocker_id,
+                blocked_id,
+                blocked_at: self.timestamp,
+            })
+            .map_conflict_ctx("failed to block player")
+    }
+
+    fn unblock_player(&self, blocker_id: &Uuid, blocked_id: &Uuid) -> bool {
+        match find_block(self, blocker_id, blocked_id) {
+            Some(block) => self.db.stdb_player_block_v1().block_id().delete(block.block_id),
+            None => false,
+        }
+    }
+}
+
+fn find_block(ctx: &ReducerContext, blocker_id: &Uuid, blocked_id: &Uuid) -> Option<StdbPlayerBlockV1> {
+    ctx.db
+        .stdb_player_block_v1()
+        .blocker_blocked_index()
+        .filter((blocker_id, blocked_id))
+        .next()
+}
@@ -0,0 +1,110 @@
+use crate::currency::repository::CurrencyRepository;
+use spacetimedb::{Filter, ReducerContext, SpacetimeType, Timestamp, client_visibility_filter, reducer, table};
+use stdb_common::prelude::{ServiceResult, Uuid, ValidateExt};
+
+pub mod repository;
+
+pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+#[client_visibility_filter]
+const STDB_PLAYER_CURRENCY_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select c.*
+    from stdb_player_currency_v1 c
+    join stdb_own_player_session_v1 s
+        on s.player_id = c.player_id
+    where s.session_id = :sender
+"#,
+);
+
+#[client_visibility_filter]
+const STDB_CURRENCY_TRANSACTION_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select t.*
+    from stdb_currency_transaction_v1 t
+    join stdb_own_player_session_v1 s
+        on s.player_id = t.player_id
+    where s.session_id = :sender
+"#,
+);
+
+/// Player currency table - current balances of a player, only visible to the owning player.
+#[table(name = stdb_player_currency_v1, public)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StdbPlayerCurrencyV1 {
+    #[primary_key]
+    pub player_id: Uuid,
+
+    pub soft_currency: u64,
+    pub hard_currency: u64,
+
+    pub updated_at: Timestamp,
+}
+
+impl StdbPlayerCurrencyV1 {
+    /// Balance of the given currency.
+    pub fn amount(&self, currency_type: CurrencyTypeV1) -> u64 {
+        match currency_type {
+            CurrencyTypeV1::Soft => self.soft_currency,
+            CurrencyTypeV1::Hard => self.hard_currency,
+        }
+    }
+
+    pub(crate) fn amount_mut(&mut self, currency_type: CurrencyTypeV1) -> &mut u64 {
+        match currency_type {
+            CurrencyTypeV1::Soft => &mut self.soft_currency,
+            CurrencyTypeV1::Hard => &mut self.hard_currency,
+        }
+    }
+}
+
+/// Currency transaction table - log of every grant and deduction, only visible to the owning player.
+#[table(name = stdb_currency_transaction_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbCurrencyTransactionV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub id: u64,
+
+    #[index(btree)]
+    pub player_id: Uuid,
+
+    pub currency_type: CurrencyTypeV1,
+
+    /// Positive for grants, negative for deductions.
+    pub delta: i64,
+
+    pub reason: String,
+
+    pub occurred_at: Timestamp,
+}
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, SpacetimeType)]
+pub enum CurrencyTypeV1 {
+    /// Earned by playing
+    Soft,
+
+    /// Usually bought with real money
+    Hard,
+}
+
+#[reducer]
+pub fn admin_grant_currency_v1(
+    ctx: &ReducerContext,
+    player_id: Uuid,
+    currency_type: CurrencyTypeV1,
+    amount: u64,
+    reason: String,
+) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.grant(player_id, currency_type, amount, reason)?;
+    Ok(())
+}
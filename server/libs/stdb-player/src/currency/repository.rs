@@ -0,0 +1,161 @@
+use crate::{
+    currency::{
+        CurrencyTypeV1, StdbCurrencyTransactionV1, StdbPlayerCurrencyV1, stdb_currency_transaction_v1, stdb_player_currency_v1,
+    },
+    error::PlayerError,
+};
+use spacetimedb::{ReducerContext, Table};
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, validate_str, validate_u64, validate_uuid};
+
+/// Repository trait for managing player currencies.
+///
+/// Every grant and deduction is logged as a transaction. Amounts are capped at `i64::MAX`
+/// so they always fit in the signed transaction delta.
+pub trait CurrencyRepository {
+    /// Returns the balances of a player, all zeros if they never had any currency.
+    fn balance(&self, player_id: &Uuid) -> StdbPlayerCurrencyV1;
+
+    /// Returns the currency transactions of a player.
+    fn find_currency_transactions(&self, player_id: &Uuid) -> Vec<StdbCurrencyTransactionV1>;
+
+    /// Adds currency to a player and logs the transaction. Balances saturate at `u64::MAX`.
+    ///
+    /// Validates reason (1-64 chars).
+    ///
+    /// # Errors
+    /// Returns error if validation fails or database operations fail.
+    fn grant(
+        &self,
+        player_id: Uuid,
+        currency_type: CurrencyTypeV1,
+        amount: u64,
+        reason: impl Into<String>,
+    ) -> ServiceResult<StdbPlayerCurrencyV1>;
+
+    /// Removes currency from a player and logs the transaction.
+    ///
+    /// Validates reason (1-64 chars).
+    ///
+    /// # Errors
+    /// Returns `ServiceError::BadRequest` if the player doesn't have enough currency,
+    /// or error if validation fails or database operations fail.
+    fn deduct(
+        &self,
+        player_id: Uuid,
+        currency_type: CurrencyTypeV1,
+        amount: u64,
+        reason: impl Into<String>,
+    ) -> ServiceResult<StdbPlayerCurrencyV1>;
+}
+
+impl CurrencyRepository for ReducerContext {
+    fn balance(&self, player_id: &Uuid) -> StdbPlayerCurrencyV1 {
+        self.db
+            .stdb_player_currency_v1()
+            .player_id()
+            .find(player_id)
+            .unwrap_or(StdbPlayerCurrencyV1 {
+                player_id: player_id.clone(),
+                soft_currency: 0,
+                hard_currency: 0,
+                updated_at: self.timestamp,
+            })
+    }
+
+    fn find_currency_transactions(&self, player_id: &Uuid) -> Vec<StdbCurrencyTransactionV1> {
+        self.db.stdb_currency_transaction_v1().player_id().filter(player_id).collect()
+    }
+
+    fn grant(
+        &self,
+        player_id: Uuid,
+        currency_type: CurrencyTypeV1,
+        amount: u64,
+        reason: impl Into<String>,
+    ) -> ServiceResult<StdbPlayerCurrencyV1> {
+        let reason = reason.into();
+        validate_uuid("player_id", &player_id)?;
+        validate_u64("amount", amount, 1, i64::MAX as u64)?;
+        validate_str("reason", &reason, 1, 64)?;
+
+        let mut balance = self.balance(&player_id);
+        let current = balance.amount_mut(currency_type);
+        *current = current.saturating_add(amount);
+
+        write_balance(self, balance, currency_type, amount as i64, reason)
+    }
+
+    fn deduct(
+        &self,
+        player_id: Uuid,
+        currency_type: CurrencyTypeV1,
+        amount: u64,
+        reason: impl Into<String>,
+    ) -> ServiceResult<StdbPlayerCurrencyV1> {
+        let reason = reason.into();
+        validate_uuid("player_id", &player_id)?;
+        validate_u64("amount", amount, 1, i64::MAX as u64)?;
+        validate_str("reason", &reason, 1, 64)?;
+
+        let mut balance = self.balance(&player_id);
+        let current = balance.amount_mut(currency_type);
+        let Some(remaining) = current.checked_sub(amount) else {
+            return Err(PlayerError::insufficient_funds(player_id));
+        };
+        *current = remaining;
+
+        write_balance(self, balance, currency_type, -(amount as i64), reason)
+    }
+}
+
+fn write_balance(
+    ctx: &ReducerContext,
+    mut balance: StdbPlayerCurrencyV1,
+    currency_type: CurrencyTypeV1,
+    delta: i64,
+    reason: String,
+) -> ServiceResult<StdbPlayerCurrencyV1> {
+    balance.updated_at = ctx.timestamp;
+    let balance = ctx
+        .db
+        .stdb_player_currency_v1()
+        .player_id()
+        .try_insert_or_update(balance)
+        .map_conflict_ctx("failed to update currency balance")?;
+
+    ctx.db
+        .stdb_currency_transaction_v1()
+        .try_insert(StdbCurrencyTransactionV1 {
+            id: 0,
+            player_id: balance.player_id.clone(),
+            currency_type,
+            delta,
+            reason,
+            occurred_at: ctx.timestamp,
+        })
+        .map_conflict_ctx("failed to insert currency transaction")?;
+
+    Ok(balance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spacetimedb::Timestamp;
+
+    #[test]
+    fn test_currency_amount_by_type() {
+        let mut balance = StdbPlayerCurrencyV1 {
+            player_id: "player".to_string(),
+            soft_currency: 10,
+            hard_currency: 2,
+            updated_at: Timestamp::UNIX_EPOCH,
+        };
+        assert_eq!(balance.amount(CurrencyTypeV1::Soft), 10);
+        assert_eq!(balance.amount(CurrencyTypeV1::Hard), 2);
+
+        *balance.amount_mut(CurrencyTypeV1::Hard) += 3;
+        assert_eq!(balance.hard_currency, 5);
+        assert_eq!(balance.soft_currency, 10);
+    }
+}
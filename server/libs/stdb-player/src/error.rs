@@ -1,3 +1,4 @@
+use spacetimedb::Identity;
 use stdb_common::prelude::{ErrorMapper, ServiceError, Uuid};
 use thiserror::Error;
 
@@ -5,10 +6,374 @@ use thiserror::Error;
 pub enum PlayerError {
     #[error("Player '{0}' not found")]
     PlayerNotFound(Uuid),
+
+    #[error("Display name '{0}' is already taken")]
+    DisplayNameTaken(String),
+
+    #[error("Leaderboard '{0}' not found")]
+    LeaderboardNotFound(Uuid),
+
+    #[error("Achievement '{0}' not found")]
+    AchievementNotFound(Uuid),
+
+    #[error("Daily reward already claimed by player '{0}'")]
+    DailyRewardAlreadyClaimed(Uuid),
+
+    #[error("Player '{0}' reached the maximum number of metadata entries")]
+    PlayerMetadataLimitReached(Uuid),
+
+    #[error("Notification '{0}' not found")]
+    NotificationNotFound(u64),
+
+    #[error("Invalid notification preferences: {0}")]
+    InvalidNotificationPrefs(String),
+
+    #[error("Player '{0}' doesn't accept VIP invites from this player")]
+    VipInvitesDisabled(Uuid),
+
+    #[error("No pending VIP invite from player '{0}'")]
+    VipInviteNotFound(Uuid),
+
+    #[error("Player '{0}' reached the maximum number of VIP friends")]
+    VipFriendLimitReached(Uuid),
+
+    #[error("Platform account '{0}' is already linked to another player")]
+    PlatformAccountAlreadyLinked(String),
+
+    #[error("Player '{0}' already has a different account linked on this platform")]
+    PlatformAlreadyLinked(Uuid),
+
+    #[error("Player '{0}' can't claim the platform account, it's linked to player '{1}'")]
+    AccountClaimedByAnotherPlayer(Uuid, Uuid),
+
+    #[error("Display name word list '{0}' is already registered")]
+    WordListAlreadyRegistered(String),
+
+    #[error("Valid game modes are already registered")]
+    GameModesAlreadyRegistered,
+
+    #[error("Account suspended: {0}")]
+    AccountSuspended(String),
+
+    #[error("Player '{0}' can't block themselves")]
+    CannotBlockSelf(Uuid),
+
+    #[error("Blocked by player '{0}'")]
+    BlockedByPlayer(Uuid),
+
+    #[error("Player '{0}' doesn't have enough currency")]
+    InsufficientFunds(Uuid),
+
+    #[error("Player '{0}' reached the maximum number of item types")]
+    InventoryLimitReached(Uuid),
+
+    #[error("Not enough items of type '{0}'")]
+    InsufficientItems(String),
+
+    #[error("Tournament '{0}' not found")]
+    TournamentNotFound(Uuid),
+
+    #[error("Tournament '{0}' no longer accepts registrations")]
+    TournamentClosed(Uuid),
+
+    #[error("Tournament '{0}' hasn't started yet")]
+    TournamentNotStarted(Uuid),
+
+    #[error("Tournament '{0}' is full")]
+    TournamentFull(Uuid),
+
+    #[error("Player '{0}' is already registered in the tournament")]
+    AlreadyInTournament(Uuid),
+
+    #[error("Tournament match '{0}' not found")]
+    TournamentMatchNotFound(u64),
+
+    #[error("Invalid result for tournament match '{0}'")]
+    InvalidMatchResult(u64),
+
+    #[error("Party '{0}' not found")]
+    PartyNotFound(Uuid),
+
+    #[error("Party '{0}' is full")]
+    PartyFull(Uuid),
+
+    #[error("Player '{0}' is already in a party")]
+    AlreadyInParty(Uuid),
+
+    #[error("Player '{0}' is not in a party")]
+    NotInParty(Uuid),
+
+    #[error("Only the leader can manage party '{0}'")]
+    NotPartyLeader(Uuid),
+
+    #[error("No pending invite to party '{0}'")]
+    PartyInviteNotFound(Uuid),
+
+    #[error("Event '{0}' not found")]
+    EventNotFound(Uuid),
+
+    #[error("Event '{0}' is not active")]
+    EventNotActive(Uuid),
+
+    #[error("Event must end after it starts")]
+    InvalidEventWindow,
+
+    #[error("Player '{0}' is already in the matchmaking queue")]
+    AlreadyInQueue(Uuid),
+
+    #[error("Player '{0}' is not in the matchmaking queue")]
+    NotInQueue(Uuid),
+
+    #[error("Player '{0}' was already matched")]
+    AlreadyMatched(Uuid),
+
+    #[error("Session '{0}' expired, sign in again")]
+    SessionExpired(Identity),
+
+    #[error("Account '{0}' is deactivated")]
+    AccountDeactivated(Uuid),
+
+    #[error("Purging player '{0}' must be confirmed with their ID")]
+    PurgeNotConfirmed(Uuid),
+
+    #[error("Invite link '{0}' not found")]
+    InviteLinkNotFound(String),
+
+    #[error("Invite link '{0}' expired")]
+    InviteLinkExpired(String),
+
+    #[error("Players can't redeem their own invite link")]
+    OwnInviteLink,
+
+    #[error("Player '{0}' reached the maximum number of invite links")]
+    InviteLinkLimitReached(Uuid),
+
+    #[error("Players can't refer themselves")]
+    SelfReferral,
+
+    #[error("Player '{0}' reached the maximum number of referrals")]
+    ReferralLimitReached(Uuid),
+
+    #[error("Referral '{0}' not found")]
+    ReferralNotFound(u64),
+
+    #[error("Reward of referral '{0}' was already granted")]
+    ReferralRewardAlreadyGranted(u64),
+
+    #[error("Player '{0}' was already referred")]
+    AlreadyReferred(Uuid),
 }
 
 impl PlayerError {
     pub fn player_not_found(uuid: Uuid) -> ServiceError {
         Self::PlayerNotFound(uuid).map_validation()
     }
+
+    pub fn display_name_taken(display_name: impl Into<String>) -> ServiceError {
+        Self::DisplayNameTaken(display_name.into()).map_conflict()
+    }
+
+    pub fn leaderboard_not_found(uuid: Uuid) -> ServiceError {
+        Self::LeaderboardNotFound(uuid).map_not_found()
+    }
+
+    pub fn achievement_not_found(uuid: Uuid) -> ServiceError {
+        Self::AchievementNotFound(uuid).map_not_found()
+    }
+
+    pub fn daily_reward_already_claimed(uuid: Uuid) -> ServiceError {
+        Self::DailyRewardAlreadyClaimed(uuid).map_rate_limited()
+    }
+
+    pub fn player_metadata_limit_reached(uuid: Uuid) -> ServiceError {
+        Self::PlayerMetadataLimitReached(uuid).map_validation()
+    }
+
+    pub fn notification_not_found(notification_id: u64) -> ServiceError {
+        Self::NotificationNotFound(notification_id).map_not_found()
+    }
+
+    pub fn invalid_notification_prefs(reason: impl Into<String>) -> ServiceError {
+        Self::InvalidNotificationPrefs(reason.into()).map_validation()
+    }
+
+    pub fn vip_invites_disabled(uuid: Uuid) -> ServiceError {
+        Self::VipInvitesDisabled(uuid).map_forbidden()
+    }
+
+    pub fn vip_invite_not_found(uuid: Uuid) -> ServiceError {
+        Self::VipInviteNotFound(uuid).map_not_found()
+    }
+
+    pub fn vip_friend_limit_reached(uuid: Uuid) -> ServiceError {
+        Self::VipFriendLimitReached(uuid).map_validation()
+    }
+
+    pub fn platform_account_already_linked(platform_user_id: impl Into<String>) -> ServiceError {
+        Self::PlatformAccountAlreadyLinked(platform_user_id.into()).map_conflict()
+    }
+
+    pub fn platform_already_linked(uuid: Uuid) -> ServiceError {
+        Self::PlatformAlreadyLinked(uuid).map_conflict()
+    }
+
+    pub fn account_claimed_by_another_player(player_id: Uuid, linked_player_id: Uuid) -> ServiceError {
+        Self::AccountClaimedByAnotherPlayer(player_id, linked_player_id).map_conflict()
+    }
+
+    pub fn word_list_already_registered(name: impl Into<String>) -> ServiceError {
+        Self::WordListAlreadyRegistered(name.into()).map_conflict()
+    }
+
+    pub fn game_modes_already_registered() -> ServiceError {
+        Self::GameModesAlreadyRegistered.map_conflict()
+    }
+
+    pub fn account_suspended(reason: impl Into<String>) -> ServiceError {
+        Self::AccountSuspended(reason.into()).map_forbidden()
+    }
+
+    pub fn cannot_block_self(uuid: Uuid) -> ServiceError {
+        Self::CannotBlockSelf(uuid).map_validation()
+    }
+
+    pub fn blocked_by_player(uuid: Uuid) -> ServiceError {
+        Self::BlockedByPlayer(uuid).map_forbidden()
+    }
+
+    pub fn insufficient_funds(uuid: Uuid) -> ServiceError {
+        Self::InsufficientFunds(uuid).map_bad_request()
+    }
+
+    pub fn inventory_limit_reached(uuid: Uuid) -> ServiceError {
+        Self::InventoryLimitReached(uuid).map_validation()
+    }
+
+    pub fn party_not_found(uuid: Uuid) -> ServiceError {
+        Self::PartyNotFound(uuid).map_not_found()
+    }
+
+    pub fn party_full(uuid: Uuid) -> ServiceError {
+        Self::PartyFull(uuid).map_validation()
+    }
+
+    pub fn already_in_party(uuid: Uuid) -> ServiceError {
+        Self::AlreadyInParty(uuid).map_conflict()
+    }
+
+    pub fn not_in_party(uuid: Uuid) -> ServiceError {
+        Self::NotInParty(uuid).map_not_found()
+    }
+
+    pub fn not_party_leader(uuid: Uuid) -> ServiceError {
+        Self::NotPartyLeader(uuid).map_forbidden()
+    }
+
+    pub fn party_invite_not_found(uuid: Uuid) -> ServiceError {
+        Self::PartyInviteNotFound(uuid).map_not_found()
+    }
+
+    pub fn tournament_not_found(uuid: Uuid) -> ServiceError {
+        Self::TournamentNotFound(uuid).map_not_found()
+    }
+
+    pub fn tournament_closed(uuid: Uuid) -> ServiceError {
+        Self::TournamentClosed(uuid).map_forbidden()
+    }
+
+    pub fn tournament_not_started(uuid: Uuid) -> ServiceError {
+        Self::TournamentNotStarted(uuid).map_forbidden()
+    }
+
+    pub fn tournament_full(uuid: Uuid) -> ServiceError {
+        Self::TournamentFull(uuid).map_validation()
+    }
+
+    pub fn already_in_tournament(uuid: Uuid) -> ServiceError {
+        Self::AlreadyInTournament(uuid).map_conflict()
+    }
+
+    pub fn tournament_match_not_found(match_id: u64) -> ServiceError {
+        Self::TournamentMatchNotFound(match_id).map_not_found()
+    }
+
+    pub fn invalid_match_result(match_id: u64) -> ServiceError {
+        Self::InvalidMatchResult(match_id).map_validation()
+    }
+
+    pub fn insufficient_items(item_type_id: impl Into<String>) -> ServiceError {
+        Self::InsufficientItems(item_type_id.into()).map_bad_request()
+    }
+
+    pub fn event_not_found(uuid: Uuid) -> ServiceError {
+        Self::EventNotFound(uuid).map_not_found()
+    }
+
+    pub fn event_not_active(uuid: Uuid) -> ServiceError {
+        Self::EventNotActive(uuid).map_forbidden()
+    }
+
+    pub fn invalid_event_window() -> ServiceError {
+        Self::InvalidEventWindow.map_validation()
+    }
+
+    pub fn already_in_queue(uuid: Uuid) -> ServiceError {
+        Self::AlreadyInQueue(uuid).map_conflict()
+    }
+
+    pub fn not_in_queue(uuid: Uuid) -> ServiceError {
+        Self::NotInQueue(uuid).map_not_found()
+    }
+
+    pub fn already_matched(uuid: Uuid) -> ServiceError {
+        Self::AlreadyMatched(uuid).map_conflict()
+    }
+
+    pub fn session_expired(session_id: Identity) -> ServiceError {
+        Self::SessionExpired(session_id).map_unauthorized()
+    }
+
+    pub fn account_deactivated(uuid: Uuid) -> ServiceError {
+        Self::AccountDeactivated(uuid).map_forbidden()
+    }
+
+    pub fn purge_not_confirmed(uuid: Uuid) -> ServiceError {
+        Self::PurgeNotConfirmed(uuid).map_validation()
+    }
+
+    pub fn invite_link_not_found(code: impl Into<String>) -> ServiceError {
+        Self::InviteLinkNotFound(code.into()).map_not_found()
+    }
+
+    pub fn invite_link_expired(code: impl Into<String>) -> ServiceError {
+        Self::InviteLinkExpired(code.into()).map_forbidden()
+    }
+
+    pub fn own_invite_link() -> ServiceError {
+        Self::OwnInviteLink.map_validation()
+    }
+
+    pub fn invite_link_limit_reached(uuid: Uuid) -> ServiceError {
+        Self::InviteLinkLimitReached(uuid).map_validation()
+    }
+
+    pub fn self_referral() -> ServiceError {
+        Self::SelfReferral.map_validation()
+    }
+
+    pub fn referral_limit_reached(uuid: Uuid) -> ServiceError {
+        Self::ReferralLimitReached(uuid).map_validation()
+    }
+
+    pub fn referral_not_found(referral_id: u64) -> ServiceError {
+        Self::ReferralNotFound(referral_id).map_not_found()
+    }
+
+    pub fn referral_reward_already_granted(referral_id: u64) -> ServiceError {
+        Self::ReferralRewardAlreadyGranted(referral_id).map_conflict()
+    }
+
+    pub fn already_referred(uuid: Uuid) -> ServiceError {
+        Self::AlreadyReferred(uuid).map_conflict()
+    }
 }
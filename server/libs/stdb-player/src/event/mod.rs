@@ -0,0 +1,53 @@
+use crate::event::repository::EventRepository;
+use spacetimedb::{ReducerContext, Timestamp, reducer, table};
+use stdb_common::prelude::{ServiceResult, Uuid, ValidateExt};
+
+pub mod repository;
+
+pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+/// Seasonal event table - limited-time events, visible to all players.
+///
+/// An event is active from `starts_at` (inclusive) until `ends_at` (exclusive).
+#[table(name = stdb_seasonal_event_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbSeasonalEventV1 {
+    #[primary_key]
+    pub event_id: Uuid,
+
+    pub name: String,
+
+    pub starts_at: Timestamp,
+    pub ends_at: Timestamp,
+
+    /// Game specific data of the event, e.g. JSON with the event rules.
+    pub metadata: String,
+}
+
+#[reducer]
+pub fn create_event_v1(
+    ctx: &ReducerContext,
+    name: String,
+    starts_at: Timestamp,
+    ends_at: Timestamp,
+    metadata: String,
+) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.create_event(name, starts_at, ends_at, metadata)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn end_event_v1(ctx: &ReducerContext, event_id: Uuid) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.end_event(&event_id)?;
+    Ok(())
+}
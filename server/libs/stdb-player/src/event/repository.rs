@@ -0,0 +1,110 @@
+use crate::{
+    error::PlayerError,
+    event::{StdbSeasonalEventV1, stdb_seasonal_event_v1},
+};
+use spacetimedb::{ReducerContext, Table, Timestamp};
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, UuidExt, validate_str, validate_uuid};
+
+/// Repository trait for managing seasonal events.
+pub trait EventRepository {
+    fn find_event(&self, event_id: &Uuid) -> Option<StdbSeasonalEventV1>;
+
+    /// Whether the event exists and the current timestamp is within its window.
+    fn is_event_active(&self, event_id: &Uuid) -> bool;
+
+    /// Creates an event running from `starts_at` until `ends_at`.
+    ///
+    /// Validates name (1-64 chars), metadata (up to 4096 chars) and that the event ends after it starts.
+    ///
+    /// # Errors
+    /// Returns error if validation fails or database operations fail.
+    fn create_event(
+        &self,
+        name: impl Into<String>,
+        starts_at: Timestamp,
+        ends_at: Timestamp,
+        metadata: impl Into<String>,
+    ) -> ServiceResult<StdbSeasonalEventV1>;
+
+    /// Ends an event now. Events that already ended are left untouched.
+    ///
+    /// # Errors
+    /// Returns error if the event doesn't exist or database operations fail.
+    fn end_event(&self, event_id: &Uuid) -> ServiceResult<StdbSeasonalEventV1>;
+}
+
+impl EventRepository for ReducerContext {
+    fn find_event(&self, event_id: &Uuid) -> Option<StdbSeasonalEventV1> {
+        self.db.stdb_seasonal_event_v1().event_id().find(event_id)
+    }
+
+    fn is_event_active(&self, event_id: &Uuid) -> bool {
+        self.find_event(event_id)
+            .is_some_and(|event| is_within_window(event.starts_at, event.ends_at, self.timestamp))
+    }
+
+    fn create_event(
+        &self,
+        name: impl Into<String>,
+        starts_at: Timestamp,
+        ends_at: Timestamp,
+        metadata: impl Into<String>,
+    ) -> ServiceResult<StdbSeasonalEventV1> {
+        let name = name.into();
+        let metadata = metadata.into();
+        validate_str("name", &name, 1, 64)?;
+        validate_str("metadata", &metadata, 0, 4096)?;
+        if ends_at <= starts_at {
+            return Err(PlayerError::invalid_event_window());
+        }
+
+        self.db
+            .stdb_seasonal_event_v1()
+            .try_insert(StdbSeasonalEventV1 {
+                event_id: self.new_uuid_v7(),
+                name,
+                starts_at,
+                ends_at,
+                metadata,
+            })
+            .map_conflict_ctx("failed to insert seasonal event")
+    }
+
+    fn end_event(&self, event_id: &Uuid) -> ServiceResult<StdbSeasonalEventV1> {
+        validate_uuid("event_id", event_id)?;
+        let Some(mut event) = self.find_event(event_id) else {
+            return Err(PlayerError::event_not_found(event_id.clone()));
+        };
+        if event.ends_at <= self.timestamp {
+            return Ok(event);
+        }
+
+        event.ends_at = self.timestamp;
+        self.db
+            .stdb_seasonal_event_v1()
+            .event_id()
+            .try_insert_or_update(event)
+            .map_conflict_ctx("failed to end seasonal event")
+    }
+}
+
+pub(crate) fn is_within_window(starts_at: Timestamp, ends_at: Timestamp, now: Timestamp) -> bool {
+    starts_at <= now && now < ends_at
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_within_window() {
+        let starts_at = Timestamp::from_micros_since_unix_epoch(1_000);
+        let ends_at = Timestamp::from_micros_since_unix_epoch(2_000);
+        let at = Timestamp::from_micros_since_unix_epoch;
+
+        assert!(!is_within_window(starts_at, ends_at, at(999)));
+        assert!(is_within_window(starts_at, ends_at, at(1_000)));
+        assert!(is_within_window(starts_at, ends_at, at(1_999)));
+        assert!(!is_within_window(starts_at, ends_at, at(2_000)));
+    }
+}
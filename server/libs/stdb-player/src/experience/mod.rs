@@ -0,0 +1,65 @@
+use crate::experience::repository::ExperienceRepository;
+use spacetimedb::{Filter, ReducerContext, Timestamp, client_visibility_filter, reducer, table};
+use stdb_common::prelude::{ServiceResult, Uuid, ValidateExt};
+
+pub mod repository;
+
+pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+#[client_visibility_filter]
+const STDB_XP_TRANSACTION_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select t.*
+    from stdb_xp_transaction_v1 t
+    join stdb_own_player_session_v1 s
+        on s.player_id = t.player_id
+    where s.session_id = :sender
+"#,
+);
+
+/// Player XP table - total experience and the level derived from it.
+///
+/// Public so other players can see each other's level.
+/// The level is computed with [`stdb_common::progression::calculate_level`].
+#[table(name = stdb_player_xp_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbPlayerXpV1 {
+    #[primary_key]
+    pub player_id: Uuid,
+
+    pub xp: u64,
+    pub level: u32,
+
+    pub updated_at: Timestamp,
+}
+
+/// XP transaction table - log of every XP grant, only visible to the owning player.
+#[table(name = stdb_xp_transaction_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbXpTransactionV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub transaction_id: u64,
+
+    #[index(btree)]
+    pub player_id: Uuid,
+
+    pub amount: u64,
+
+    pub occurred_at: Timestamp,
+}
+
+#[reducer]
+pub fn grant_xp_v1(ctx: &ReducerContext, player_id: Uuid, amount: u64) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.grant_xp(player_id, amount)?;
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use crate::experience::{StdbPlayerXpV1, StdbXpTransactionV1, stdb_player_xp_v1, stdb_xp_transaction_v1};
+use spacetimedb::{ReducerContext, Table};
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, calculate_level, validate_u64, validate_uuid};
+
+/// Repository trait for managing player experience points.
+///
+/// Every grant recalculates the player's level and is logged as a transaction.
+pub trait ExperienceRepository {
+    /// Finds the XP of a player.
+    ///
+    /// Returns `None` if the player was never granted XP.
+    fn find_player_xp(&self, player_id: &Uuid) -> Option<StdbPlayerXpV1>;
+
+    /// Returns the XP transactions of a player.
+    fn find_xp_transactions(&self, player_id: &Uuid) -> Vec<StdbXpTransactionV1>;
+
+    /// Grants XP to a player, recalculates their level and logs the transaction.
+    ///
+    /// XP saturates at `u64::MAX` instead of overflowing.
+    ///
+    /// # Errors
+    /// Returns error if validation fails or database operations fail.
+    fn grant_xp(&self, player_id: Uuid, amount: u64) -> ServiceResult<StdbPlayerXpV1>;
+}
+
+impl ExperienceRepository for ReducerContext {
+    fn find_player_xp(&self, player_id: &Uuid) -> Option<StdbPlayerXpV1> {
+        self.db.stdb_player_xp_v1().player_id().find(player_id)
+    }
+
+    fn find_xp_transactions(&self, player_id: &Uuid) -> Vec<StdbXpTransactionV1> {
+        self.db.stdb_xp_transaction_v1().player_id().filter(player_id).collect()
+    }
+
+    fn grant_xp(&self, player_id: Uuid, amount: u64) -> ServiceResult<StdbPlayerXpV1> {
+        validate_uuid("player_id", &player_id)?;
+        validate_u64("amount", amount, 1, u64::MAX)?;
+
+        let mut player_xp = self.find_player_xp(&player_id).unwrap_or(StdbPlayerXpV1 {
+            player_id: player_id.clone(),
+            xp: 0,
+            level: 0,
+            updated_at: self.timestamp,
+        });
+        player_xp.xp = player_xp.xp.saturating_add(amount);
+        player_xp.level = calculate_level(player_xp.xp);
+        player_xp.updated_at = self.timestamp;
+
+        let player_xp = self
+            .db
+            .stdb_player_xp_v1()
+            .player_id()
+            .try_insert_or_update(player_xp)
+            .map_conflict_ctx("failed to grant xp")?;
+
+        self.db
+            .stdb_xp_transaction_v1()
+            .try_insert(StdbXpTransactionV1 {
+                transaction_id: 0,
+                player_id,
+                amount,
+                occurred_at: self.timestamp,
+            })
+            .map_conflict_ctx("failed to insert xp transaction")?;
+
+        Ok(player_xp)
+    }
+}
@@ -0,0 +1,28 @@
+//! Rows and values shared by the unit tests.
+
+use crate::player::{DeviceTypeV1, StdbOwnPlayerSessionV1};
+use spacetimedb::{Identity, Timestamp};
+use stdb_common::prelude::Uuid;
+
+pub(crate) const PLAYER_ID: &str = "01890a5d-ac96-774b-bcce-b302099a8057";
+pub(crate) const OTHER_PLAYER_ID: &str = "01890a5d-ac96-774b-bcce-b302099a8058";
+
+pub(crate) fn uuid(id: &str) -> Uuid {
+    id.to_string()
+}
+
+/// `secs` seconds after the Unix epoch.
+pub(crate) fn at(secs: i64) -> Timestamp {
+    Timestamp::from_micros_since_unix_epoch(secs * 1_000_000)
+}
+
+pub(crate) fn session(index: u64, player_id: &str, is_online: bool) -> StdbOwnPlayerSessionV1 {
+    StdbOwnPlayerSessionV1 {
+        session_id: Identity::from_u256(index.into()),
+        player_id: uuid(player_id),
+        is_online,
+        device_type: DeviceTypeV1::Unknown,
+        client_version: "1.0.0".to_string(),
+        expired_at: None,
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{error::PlayerError, gdpr::repository::GdprRepository, prelude::PlayerExt};
+use log::info;
+use spacetimedb::{Filter, ReducerContext, Timestamp, client_visibility_filter, reducer, table};
+use std::time::Duration;
+use stdb_common::prelude::{ServiceResult, Uuid, ValidateExt};
+
+pub mod repository;
+
+pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+/// Minimum time between two data exports requested by the same player (30 days).
+pub const DATA_EXPORT_COOLDOWN: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+#[client_visibility_filter]
+const STDB_DATA_EXPORT_REQUEST_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select e.*
+    from stdb_data_export_request_v1 e
+    join stdb_own_player_session_v1 s
+        on s.player_id = e.player_id
+    where s.session_id = :sender
+"#,
+);
+
+/// Data export request table - copies of a player's data they requested, only visible to the owning player.
+#[table(name = stdb_data_export_request_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbDataExportRequestV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub request_id: u64,
+
+    #[index(btree)]
+    pub player_id: Uuid,
+
+    pub requested_at: Timestamp,
+    pub completed_at: Option<Timestamp>,
+
+    /// Player record, sessions, sent VIP invites, notifications and display name history as JSON.
+    pub export_json: String,
+}
+
+/// Exports the data of the current player, at most once every [`DATA_EXPORT_COOLDOWN`].
+#[reducer]
+pub fn export_my_data_v1(ctx: &ReducerContext) -> ServiceResult<()> {
+    let session = ctx.require_rate_limit("export_my_data", DATA_EXPORT_COOLDOWN)?;
+    ctx.export_player_data(&session.player_id)?;
+    Ok(())
+}
+
+/// Permanently deletes all the data of a player.
+///
+/// `confirmation` must repeat the player ID, to avoid purging the wrong player by accident.
+#[reducer]
+pub fn purge_player_data_v1(ctx: &ReducerContext, player_id: Uuid, confirmation: String) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    if confirmation != player_id {
+        return Err(PlayerError::purge_not_confirmed(player_id));
+    }
+
+    let deleted = ctx.purge_player_data(&player_id)?;
+    info!(
+        "stdb-player: purged player {}: {}",
+        player_id,
+        serde_json::to_string(&deleted).unwrap_or_default()
+    );
+    Ok(())
+}
@@ -0,0 +1,406 @@
+use crate::{
+    error::PlayerError,
+    gdpr::{StdbDataExportRequestV1, stdb_data_export_request_v1},
+    notification::{repository::NotificationRepository, stdb_notification_prefs_v1, stdb_notification_v1},
+    player::{
+        rank::stdb_player_rank_v1,
+        repository::{PlayerRepository, PlayerSessionRepository},
+        stdb_display_name_history_v1, stdb_display_name_tombstone_v1, stdb_own_player_session_v1, stdb_own_player_v1,
+        stdb_player_metadata_v1, stdb_player_settings_v1, stdb_pub_player_card_v1, stdb_referral_v1, stdb_session_heartbeat_v1,
+    },
+};
+use serde_json::{Value, json};
+use spacetimedb::{ReducerContext, Table, Timestamp};
+use std::collections::HashMap;
+use stdb_common::{
+    cooldown::stdb_cooldown_v1,
+    prelude::{ResultExt, ServiceResult, Uuid, validate_uuid},
+    rate_limiter::stdb_rate_limiter_bucket_v1,
+};
+
+/// Repository trait for the data protection requests of players.
+pub trait GdprRepository {
+    /// Returns the data exports requested by a player.
+    fn find_data_exports(&self, player_id: &Uuid) -> Vec<StdbDataExportRequestV1>;
+
+    /// Gathers the data of a player as JSON and stores it as a completed export.
+    ///
+    /// # Errors
+    /// Returns error if the player doesn't exist or database operations fail.
+    fn export_player_data(&self, player_id: &Uuid) -> ServiceResult<StdbDataExportRequestV1>;
+
+    /// Permanently deletes every row keyed by the player, in every table of the enabled features.
+    ///
+    /// VIP connections, blocks and referrals are deleted on both sides. Returns the number of deleted rows per table,
+    /// tables without rows of the player are left out.
+    ///
+    /// # Errors
+    /// Returns error if validation fails.
+    fn purge_player_data(&self, player_id: &Uuid) -> ServiceResult<HashMap<String, u32>>;
+}
+
+impl GdprRepository for ReducerContext {
+    fn find_data_exports(&self, player_id: &Uuid) -> Vec<StdbDataExportRequestV1> {
+        self.db.stdb_data_export_request_v1().player_id().filter(player_id).collect()
+    }
+
+    fn export_player_data(&self, player_id: &Uuid) -> ServiceResult<StdbDataExportRequestV1> {
+        let Some(player) = self.find_player(player_id) else {
+            return Err(PlayerError::player_not_found(player_id.clone()));
+        };
+
+        let sessions: Vec<_> = self
+            .find_all_sessions_for_player(player_id)
+            .into_iter()
+            .map(|session| {
+                json!({
+                    "session_id": session.session_id.to_string(),
+                    "is_online": session.is_online,
+                    "device_type": format!("{:?}", session.device_type),
+                    "client_version": session.client_version,
+                    "expired_at": session.expired_at.map(timestamp_json),
+                })
+            })
+            .collect();
+
+        let notifications: Vec<_> = self
+            .find_notifications_for_player(player_id)
+            .into_iter()
+            .map(|notification| {
+                json!({
+                    "notification_id": notification.notification_id,
+                    "kind": format!("{:?}", notification.kind),
+                    "payload": notification.payload,
+                    "created_at": timestamp_json(notification.created_at),
+                    "read_at": notification.read_at.map(timestamp_json),
+                })
+            })
+            .collect();
+
+        let display_name_history: Vec<_> = self
+            .find_display_name_history(player_id)
+            .into_iter()
+            .map(|entry| {
+                json!({
+                    "old_name": entry.old_name,
+                    "changed_at": timestamp_json(entry.changed_at),
+                })
+            })
+            .collect();
+
+        let export = json!({
+            "player": {
+                "player_id": player.player_id,
+                "display_name": player.display_name,
+                "avatar": player.avatar,
+                "bio": player.bio,
+                "locale": player.locale,
+                "created_at": timestamp_json(player.created_at),
+                "signed_in_at": timestamp_json(player.signed_in_at),
+                "last_signed_out_at": timestamp_json(player.last_signed_out_at),
+                "login_streak": player.login_streak,
+                "total_sessions": player.total_sessions,
+                "total_play_time_secs": player.total_play_time_secs,
+                "deleted_at": player.deleted_at.map(timestamp_json),
+                "referred_by": player.referred_by,
+            },
+            "sessions": sessions,
+            "vips": vips_json(self, player_id),
+            "notifications": notifications,
+            "display_name_history": display_name_history,
+        });
+
+        self.db
+            .stdb_data_export_request_v1()
+            .try_insert(StdbDataExportRequestV1 {
+                request_id: 0,
+                player_id: player_id.clone(),
+                requested_at: self.timestamp,
+                completed_at: Some(self.timestamp),
+                export_json: export.to_string(),
+            })
+            .map_conflict_ctx("failed to insert data export")
+    }
+
+    fn purge_player_data(&self, player_id: &Uuid) -> ServiceResult<HashMap<String, u32>> {
+        validate_uuid("player_id", player_id)?;
+        let mut deleted = HashMap::new();
+        let mut record = |table: &str, count: u64| {
+            if count > 0 {
+                deleted.insert(table.to_string(), count.min(u32::MAX as u64) as u32);
+            }
+        };
+
+        let db = &self.db;
+        let sessions = self.find_all_sessions_for_player(player_id);
+        let heartbeats = sessions
+            .iter()
+            .filter(|session| db.stdb_session_heartbeat_v1().session_id().delete(session.session_id))
+            .count();
+        record("stdb_session_heartbeat_v1", heartbeats as u64);
+        record(
+            "stdb_own_player_session_v1",
+            db.stdb_own_player_session_v1().player_id().delete(player_id),
+        );
+        record(
+            "stdb_own_player_v1",
+            db.stdb_own_player_v1().player_id().delete(player_id).into(),
+        );
+        record(
+            "stdb_pub_player_card_v1",
+            db.stdb_pub_player_card_v1().player_id().delete(player_id).into(),
+        );
+        record(
+            "stdb_player_settings_v1",
+            db.stdb_player_settings_v1().player_id().delete(player_id).into(),
+        );
+        record(
+            "stdb_player_metadata_v1",
+            db.stdb_player_metadata_v1().player_id().delete(player_id),
+        );
+        record(
+            "stdb_display_name_history_v1",
+            db.stdb_display_name_history_v1().player_id().delete(player_id),
+        );
+        record(
+            "stdb_display_name_tombstone_v1",
+            db.stdb_display_name_tombstone_v1().player_id().delete(player_id),
+        );
+        record(
+            "stdb_player_rank_v1",
+            db.stdb_player_rank_v1().player_id().delete(player_id).into(),
+        );
+        let referrals = db.stdb_referral_v1().referrer_id().delete(player_id);
+        let referred = db.stdb_referral_v1().referred_id().delete(player_id);
+        record("stdb_referral_v1", referrals + u64::from(referred));
+        record(
+            "stdb_notification_v1",
+            db.stdb_notification_v1().player_id().delete(player_id),
+        );
+        record(
+            "stdb_notification_prefs_v1",
+            db.stdb_notification_prefs_v1().player_id().delete(player_id).into(),
+        );
+        record(
+            "stdb_data_export_request_v1",
+            db.stdb_data_export_request_v1().player_id().delete(player_id),
+        );
+        record(
+            "stdb_cooldown_v1",
+            db.stdb_cooldown_v1().player_action_index().delete(player_id),
+        );
+        record(
+            "stdb_rate_limiter_bucket_v1",
+            db.stdb_rate_limiter_bucket_v1().player_action_index().delete(player_id),
+        );
+
+        #[cfg(feature = "vip")]
+        {
+            use crate::vip::{stdb_own_vip_v1, stdb_player_privacy_v1};
+            let sent = db.stdb_own_vip_v1().sender_id().delete(player_id);
+            let received = db.stdb_own_vip_v1().receiver_id().delete(player_id);
+            record("stdb_own_vip_v1", sent + received);
+            record(
+                "stdb_player_privacy_v1",
+                db.stdb_player_privacy_v1().player_id().delete(player_id).into(),
+            );
+        }
+
+        #[cfg(feature = "leaderboard")]
+        {
+            use crate::leaderboard::{stdb_leaderboard_entry_v1, stdb_leaderboard_snapshot_v1};
+            record(
+                "stdb_leaderboard_entry_v1",
+                delete_where(db.stdb_leaderboard_entry_v1(), |entry| entry.player_id == *player_id),
+            );
+            record(
+                "stdb_leaderboard_snapshot_v1",
+                delete_where(db.stdb_leaderboard_snapshot_v1(), |snapshot| snapshot.player_id == *player_id),
+            );
+        }
+
+        #[cfg(feature = "achievement")]
+        {
+            use crate::achievement::stdb_player_achievement_v1;
+            record(
+                "stdb_player_achievement_v1",
+                db.stdb_player_achievement_v1().player_achievement_index().delete(player_id),
+            );
+        }
+
+        #[cfg(feature = "reward")]
+        {
+            use crate::reward::stdb_daily_reward_v1;
+            record(
+                "stdb_daily_reward_v1",
+                db.stdb_daily_reward_v1().player_id().delete(player_id).into(),
+            );
+        }
+
+        #[cfg(feature = "experience")]
+        {
+            use crate::experience::{stdb_player_xp_v1, stdb_xp_transaction_v1};
+            record(
+                "stdb_player_xp_v1",
+                db.stdb_player_xp_v1().player_id().delete(player_id).into(),
+            );
+            record(
+                "stdb_xp_transaction_v1",
+                db.stdb_xp_transaction_v1().player_id().delete(player_id),
+            );
+        }
+
+        #[cfg(feature = "platform")]
+        {
+            use crate::platform::stdb_platform_auth_v1;
+            record(
+                "stdb_platform_auth_v1",
+                db.stdb_platform_auth_v1().player_platform_index().delete(player_id),
+            );
+        }
+
+        #[cfg(feature = "moderation")]
+        {
+            use crate::moderation::stdb_player_ban_v1;
+            record(
+                "stdb_player_ban_v1",
+                db.stdb_player_ban_v1().player_id().delete(player_id).into(),
+            );
+        }
+
+        #[cfg(feature = "block")]
+        {
+            use crate::block::stdb_player_block_v1;
+            let blocking = db.stdb_player_block_v1().blocker_blocked_index().delete(player_id);
+            let blocked_by = delete_where(db.stdb_player_block_v1(), |block| block.blocked_id == *player_id);
+            record("stdb_player_block_v1", blocking + blocked_by);
+        }
+
+        #[cfg(feature = "currency")]
+        {
+            use crate::currency::{stdb_currency_transaction_v1, stdb_player_currency_v1};
+            record(
+                "stdb_player_currency_v1",
+                db.stdb_player_currency_v1().player_id().delete(player_id).into(),
+            );
+            record(
+                "stdb_currency_transaction_v1",
+                db.stdb_currency_transaction_v1().player_id().delete(player_id),
+            );
+        }
+
+        #[cfg(feature = "inventory")]
+        {
+            use crate::inventory::stdb_inventory_item_v1;
+            record(
+                "stdb_inventory_item_v1",
+                db.stdb_inventory_item_v1().player_id().delete(player_id),
+            );
+        }
+
+        #[cfg(feature = "matchmaking")]
+        {
+            use crate::matchmaking::stdb_matchmaking_queue_v1;
+            record(
+                "stdb_matchmaking_queue_v1",
+                db.stdb_matchmaking_queue_v1().player_id().delete(player_id).into(),
+            );
+        }
+
+        #[cfg(feature = "tournament")]
+        {
+            use crate::tournament::stdb_tournament_participant_v1;
+            record(
+                "stdb_tournament_participant_v1",
+                db.stdb_tournament_participant_v1().player_id().delete(player_id),
+            );
+        }
+
+        #[cfg(feature = "invite_link")]
+        {
+            use crate::invite_link::stdb_invite_link_v1;
+            record("stdb_invite_link_v1", db.stdb_invite_link_v1().player_id().delete(player_id));
+        }
+
+        #[cfg(feature = "analytics")]
+        {
+            use crate::player::analytics::stdb_session_event_v1;
+            record(
+                "stdb_session_event_v1",
+                db.stdb_session_event_v1().player_id().delete(player_id),
+            );
+        }
+
+        #[cfg(feature = "party")]
+        {
+            use crate::party::{repository::PartyRepository, stdb_party_invite_v1};
+            let in_party = self.find_party_member(player_id).is_some();
+            if in_party {
+                self.leave_party(player_id)?;
+            }
+            record("stdb_party_member_v1", u64::from(in_party));
+            record(
+                "stdb_party_invite_v1",
+                db.stdb_party_invite_v1().invitee_id().delete(player_id),
+            );
+        }
+
+        #[cfg(feature = "presence")]
+        {
+            use crate::presence::stdb_player_presence_v1;
+            record(
+                "stdb_player_presence_v1",
+                db.stdb_player_presence_v1().player_id().delete(player_id).into(),
+            );
+        }
+
+        Ok(deleted)
+    }
+}
+
+/// Deletes the rows of a table without an index on the player, returning how many were deleted.
+#[cfg(any(feature = "leaderboard", feature = "block"))]
+fn delete_where<T: Table>(table: &T, predicate: impl Fn(&T::Row) -> bool) -> u64 {
+    let rows: Vec<_> = table.iter().filter(|row| predicate(row)).collect();
+    rows.into_iter().map(|row| u64::from(table.delete(row))).sum()
+}
+
+#[cfg(feature = "vip")]
+fn vips_json(ctx: &ReducerContext, player_id: &Uuid) -> Vec<Value> {
+    use crate::vip::stdb_own_vip_v1;
+
+    ctx.db
+        .stdb_own_vip_v1()
+        .sender_id()
+        .filter(player_id)
+        .map(|vip| {
+            json!({
+                "receiver_id": vip.receiver_id,
+                "tag": vip.tag,
+                "status": format!("{:?}", vip.status),
+                "created_at": timestamp_json(vip.created_at),
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "vip"))]
+fn vips_json(_ctx: &ReducerContext, _player_id: &Uuid) -> Vec<Value> {
+    vec![]
+}
+
+/// RFC 3339 timestamps are easier to read in an export than microseconds.
+pub(crate) fn timestamp_json(timestamp: Timestamp) -> Value {
+    Value::String(timestamp.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp_json() {
+        let timestamp = Timestamp::from_micros_since_unix_epoch(1_745_712_000_000_000);
+        assert_eq!(timestamp_json(timestamp), json!("2025-04-27T00:00:00+00:00"));
+    }
+}
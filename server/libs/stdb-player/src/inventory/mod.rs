@@ -0,0 +1,67 @@
+use crate::{inventory::repository::InventoryRepository, prelude::PlayerExt};
+use spacetimedb::{Filter, ReducerContext, Timestamp, client_visibility_filter, reducer, table};
+use std::time::Duration;
+use stdb_common::prelude::{ServiceResult, Uuid};
+
+pub mod repository;
+
+pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+/// Maximum number of distinct item types a player can own.
+pub const INVENTORY_ITEM_TYPES_CAP: usize = 500;
+
+/// Minimum time between two item consumptions requested by the same player.
+pub const CONSUME_ITEM_COOLDOWN: Duration = Duration::from_millis(500);
+
+#[client_visibility_filter]
+const STDB_INVENTORY_ITEM_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select i.*
+    from stdb_inventory_item_v1 i
+    join stdb_own_player_session_v1 s
+        on s.player_id = i.player_id
+    where s.session_id = :sender
+"#,
+);
+
+/// Inventory item table - items owned by a player, only visible to the owning player.
+///
+/// Items of the same type are stacked in a single row per `(player_id, item_type_id)`,
+/// with at most [`INVENTORY_ITEM_TYPES_CAP`] rows per player.
+#[table(
+    name = stdb_inventory_item_v1,
+    public,
+    index(name = player_item_type_index, btree(columns = [player_id, item_type_id]))
+)]
+#[derive(Debug, Clone)]
+pub struct StdbInventoryItemV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub item_id: u64,
+
+    #[index(btree)]
+    pub player_id: Uuid,
+
+    /// Game specific identifier of the item type.
+    pub item_type_id: String,
+
+    pub quantity: u32,
+
+    /// When the first item of this type was granted.
+    pub acquired_at: Timestamp,
+}
+
+#[reducer]
+pub fn consume_item_v1(ctx: &ReducerContext, item_type_id: String, quantity: u32) -> ServiceResult<()> {
+    let session = ctx.require_rate_limit("consume_item", CONSUME_ITEM_COOLDOWN)?;
+    ctx.consume_item(session.player_id, item_type_id, quantity)?;
+    Ok(())
+}
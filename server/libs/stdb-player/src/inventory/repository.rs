@@ -0,0 +1,138 @@
+use crate::{
+    error::PlayerError,
+    inventory::{INVENTORY_ITEM_TYPES_CAP, StdbInventoryItemV1, stdb_inventory_item_v1},
+};
+use spacetimedb::ReducerContext;
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, validate_str, validate_u32, validate_uuid};
+
+/// Repository trait for managing player inventories.
+pub trait InventoryRepository {
+    /// Returns the items owned by a player.
+    fn find_inventory(&self, player_id: &Uuid) -> Vec<StdbInventoryItemV1>;
+
+    /// Returns the stack of an item type owned by a player.
+    fn find_inventory_item(&self, player_id: &Uuid, item_type_id: &str) -> Option<StdbInventoryItemV1>;
+
+    /// Whether the player owns at least `quantity` items of the given type.
+    fn has_item(&self, player_id: &Uuid, item_type_id: &str, quantity: u32) -> bool;
+
+    /// Adds items to a player's inventory, stacking them with items of the same type.
+    /// Stacks saturate at `u32::MAX`.
+    ///
+    /// Validates item_type_id (1-64 chars) and quantity (at least 1).
+    ///
+    /// # Errors
+    /// Returns error if validation fails, the player already owns [`INVENTORY_ITEM_TYPES_CAP`]
+    /// item types, or database operations fail.
+    fn grant_item(&self, player_id: Uuid, item_type_id: impl Into<String>, quantity: u32)
+    -> ServiceResult<StdbInventoryItemV1>;
+
+    /// Removes items from a player's inventory. The stack is deleted once it's empty.
+    ///
+    /// Validates item_type_id (1-64 chars) and quantity (at least 1).
+    ///
+    /// # Errors
+    /// Returns error if validation fails, the player doesn't own enough items, or database operations fail.
+    fn consume_item(&self, player_id: Uuid, item_type_id: impl Into<String>, quantity: u32) -> ServiceResult<()>;
+}
+
+impl InventoryRepository for ReducerContext {
+    fn find_inventory(&self, player_id: &Uuid) -> Vec<StdbInventoryItemV1> {
+        self.db.stdb_inventory_item_v1().player_id().filter(player_id).collect()
+    }
+
+    fn find_inventory_item(&self, player_id: &Uuid, item_type_id: &str) -> Option<StdbInventoryItemV1> {
+        self.db
+            .stdb_inventory_item_v1()
+            .player_item_type_index()
+            .filter((player_id, item_type_id))
+            .next()
+    }
+
+    fn has_item(&self, player_id: &Uuid, item_type_id: &str, quantity: u32) -> bool {
+        self.find_inventory_item(player_id, item_type_id)
+            .is_some_and(|item| item.quantity >= quantity)
+    }
+
+    fn grant_item(
+        &self,
+        player_id: Uuid,
+        item_type_id: impl Into<String>,
+        quantity: u32,
+    ) -> ServiceResult<StdbInventoryItemV1> {
+        let item_type_id = item_type_id.into();
+        validate_uuid("player_id", &player_id)?;
+        validate_str("item_type_id", &item_type_id, 1, 64)?;
+        validate_u32("quantity", quantity, 1, u32::MAX)?;
+
+        let item = match self.find_inventory_item(&player_id, &item_type_id) {
+            Some(mut item) => {
+                item.quantity = item.quantity.saturating_add(quantity);
+                item
+            },
+            None => {
+                let count = self.db.stdb_inventory_item_v1().player_id().filter(&player_id).count();
+                if count >= INVENTORY_ITEM_TYPES_CAP {
+                    return Err(PlayerError::inventory_limit_reached(player_id));
+                }
+
+                StdbInventoryItemV1 {
+                    item_id: 0,
+                    player_id,
+                    item_type_id,
+                    quantity,
+                    acquired_at: self.timestamp,
+                }
+            },
+        };
+
+        self.db
+            .stdb_inventory_item_v1()
+            .item_id()
+            .try_insert_or_update(item)
+            .map_conflict_ctx("failed to insert or update inventory item")
+    }
+
+    fn consume_item(&self, player_id: Uuid, item_type_id: impl Into<String>, quantity: u32) -> ServiceResult<()> {
+        let item_type_id = item_type_id.into();
+        validate_uuid("player_id", &player_id)?;
+        validate_str("item_type_id", &item_type_id, 1, 64)?;
+        validate_u32("quantity", quantity, 1, u32::MAX)?;
+
+        let Some(mut item) = self.find_inventory_item(&player_id, &item_type_id) else {
+            return Err(PlayerError::insufficient_items(item_type_id));
+        };
+        let Some(remaining) = remaining_quantity(item.quantity, quantity) else {
+            return Err(PlayerError::insufficient_items(item_type_id));
+        };
+
+        if remaining == 0 {
+            self.db.stdb_inventory_item_v1().item_id().delete(item.item_id);
+        } else {
+            item.quantity = remaining;
+            self.db
+                .stdb_inventory_item_v1()
+                .item_id()
+                .try_insert_or_update(item)
+                .map_conflict_ctx("failed to update inventory item")?;
+        }
+        Ok(())
+    }
+}
+
+/// Quantity left after consuming `quantity` items, `None` if there aren't enough.
+pub(crate) fn remaining_quantity(current: u32, quantity: u32) -> Option<u32> {
+    current.checked_sub(quantity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remaining_quantity() {
+        assert_eq!(remaining_quantity(5, 3), Some(2));
+        assert_eq!(remaining_quantity(5, 5), Some(0));
+        assert_eq!(remaining_quantity(5, 6), None);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{invite_link::repository::InviteLinkRepository, prelude::PlayerExt};
+use spacetimedb::{Filter, ReducerContext, ScheduleAt, Table, Timestamp, client_visibility_filter, reducer, table};
+use std::time::Duration;
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, ValidateExt};
+
+pub mod repository;
+
+/// Length of generated invite codes.
+pub const INVITE_LINK_CODE_LEN: usize = 8;
+
+/// Longest time an invite link stays valid.
+pub const INVITE_LINK_MAX_TTL_DAYS: u32 = 30;
+
+/// Most uses a single invite link can have.
+pub const INVITE_LINK_MAX_USES: u32 = 1000;
+
+/// Most unexpired invite links a player can have at once.
+pub const INVITE_LINKS_CAP: usize = 20;
+
+/// Interval between two runs of `prune_invite_links_v1`.
+pub const INVITE_LINK_CLEANUP_INTERVAL_SECS: u64 = 60 * 60;
+
+pub(crate) fn stdb_init(ctx: &ReducerContext) -> ServiceResult<()> {
+    if ctx.db.stdb_priv_invite_link_cleanup_schedule_v1().count() == 0 {
+        ctx.db
+            .stdb_priv_invite_link_cleanup_schedule_v1()
+            .try_insert(StdbPrivInviteLinkCleanupScheduleV1 {
+                scheduled_id: 0,
+                scheduled_at: Duration::from_secs(INVITE_LINK_CLEANUP_INTERVAL_SECS).into(),
+            })
+            .map_conflict_ctx("failed to schedule invite links cleanup")?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+#[client_visibility_filter]
+const STDB_INVITE_LINK_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select l.*
+    from stdb_invite_link_v1 l
+    join stdb_own_player_session_v1 s
+        on s.player_id = l.player_id
+    where s.session_id = :sender
+"#,
+);
+
+/// Invite link table - shareable codes that send a VIP invite from their owner to whoever redeems them.
+///
+/// Only visible to the owner. Rows are deleted once they run out of uses, expired ones are deleted by
+/// `prune_invite_links_v1`.
+#[table(name = stdb_invite_link_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbInviteLinkV1 {
+    #[primary_key]
+    pub code: String,
+
+    #[index(btree)]
+    pub player_id: Uuid,
+
+    pub created_at: Timestamp,
+    pub expires_at: Timestamp,
+
+    /// `None` for links without a use limit.
+    pub uses_remaining: Option<u32>,
+}
+
+/// Schedule table driving `prune_invite_links_v1`.
+#[table(name = stdb_priv_invite_link_cleanup_schedule_v1, scheduled(prune_invite_links_v1))]
+#[derive(Debug, Clone)]
+pub struct StdbPrivInviteLinkCleanupScheduleV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub scheduled_id: u64,
+
+    pub scheduled_at: ScheduleAt,
+}
+
+/// Creates an invite link of the current player. `ttl_days` is capped at 30 and `uses` at 1000,
+/// a player can have up to 20 unexpired links.
+#[reducer]
+pub fn create_invite_link_v1(ctx: &ReducerContext, uses: Option<u32>, ttl_days: u32) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.create_invite_link(session.player_id, uses, ttl_days)?;
+    Ok(())
+}
+
+/// Redeems an invite link, the owner of the link sends a VIP invite to the current player.
+#[reducer]
+pub fn redeem_invite_link_v1(ctx: &ReducerContext, code: String) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    if let Some(link) = ctx.find_invite_link(&code) {
+        ctx.require_not_blocked(&link.player_id)?;
+    }
+    ctx.redeem_invite_link(session.player_id, &code)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn prune_invite_links_v1(ctx: &ReducerContext, _schedule: StdbPrivInviteLinkCleanupScheduleV1) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.prune_expired_invite_links();
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use crate::{
+    error::PlayerError,
+    invite_link::{
+        INVITE_LINK_CODE_LEN, INVITE_LINK_MAX_TTL_DAYS, INVITE_LINK_MAX_USES, INVITE_LINKS_CAP, StdbInviteLinkV1,
+        stdb_invite_link_v1,
+    },
+    player::repository::random_index,
+    vip::{StdbOwnVipV1, repository::VipRepository},
+};
+use spacetimedb::{ReducerContext, Table, Timestamp};
+use std::time::Duration;
+use stdb_common::{
+    duration::DurationExt,
+    prelude::{ResultExt, ServiceResult, Uuid, validate_uuid, with_retry},
+};
+
+/// Generating a code that is already taken is unlikely, 36^8 codes are available.
+const INVITE_LINK_INSERT_ATTEMPTS: u8 = 5;
+
+/// Uppercase letters and digits, so codes are easy to read out and type.
+const INVITE_LINK_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Repository trait for managing invite links.
+pub trait InviteLinkRepository {
+    fn find_invite_link(&self, code: &str) -> Option<StdbInviteLinkV1>;
+
+    /// Returns the invite links owned by a player, expired ones included.
+    fn find_invite_links(&self, player_id: &Uuid) -> Vec<StdbInviteLinkV1>;
+
+    /// Creates an invite link with a new random code.
+    ///
+    /// `ttl_days` is clamped to 1-[`INVITE_LINK_MAX_TTL_DAYS`] and `uses` to 1-[`INVITE_LINK_MAX_USES`],
+    /// `None` means unlimited uses.
+    ///
+    /// # Errors
+    /// Returns error if validation fails, the player already has [`INVITE_LINKS_CAP`] unexpired links
+    /// or database operations fail.
+    fn create_invite_link(&self, player_id: Uuid, uses: Option<u32>, ttl_days: u32) -> ServiceResult<StdbInviteLinkV1>;
+
+    /// Uses an invite link, sending a VIP invite from its owner to `player_id`.
+    ///
+    /// The link is deleted once it runs out of uses.
+    ///
+    /// # Errors
+    /// Returns error if the link doesn't exist or expired, `player_id` owns the link,
+    /// the VIP invite can't be sent or database operations fail.
+    fn redeem_invite_link(&self, player_id: Uuid, code: &str) -> ServiceResult<StdbOwnVipV1>;
+
+    /// Deletes every expired invite link, returning how many were deleted.
+    fn prune_expired_invite_links(&self) -> usize;
+}
+
+impl InviteLinkRepository for ReducerContext {
+    fn find_invite_link(&self, code: &str) -> Option<StdbInviteLinkV1> {
+        self.db.stdb_invite_link_v1().code().find(code.to_string())
+    }
+
+    fn find_invite_links(&self, player_id: &Uuid) -> Vec<StdbInviteLinkV1> {
+        self.db.stdb_invite_link_v1().player_id().filter(player_id).collect()
+    }
+
+    fn create_invite_link(&self, player_id: Uuid, uses: Option<u32>, ttl_days: u32) -> ServiceResult<StdbInviteLinkV1> {
+        validate_uuid("player_id", &player_id)?;
+        let ttl_days = ttl_days.clamp(1, INVITE_LINK_MAX_TTL_DAYS);
+        let uses_remaining = uses.map(|uses| uses.clamp(1, INVITE_LINK_MAX_USES));
+        let expires_at = self.timestamp + Duration::from_days_ext(ttl_days.into());
+        let active_links = self
+            .find_invite_links(&player_id)
+            .iter()
+            .filter(|link| !is_invite_link_expired(link, self.timestamp))
+            .count();
+        require_invite_link_slot(&player_id, active_links)?;
+
+        with_retry(INVITE_LINK_INSERT_ATTEMPTS, || {
+            self.db
+                .stdb_invite_link_v1()
+                .try_insert(StdbInviteLinkV1 {
+                    code: invite_code_with(|| self.random::<u32>()),
+                    player_id: player_id.clone(),
+                    created_at: self.timestamp,
+                    expires_at,
+                    uses_remaining,
+                })
+                .map_conflict_ctx("failed to insert invite link")
+        })
+    }
+
+    fn redeem_invite_link(&self, player_id: Uuid, code: &str) -> ServiceResult<StdbOwnVipV1> {
+        let Some(mut link) = self.find_invite_link(code) else {
+            return Err(PlayerError::invite_link_not_found(code));
+        };
+        // Failing rolls the transaction back, expired links are left to `prune_invite_links_v1`
+        if is_invite_link_expired(&link, self.timestamp) {
+            return Err(PlayerError::invite_link_expired(code));
+        }
+        if link.player_id == player_id {
+            return Err(PlayerError::own_invite_link());
+        }
+
+        let vip = self.insert_vip(link.player_id.clone(), player_id, String::new())?;
+
+        match link.uses_remaining {
+            Some(uses) if uses <= 1 => {
+                self.db.stdb_invite_link_v1().code().delete(&link.code);
+            },
+            Some(uses) => {
+                link.uses_remaining = Some(uses - 1);
+                self.db
+                    .stdb_invite_link_v1()
+                    .code()
+                    .try_insert_or_update(link)
+                    .map_conflict_ctx("failed to update invite link")?;
+            },
+            None => {},
+        }
+
+        Ok(vip)
+    }
+
+    fn prune_expired_invite_links(&self) -> usize {
+        let expired: Vec<_> = self
+            .db
+            .stdb_invite_link_v1()
+            .iter()
+            .filter(|link| is_invite_link_expired(link, self.timestamp))
+            .map(|link| link.code)
+            .collect();
+
+        for code in &expired {
+            self.db.stdb_invite_link_v1().code().delete(code);
+        }
+        expired.len()
+    }
+}
+
+/// Fails if a player with `active_links` unexpired links already reached [`INVITE_LINKS_CAP`].
+pub(crate) fn require_invite_link_slot(player_id: &Uuid, active_links: usize) -> ServiceResult<()> {
+    if active_links >= INVITE_LINKS_CAP {
+        return Err(PlayerError::invite_link_limit_reached(player_id.clone()));
+    }
+    Ok(())
+}
+
+pub(crate) fn is_invite_link_expired(link: &StdbInviteLinkV1, now: Timestamp) -> bool {
+    now >= link.expires_at
+}
+
+/// Builds a code of [`INVITE_LINK_CODE_LEN`] chars out of [`INVITE_LINK_ALPHABET`].
+pub(crate) fn invite_code_with(random: impl Fn() -> u32) -> String {
+    (0..INVITE_LINK_CODE_LEN)
+        .map(|_| INVITE_LINK_ALPHABET[random_index(&random, INVITE_LINK_ALPHABET.len())] as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{PLAYER_ID, uuid};
+    use std::cell::Cell;
+
+    #[test]
+    fn test_invite_code_with() {
+        let state = Cell::new(0x2545_f491_u32);
+        let random = || {
+            let mut x = state.get();
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            state.set(x);
+            x
+        };
+
+        let code = invite_code_with(random);
+        assert_eq!(code.len(), INVITE_LINK_CODE_LEN);
+        assert!(code.chars().all(|ch| ch.is_ascii_uppercase() || ch.is_ascii_digit()));
+        assert_ne!(invite_code_with(random), code);
+        assert_eq!(invite_code_with(|| 0), "AAAAAAAA");
+    }
+
+    #[test]
+    fn test_require_invite_link_slot() {
+        let player_id = uuid(PLAYER_ID);
+        assert!(require_invite_link_slot(&player_id, 0).is_ok());
+        assert!(require_invite_link_slot(&player_id, INVITE_LINKS_CAP - 1).is_ok());
+
+        let error = require_invite_link_slot(&player_id, INVITE_LINKS_CAP).unwrap_err();
+        assert_eq!(error.message(), PlayerError::invite_link_limit_reached(player_id).message());
+    }
+
+    #[test]
+    fn test_is_invite_link_expired() {
+        let created_at = Timestamp::from_micros_since_unix_epoch(1_745_712_000_000_000);
+        let link = StdbInviteLinkV1 {
+            code: "AAAAAAAA".to_string(),
+            player_id: uuid(PLAYER_ID),
+            created_at,
+            expires_at: created_at + Duration::from_days_ext(1),
+            uses_remaining: None,
+        };
+
+        assert!(!is_invite_link_expired(&link, created_at));
+        assert!(is_invite_link_expired(&link, link.expires_at));
+    }
+}
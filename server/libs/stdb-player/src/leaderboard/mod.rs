@@ -0,0 +1,139 @@
+use crate::{leaderboard::repository::LeaderboardRepository, prelude::PlayerExt};
+use spacetimedb::{ReducerContext, ScheduleAt, SpacetimeType, Timestamp, reducer, table};
+use std::time::Duration;
+use stdb_common::{
+    duration::DurationExt,
+    prelude::{ServiceResult, Uuid, ValidateExt},
+};
+
+pub mod repository;
+
+pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+/// Leaderboard definition table - one row per board.
+///
+/// Boards are public so every client can list them and subscribe to their entries.
+#[table(name = stdb_leaderboard_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbLeaderboardV1 {
+    #[primary_key]
+    pub board_id: Uuid,
+
+    pub name: String,
+
+    pub reset_period: ResetPeriodV1,
+
+    pub created_at: Timestamp,
+}
+
+/// Leaderboard entry table - the best score of each player on each board.
+///
+/// Only the highest submitted score is kept per `(board_id, player_id)`.
+#[table(
+    name = stdb_leaderboard_entry_v1,
+    public,
+    index(name = board_score_index, btree(columns = [board_id, score])),
+    index(name = board_player_index, btree(columns = [board_id, player_id])),
+)]
+#[derive(Debug, Clone)]
+pub struct StdbLeaderboardEntryV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub entry_id: u64,
+
+    pub board_id: Uuid,
+
+    pub player_id: Uuid,
+
+    pub score: i64,
+
+    pub updated_at: Timestamp,
+}
+
+/// Leaderboard snapshot table - the final standings of a board before each reset.
+///
+/// Only the latest snapshots of each board are retained, see [`LEADERBOARD_SNAPSHOT_RETENTION`].
+#[table(name = stdb_leaderboard_snapshot_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbLeaderboardSnapshotV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub snapshot_id: u64,
+
+    #[index(btree)]
+    pub board_id: Uuid,
+
+    pub player_id: Uuid,
+
+    pub score: i64,
+
+    pub rank: u32,
+
+    pub snapshot_at: Timestamp,
+}
+
+/// Schedule table driving the periodic reset of boards with a [`ResetPeriodV1`] other than `Never`.
+#[table(name = stdb_priv_leaderboard_reset_schedule_v1, scheduled(reset_leaderboard_v1))]
+#[derive(Debug, Clone)]
+pub struct StdbPrivLeaderboardResetScheduleV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub scheduled_id: u64,
+
+    pub scheduled_at: ScheduleAt,
+
+    #[unique]
+    pub board_id: Uuid,
+}
+
+/// Number of snapshots kept per board. Older ones are deleted on reset.
+pub const LEADERBOARD_SNAPSHOT_RETENTION: usize = 3;
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, SpacetimeType)]
+pub enum ResetPeriodV1 {
+    /// The board is never reset
+    Never,
+
+    /// The board is reset every day at midnight UTC
+    Daily,
+
+    /// The board is reset every week
+    Weekly,
+
+    /// The board is reset every month (30 days)
+    Monthly,
+}
+
+impl ResetPeriodV1 {
+    /// Interval between two resets, `None` when the board is never reset.
+    pub fn interval(&self) -> Option<Duration> {
+        match self {
+            ResetPeriodV1::Never => None,
+            ResetPeriodV1::Daily => Some(Duration::from_days_ext(1)),
+            ResetPeriodV1::Weekly => Some(Duration::from_weeks_ext(1)),
+            ResetPeriodV1::Monthly => Some(Duration::from_days_ext(30)),
+        }
+    }
+}
+
+#[reducer]
+pub fn submit_score_v1(ctx: &ReducerContext, board_id: Uuid, score: i64) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.submit_score(board_id, session.player_id, score)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn reset_leaderboard_v1(ctx: &ReducerContext, schedule: StdbPrivLeaderboardResetScheduleV1) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.reset_leaderboard(&schedule.board_id)?;
+    Ok(())
+}
@@ -0,0 +1,270 @@
+use crate::{
+    error::PlayerError,
+    leaderboard::{
+        LEADERBOARD_SNAPSHOT_RETENTION, ResetPeriodV1, StdbLeaderboardEntryV1, StdbLeaderboardSnapshotV1, StdbLeaderboardV1,
+        StdbPrivLeaderboardResetScheduleV1, stdb_leaderboard_entry_v1, stdb_leaderboard_snapshot_v1, stdb_leaderboard_v1,
+        stdb_priv_leaderboard_reset_schedule_v1,
+    },
+};
+use spacetimedb::{ReducerContext, Table};
+use stdb_common::prelude::{ResultExt, ServiceResult, SortOrder, Uuid, UuidExt, sort_vec_by, validate_str, validate_uuid};
+
+/// Repository trait for managing leaderboards and their entries.
+///
+/// Each player keeps a single entry per board holding their best score.
+/// Ranks are 1-based and computed on read, highest score first.
+pub trait LeaderboardRepository {
+    /// Finds a leaderboard by its board ID.
+    ///
+    /// Returns `None` if no board exists with the given ID.
+    fn find_leaderboard(&self, board_id: &Uuid) -> Option<StdbLeaderboardV1>;
+
+    /// Creates a new leaderboard with a random board ID.
+    ///
+    /// Schedules the periodic reset of the board unless `reset_period` is `Never`.
+    ///
+    /// # Errors
+    /// Returns error if validation fails or database operations fail.
+    fn insert_leaderboard(&self, name: impl Into<String>, reset_period: ResetPeriodV1) -> ServiceResult<StdbLeaderboardV1>;
+
+    /// Finds the entry of a player on a board.
+    ///
+    /// Returns `None` if the player never submitted a score to the board.
+    fn find_leaderboard_entry(&self, board_id: &Uuid, player_id: &Uuid) -> Option<StdbLeaderboardEntryV1>;
+
+    /// Submits a score for a player, keeping only the highest one.
+    ///
+    /// Creates the entry if the player has none yet, or updates it when the new
+    /// score is higher than the stored one. Lower or equal scores are ignored and
+    /// the existing entry is returned.
+    ///
+    /// # Errors
+    /// Returns error if the board doesn't exist, validation fails or database operations fail.
+    fn submit_score(&self, board_id: Uuid, player_id: Uuid, score: i64) -> ServiceResult<StdbLeaderboardEntryV1>;
+
+    /// Returns the first `n` entries of a board by score.
+    ///
+    /// `Descending` returns the highest scores first, `Ascending` the lowest ones (e.g. for time trials).
+    /// Ties go to whoever reached the score first in both orders.
+    fn get_top_n(&self, board_id: &Uuid, n: u32, order: SortOrder) -> Vec<StdbLeaderboardEntryV1>;
+
+    /// Returns the 1-based rank of a player on a board.
+    ///
+    /// Players with the same score share the same rank.
+    /// Returns `None` if the player has no entry on the board.
+    fn get_player_rank(&self, board_id: &Uuid, player_id: &Uuid) -> Option<u32>;
+
+    /// Archives the current standings of a board and deletes all of its entries.
+    ///
+    /// Entries are copied into the snapshot table with their computed ranks, then
+    /// snapshots beyond the last [`LEADERBOARD_SNAPSHOT_RETENTION`] are deleted.
+    ///
+    /// # Errors
+    /// Returns error if the board doesn't exist or database operations fail.
+    fn reset_leaderboard(&self, board_id: &Uuid) -> ServiceResult<()>;
+
+    /// Returns all retained snapshot rows of a board, newest snapshot first, then by rank.
+    fn get_snapshots_for_board(&self, board_id: &Uuid) -> Vec<StdbLeaderboardSnapshotV1>;
+}
+
+impl LeaderboardRepository for ReducerContext {
+    fn find_leaderboard(&self, board_id: &Uuid) -> Option<StdbLeaderboardV1> {
+        self.db.stdb_leaderboard_v1().board_id().find(board_id)
+    }
+
+    fn insert_leaderboard(&self, name: impl Into<String>, reset_period: ResetPeriodV1) -> ServiceResult<StdbLeaderboardV1> {
+        let name = name.into();
+        validate_str("name", &name, 1, 64)?;
+
+        let board = self
+            .db
+            .stdb_leaderboard_v1()
+            .try_insert(StdbLeaderboardV1 {
+                board_id: self.new_uuid_v7(),
+                name,
+                reset_period,
+                created_at: self.timestamp,
+            })
+            .map_conflict_ctx("failed to insert leaderboard")?;
+
+        if let Some(interval) = reset_period.interval() {
+            self.db
+                .stdb_priv_leaderboard_reset_schedule_v1()
+                .try_insert(StdbPrivLeaderboardResetScheduleV1 {
+                    scheduled_id: 0,
+                    scheduled_at: interval.into(),
+                    board_id: board.board_id.clone(),
+                })
+                .map_conflict_ctx("failed to schedule leaderboard reset")?;
+        }
+
+        Ok(board)
+    }
+
+    fn find_leaderboard_entry(&self, board_id: &Uuid, player_id: &Uuid) -> Option<StdbLeaderboardEntryV1> {
+        self.db
+            .stdb_leaderboard_entry_v1()
+            .board_player_index()
+            .filter((board_id, player_id))
+            .next()
+    }
+
+    fn submit_score(&self, board_id: Uuid, player_id: Uuid, score: i64) -> ServiceResult<StdbLeaderboardEntryV1> {
+        validate_uuid("board_id", &board_id)?;
+        validate_uuid("player_id", &player_id)?;
+
+        if self.find_leaderboard(&board_id).is_none() {
+            return Err(PlayerError::leaderboard_not_found(board_id));
+        }
+
+        let entry = match self.find_leaderboard_entry(&board_id, &player_id) {
+            Some(entry) if entry.score >= score => return Ok(entry),
+            Some(mut entry) => {
+                entry.score = score;
+                entry.updated_at = self.timestamp;
+                entry
+            },
+            None => StdbLeaderboardEntryV1 {
+                entry_id: 0,
+                board_id,
+                player_id,
+                score,
+                updated_at: self.timestamp,
+            },
+        };
+
+        self.db
+            .stdb_leaderboard_entry_v1()
+            .entry_id()
+            .try_insert_or_update(entry)
+            .map_conflict_ctx("failed to submit leaderboard score")
+    }
+
+    fn get_top_n(&self, board_id: &Uuid, n: u32, order: SortOrder) -> Vec<StdbLeaderboardEntryV1> {
+        let mut entries: Vec<_> = self
+            .db
+            .stdb_leaderboard_entry_v1()
+            .board_score_index()
+            .filter(board_id)
+            .collect();
+        // Stable sort, so the first sort breaks the ties of the second one
+        entries.sort_by_key(|entry| entry.updated_at);
+        sort_vec_by(&mut entries, |entry| entry.score, order);
+        entries.truncate(n as usize);
+        entries
+    }
+
+    fn get_player_rank(&self, board_id: &Uuid, player_id: &Uuid) -> Option<u32> {
+        let entry = self.find_leaderboard_entry(board_id, player_id)?;
+        let higher_scores = self
+            .db
+            .stdb_leaderboard_entry_v1()
+            .board_score_index()
+            .filter(board_id)
+            .filter(|other| other.score > entry.score)
+            .count();
+
+        Some(higher_scores as u32 + 1)
+    }
+
+    fn reset_leaderboard(&self, board_id: &Uuid) -> ServiceResult<()> {
+        if self.find_leaderboard(board_id).is_none() {
+            return Err(PlayerError::leaderboard_not_found(board_id.clone()));
+        }
+
+        let mut entries: Vec<_> = self
+            .db
+            .stdb_leaderboard_entry_v1()
+            .board_score_index()
+            .filter(board_id)
+            .collect();
+        sort_by_rank(&mut entries);
+
+        let scores: Vec<_> = entries.iter().map(|entry| entry.score).collect();
+        for (entry, rank) in entries.into_iter().zip(compute_ranks(&scores)) {
+            self.db
+                .stdb_leaderboard_snapshot_v1()
+                .try_insert(StdbLeaderboardSnapshotV1 {
+                    snapshot_id: 0,
+                    board_id: entry.board_id.clone(),
+                    player_id: entry.player_id.clone(),
+                    score: entry.score,
+                    rank,
+                    snapshot_at: self.timestamp,
+                })
+                .map_conflict_ctx("failed to insert leaderboard snapshot")?;
+
+            self.db.stdb_leaderboard_entry_v1().entry_id().delete(entry.entry_id);
+        }
+
+        let mut snapshot_times: Vec<_> = self
+            .db
+            .stdb_leaderboard_snapshot_v1()
+            .board_id()
+            .filter(board_id)
+            .map(|snapshot| snapshot.snapshot_at)
+            .collect();
+        snapshot_times.sort_unstable_by(|a, b| b.cmp(a));
+        snapshot_times.dedup();
+
+        if let Some(&oldest_retained) = snapshot_times.get(LEADERBOARD_SNAPSHOT_RETENTION - 1) {
+            for snapshot in self.db.stdb_leaderboard_snapshot_v1().board_id().filter(board_id) {
+                if snapshot.snapshot_at < oldest_retained {
+                    self.db
+                        .stdb_leaderboard_snapshot_v1()
+                        .snapshot_id()
+                        .delete(snapshot.snapshot_id);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_snapshots_for_board(&self, board_id: &Uuid) -> Vec<StdbLeaderboardSnapshotV1> {
+        let mut snapshots: Vec<_> = self.db.stdb_leaderboard_snapshot_v1().board_id().filter(board_id).collect();
+        snapshots.sort_by(|a, b| b.snapshot_at.cmp(&a.snapshot_at).then(a.rank.cmp(&b.rank)));
+        snapshots
+    }
+}
+
+/// Sorts entries by score descending. Ties are broken by whoever reached the score first.
+pub(crate) fn sort_by_rank(entries: &mut [StdbLeaderboardEntryV1]) {
+    entries.sort_by(|a, b| b.score.cmp(&a.score).then(a.updated_at.cmp(&b.updated_at)));
+}
+
+/// Computes 1-based ranks for scores sorted descending. Equal scores share the same rank,
+/// and the next distinct score skips the shared positions (1, 2, 2, 4).
+pub(crate) fn compute_ranks(sorted_scores: &[i64]) -> Vec<u32> {
+    let mut ranks = Vec::with_capacity(sorted_scores.len());
+    for (index, score) in sorted_scores.iter().enumerate() {
+        let rank = match index {
+            0 => 1,
+            _ if sorted_scores[index - 1] == *score => ranks[index - 1],
+            _ => index as u32 + 1,
+        };
+        ranks.push(rank);
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_ranks_distinct_scores() {
+        assert_eq!(compute_ranks(&[300, 200, 100]), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_compute_ranks_shared_scores() {
+        assert_eq!(compute_ranks(&[300, 200, 200, 100]), vec![1, 2, 2, 4]);
+        assert_eq!(compute_ranks(&[50, 50, 50]), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn test_compute_ranks_empty() {
+        assert!(compute_ranks(&[]).is_empty());
+    }
+}
@@ -1,48 +1,266 @@
-// TODO friends, guilds?, chat?, basic auth with game center and google play services?, i18n
+// TODO friends, guilds?, chat?
 
+use crate::player::DeviceTypeV1;
 use log::{debug, info};
 use spacetimedb::ReducerContext;
 use stdb_common::prelude::ServiceResult;
 
 pub mod error;
+pub mod gdpr;
+pub mod notification;
 pub mod player;
 pub mod validate;
 
+#[cfg(test)]
+mod fixtures;
+
 #[cfg(feature = "vip")]
 pub mod vip;
 
+#[cfg(feature = "leaderboard")]
+pub mod leaderboard;
+
+#[cfg(feature = "achievement")]
+pub mod achievement;
+
+#[cfg(feature = "reward")]
+pub mod reward;
+
+#[cfg(feature = "experience")]
+pub mod experience;
+
+#[cfg(feature = "platform")]
+pub mod platform;
+
+#[cfg(feature = "moderation")]
+pub mod moderation;
+
+#[cfg(feature = "block")]
+pub mod block;
+
+#[cfg(feature = "currency")]
+pub mod currency;
+
+#[cfg(feature = "inventory")]
+pub mod inventory;
+
+#[cfg(feature = "event")]
+pub mod event;
+
+#[cfg(feature = "matchmaking")]
+pub mod matchmaking;
+
+#[cfg(feature = "presence")]
+pub mod presence;
+
+#[cfg(feature = "tournament")]
+pub mod tournament;
+
+#[cfg(feature = "invite_link")]
+pub mod invite_link;
+
+#[cfg(feature = "party")]
+pub mod party;
+
 pub mod prelude {
-    pub use crate::{error::*, validate::*};
+    pub use crate::{
+        error::*,
+        notification::repository::NotificationRepository,
+        player::{
+            StdbOwnPlayerSessionV1, StdbOwnPlayerV1, StdbPubPlayerCardV1,
+            repository::{PlayerRepository, PlayerSessionRepository},
+        },
+        validate::*,
+    };
     pub use stdb_common::prelude::*;
+
+    #[cfg(feature = "vip")]
+    pub use crate::vip::{StdbOwnVipV1, StdbPlayerPrivacyV1, VipInvitePolicyV1, VipStatusV1, repository::VipRepository};
+
+    #[cfg(feature = "experience")]
+    pub use crate::experience::{StdbPlayerXpV1, StdbXpTransactionV1, repository::ExperienceRepository};
 }
 
 #[inline]
 pub fn stdb_init(ctx: &ReducerContext) -> ServiceResult<()> {
+    stdb_common::rate_limiter::stdb_init(ctx)?;
     player::stdb_init(ctx)?;
+    notification::stdb_init(ctx)?;
+    gdpr::stdb_init(ctx)?;
 
     #[cfg(feature = "vip")]
     vip::stdb_init(ctx)?;
 
+    #[cfg(feature = "leaderboard")]
+    leaderboard::stdb_init(ctx)?;
+
+    #[cfg(feature = "achievement")]
+    achievement::stdb_init(ctx)?;
+
+    #[cfg(feature = "reward")]
+    reward::stdb_init(ctx)?;
+
+    #[cfg(feature = "experience")]
+    experience::stdb_init(ctx)?;
+
+    #[cfg(feature = "platform")]
+    platform::stdb_init(ctx)?;
+
+    #[cfg(feature = "moderation")]
+    moderation::stdb_init(ctx)?;
+
+    #[cfg(feature = "block")]
+    block::stdb_init(ctx)?;
+
+    #[cfg(feature = "currency")]
+    currency::stdb_init(ctx)?;
+
+    #[cfg(feature = "inventory")]
+    inventory::stdb_init(ctx)?;
+
+    #[cfg(feature = "event")]
+    event::stdb_init(ctx)?;
+
+    #[cfg(feature = "matchmaking")]
+    matchmaking::stdb_init(ctx)?;
+
+    #[cfg(feature = "presence")]
+    presence::stdb_init(ctx)?;
+
+    #[cfg(feature = "tournament")]
+    tournament::stdb_init(ctx)?;
+
+    #[cfg(feature = "invite_link")]
+    invite_link::stdb_init(ctx)?;
+
+    #[cfg(feature = "party")]
+    party::stdb_init(ctx)?;
+
     info!("stdb-player: initialized");
     Ok(())
 }
 
+/// Call from the `client_connected` reducer of the game.
+///
+/// `client_connected` can't receive arguments, so games that don't know the device type or client version
+/// at this point usually pass `DeviceTypeV1::Unknown` and their server version, then let the client report
+/// the real values with `update_session_client_v1`.
+///
+/// Returns `true` when a new player was just created, e.g. to show a first-time welcome flow.
 #[inline]
-pub fn stdb_identity_connected(ctx: &ReducerContext) -> ServiceResult<()> {
-    player::stdb_identity_connected(ctx)?;
+pub fn stdb_identity_connected(
+    ctx: &ReducerContext,
+    device_type: DeviceTypeV1,
+    client_version: impl Into<String>,
+) -> ServiceResult<bool> {
+    let is_new_player = player::stdb_identity_connected(ctx, device_type, client_version.into())?;
+    notification::stdb_identity_connected(ctx)?;
+    gdpr::stdb_identity_connected(ctx)?;
 
     #[cfg(feature = "vip")]
     vip::stdb_identity_connected(ctx)?;
 
+    #[cfg(feature = "leaderboard")]
+    leaderboard::stdb_identity_connected(ctx)?;
+
+    #[cfg(feature = "achievement")]
+    achievement::stdb_identity_connected(ctx)?;
+
+    #[cfg(feature = "reward")]
+    reward::stdb_identity_connected(ctx)?;
+
+    #[cfg(feature = "experience")]
+    experience::stdb_identity_connected(ctx)?;
+
+    #[cfg(feature = "platform")]
+    platform::stdb_identity_connected(ctx)?;
+
+    #[cfg(feature = "moderation")]
+    moderation::stdb_identity_connected(ctx)?;
+
+    #[cfg(feature = "block")]
+    block::stdb_identity_connected(ctx)?;
+
+    #[cfg(feature = "currency")]
+    currency::stdb_identity_connected(ctx)?;
+
+    #[cfg(feature = "inventory")]
+    inventory::stdb_identity_connected(ctx)?;
+
+    #[cfg(feature = "event")]
+    event::stdb_identity_connected(ctx)?;
+
+    #[cfg(feature = "matchmaking")]
+    matchmaking::stdb_identity_connected(ctx)?;
+
+    #[cfg(feature = "presence")]
+    presence::stdb_identity_connected(ctx)?;
+
+    #[cfg(feature = "tournament")]
+    tournament::stdb_identity_connected(ctx)?;
+
+    #[cfg(feature = "invite_link")]
+    invite_link::stdb_identity_connected(ctx)?;
+
+    #[cfg(feature = "party")]
+    party::stdb_identity_connected(ctx)?;
+
     debug!("stdb-player: identity connected");
-    Ok(())
+    Ok(is_new_player)
 }
 
 #[inline]
 pub fn stdb_identity_disconnected(ctx: &ReducerContext) {
+    #[cfg(feature = "party")]
+    party::stdb_identity_disconnected(ctx);
+
+    #[cfg(feature = "invite_link")]
+    invite_link::stdb_identity_disconnected(ctx);
+
+    #[cfg(feature = "tournament")]
+    tournament::stdb_identity_disconnected(ctx);
+
+    #[cfg(feature = "presence")]
+    presence::stdb_identity_disconnected(ctx);
+
+    #[cfg(feature = "matchmaking")]
+    matchmaking::stdb_identity_disconnected(ctx);
+
+    #[cfg(feature = "event")]
+    event::stdb_identity_disconnected(ctx);
+
+    #[cfg(feature = "inventory")]
+    inventory::stdb_identity_disconnected(ctx);
+
+    #[cfg(feature = "currency")]
+    currency::stdb_identity_disconnected(ctx);
+
+    #[cfg(feature = "block")]
+    block::stdb_identity_disconnected(ctx);
+
+    #[cfg(feature = "moderation")]
+    moderation::stdb_identity_disconnected(ctx);
+
+    #[cfg(feature = "platform")]
+    platform::stdb_identity_disconnected(ctx);
+
+    #[cfg(feature = "experience")]
+    experience::stdb_identity_disconnected(ctx);
+
+    #[cfg(feature = "reward")]
+    reward::stdb_identity_disconnected(ctx);
+
+    #[cfg(feature = "achievement")]
+    achievement::stdb_identity_disconnected(ctx);
+
+    #[cfg(feature = "leaderboard")]
+    leaderboard::stdb_identity_disconnected(ctx);
+
     #[cfg(feature = "vip")]
     vip::stdb_identity_disconnected(ctx);
 
+    gdpr::stdb_identity_disconnected(ctx);
+    notification::stdb_identity_disconnected(ctx);
     player::stdb_identity_disconnected(ctx);
 
     debug!("stdb-player: identity disconnected");
@@ -0,0 +1,106 @@
+use crate::{matchmaking::repository::MatchmakingRepository, prelude::PlayerExt};
+use spacetimedb::{Filter, ReducerContext, ScheduleAt, Table, Timestamp, client_visibility_filter, reducer, table};
+use std::time::Duration;
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, ValidateExt};
+
+pub mod repository;
+
+/// Queue entries older than this are removed by `cleanup_stale_queue_entries_v1`.
+pub const MATCHMAKING_QUEUE_TIMEOUT_SECS: u64 = 5 * 60;
+
+/// How often stale queue entries are removed.
+pub const MATCHMAKING_CLEANUP_INTERVAL_SECS: u64 = 60;
+
+/// Minimum time between two queue joins, or two queue leaves, requested by the same player.
+pub const MATCHMAKING_COOLDOWN: Duration = Duration::from_secs(2);
+
+pub(crate) fn stdb_init(ctx: &ReducerContext) -> ServiceResult<()> {
+    if ctx.db.stdb_priv_matchmaking_cleanup_schedule_v1().count() == 0 {
+        ctx.db
+            .stdb_priv_matchmaking_cleanup_schedule_v1()
+            .try_insert(StdbPrivMatchmakingCleanupScheduleV1 {
+                scheduled_id: 0,
+                scheduled_at: Duration::from_secs(MATCHMAKING_CLEANUP_INTERVAL_SECS).into(),
+            })
+            .map_conflict_ctx("failed to schedule matchmaking queue cleanup")?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+#[client_visibility_filter]
+const STDB_MATCHMAKING_QUEUE_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select q.*
+    from stdb_matchmaking_queue_v1 q
+    join stdb_own_player_session_v1 s
+        on s.player_id = q.player_id
+    where s.session_id = :sender
+"#,
+);
+
+/// Matchmaking queue table - players waiting for a match, only visible to the queued player.
+///
+/// A player has at most one entry. Once matched by `MatchmakingRepository::assign_match`, `matched_at`
+/// and `match_id` are set and the entry stays until the player leaves the queue or it's cleaned up.
+#[table(name = stdb_matchmaking_queue_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbMatchmakingQueueV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub queue_id: u64,
+
+    #[unique]
+    pub player_id: Uuid,
+
+    #[index(btree)]
+    pub skill_rating: i32,
+
+    pub queued_at: Timestamp,
+    pub matched_at: Option<Timestamp>,
+    pub match_id: Option<Uuid>,
+}
+
+/// Schedule table driving `cleanup_stale_queue_entries_v1`.
+#[table(name = stdb_priv_matchmaking_cleanup_schedule_v1, scheduled(cleanup_stale_queue_entries_v1))]
+#[derive(Debug, Clone)]
+pub struct StdbPrivMatchmakingCleanupScheduleV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub scheduled_id: u64,
+
+    pub scheduled_at: ScheduleAt,
+}
+
+/// Joins the matchmaking queue with a client reported skill rating.
+///
+/// Games that keep the rating server side should call `MatchmakingRepository::join_queue` from their own reducer.
+#[reducer]
+pub fn join_matchmaking_v1(ctx: &ReducerContext, skill_rating: i32) -> ServiceResult<()> {
+    let session = ctx.require_rate_limit("join_matchmaking", MATCHMAKING_COOLDOWN)?;
+    ctx.join_queue(session.player_id, skill_rating)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn leave_matchmaking_v1(ctx: &ReducerContext) -> ServiceResult<()> {
+    let session = ctx.require_rate_limit("leave_matchmaking", MATCHMAKING_COOLDOWN)?;
+    ctx.leave_queue(&session.player_id);
+    Ok(())
+}
+
+#[reducer]
+pub fn cleanup_stale_queue_entries_v1(
+    ctx: &ReducerContext,
+    _schedule: StdbPrivMatchmakingCleanupScheduleV1,
+) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.cleanup_stale_queue_entries(Duration::from_secs(MATCHMAKING_QUEUE_TIMEOUT_SECS));
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use crate::{
+    error::PlayerError,
+    matchmaking::{StdbMatchmakingQueueV1, stdb_matchmaking_queue_v1},
+};
+use spacetimedb::{ReducerContext, Table, Timestamp};
+use std::time::Duration;
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, UuidExt, validate_uuid};
+
+/// Repository trait for managing the matchmaking queue.
+pub trait MatchmakingRepository {
+    fn find_queue_entry(&self, player_id: &Uuid) -> Option<StdbMatchmakingQueueV1>;
+
+    /// Adds a player to the queue.
+    ///
+    /// # Errors
+    /// Returns error if the player is already queued or database operations fail.
+    fn join_queue(&self, player_id: Uuid, skill_rating: i32) -> ServiceResult<StdbMatchmakingQueueV1>;
+
+    /// Removes a player from the queue. Returns `false` if they weren't queued.
+    fn leave_queue(&self, player_id: &Uuid) -> bool;
+
+    /// Returns the unmatched players whose rating is within `max_rating_diff` of the player's,
+    /// closest rating first and then longest waiting first.
+    ///
+    /// Returns an empty list if the player isn't queued.
+    fn find_match_candidates(&self, player_id: &Uuid, max_rating_diff: u32) -> Vec<StdbMatchmakingQueueV1>;
+
+    /// Matches the queued players together, setting the same new `match_id` and `matched_at` on
+    /// every entry. Returns the match ID.
+    ///
+    /// # Errors
+    /// Returns error if a player isn't queued, was already matched or database operations fail.
+    fn assign_match(&self, player_ids: &[Uuid]) -> ServiceResult<Uuid>;
+
+    /// Removes queue entries older than `timeout`. Returns the number of removed entries.
+    fn cleanup_stale_queue_entries(&self, timeout: Duration) -> usize;
+}
+
+impl MatchmakingRepository for ReducerContext {
+    fn find_queue_entry(&self, player_id: &Uuid) -> Option<StdbMatchmakingQueueV1> {
+        self.db.stdb_matchmaking_queue_v1().player_id().find(player_id)
+    }
+
+    fn join_queue(&self, player_id: Uuid, skill_rating: i32) -> ServiceResult<StdbMatchmakingQueueV1> {
+        validate_uuid("player_id", &player_id)?;
+        if self.find_queue_entry(&player_id).is_some() {
+            return Err(PlayerError::already_in_queue(player_id));
+        }
+
+        self.db
+            .stdb_matchmaking_queue_v1()
+            .try_insert(StdbMatchmakingQueueV1 {
+                queue_id: 0,
+                player_id,
+                skill_rating,
+                queued_at: self.timestamp,
+                matched_at: None,
+                match_id: None,
+            })
+            .map_conflict_ctx("failed to join matchmaking queue")
+    }
+
+    fn leave_queue(&self, player_id: &Uuid) -> bool {
+        self.db.stdb_matchmaking_queue_v1().player_id().delete(player_id)
+    }
+
+    fn find_match_candidates(&self, player_id: &Uuid, max_rating_diff: u32) -> Vec<StdbMatchmakingQueueV1> {
+        let Some(entry) = self.find_queue_entry(player_id) else {
+            return vec![];
+        };
+
+        let max_rating_diff = max_rating_diff.min(i32::MAX as u32) as i32;
+        let min_rating = entry.skill_rating.saturating_sub(max_rating_diff);
+        let max_rating = entry.skill_rating.saturating_add(max_rating_diff);
+
+        let mut candidates: Vec<_> = self
+            .db
+            .stdb_matchmaking_queue_v1()
+            .skill_rating()
+            .filter(min_rating..=max_rating)
+            .filter(|candidate| candidate.player_id != entry.player_id && candidate.match_id.is_none())
+            .collect();
+        sort_match_candidates(&mut candidates, entry.skill_rating);
+        candidates
+    }
+
+    fn assign_match(&self, player_ids: &[Uuid]) -> ServiceResult<Uuid> {
+        let match_id = self.new_uuid_v7();
+        let entries = player_ids
+            .iter()
+            .map(|player_id| {
+                let entry = self
+                    .find_queue_entry(player_id)
+                    .ok_or_else(|| PlayerError::not_in_queue(player_id.clone()))?;
+                matched_entry(entry, &match_id, self.timestamp)
+            })
+            .collect::<ServiceResult<Vec<_>>>()?;
+
+        for entry in entries {
+            self.db
+                .stdb_matchmaking_queue_v1()
+                .queue_id()
+                .try_insert_or_update(entry)
+                .map_conflict_ctx("failed to assign match")?;
+        }
+
+        Ok(match_id)
+    }
+
+    fn cleanup_stale_queue_entries(&self, timeout: Duration) -> usize {
+        let stale: Vec<_> = self
+            .db
+            .stdb_matchmaking_queue_v1()
+            .iter()
+            .filter(|entry| is_queue_entry_stale(entry.queued_at, self.timestamp, timeout))
+            .collect();
+
+        for entry in &stale {
+            self.db.stdb_matchmaking_queue_v1().queue_id().delete(entry.queue_id);
+        }
+
+        stale.len()
+    }
+}
+
+/// Sorts candidates by rating distance to `skill_rating`, then by queue time.
+pub(crate) fn sort_match_candidates(candidates: &mut [StdbMatchmakingQueueV1], skill_rating: i32) {
+    candidates.sort_by_key(|candidate| (candidate.skill_rating.abs_diff(skill_rating), candidate.queued_at));
+}
+
+/// Marks the entry as matched, unless it already was.
+pub(crate) fn matched_entry(
+    mut entry: StdbMatchmakingQueueV1,
+    match_id: &Uuid,
+    now: Timestamp,
+) -> ServiceResult<StdbMatchmakingQueueV1> {
+    if entry.match_id.is_some() {
+        return Err(PlayerError::already_matched(entry.player_id));
+    }
+
+    entry.matched_at = Some(now);
+    entry.match_id = Some(match_id.clone());
+    Ok(entry)
+}
+
+/// Whether an entry queued at `queued_at` is older than `timeout` at `now`.
+pub(crate) fn is_queue_entry_stale(queued_at: Timestamp, now: Timestamp, timeout: Duration) -> bool {
+    now.duration_since(queued_at).is_some_and(|elapsed| elapsed > timeout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::at;
+
+    fn entry(player_id: &str, skill_rating: i32, queued_at: i64) -> StdbMatchmakingQueueV1 {
+        StdbMatchmakingQueueV1 {
+            queue_id: 0,
+            player_id: player_id.to_string(),
+            skill_rating,
+            queued_at: Timestamp::from_micros_since_unix_epoch(queued_at),
+            matched_at: None,
+            match_id: None,
+        }
+    }
+
+    #[test]
+    fn test_sort_match_candidates() {
+        let mut candidates = vec![entry("far", 1300, 0), entry("late", 1090, 20), entry("early", 1110, 10)];
+        sort_match_candidates(&mut candidates, 1100);
+
+        let order: Vec<_> = candidates.iter().map(|candidate| candidate.player_id.as_str()).collect();
+        assert_eq!(order, ["early", "late", "far"]);
+    }
+
+    #[test]
+    fn test_matched_entry() {
+        let now = Timestamp::from_micros_since_unix_epoch(50);
+        let matched = matched_entry(entry("a", 1100, 10), &"match".to_string(), now).unwrap();
+        assert_eq!(matched.matched_at, Some(now));
+        assert_eq!(matched.match_id.as_deref(), Some("match"));
+
+        let error = matched_entry(matched, &"other".to_string(), now).unwrap_err();
+        assert_eq!(error.message(), PlayerError::already_matched("a".to_string()).message());
+    }
+
+    #[test]
+    fn test_is_queue_entry_stale() {
+        let queued_at = Timestamp::from_micros_since_unix_epoch(0);
+        let timeout = Duration::from_secs(300);
+
+        assert!(!is_queue_entry_stale(queued_at, at(300), timeout));
+        assert!(is_queue_entry_stale(queued_at, at(301), timeout));
+    }
+}
@@ -0,0 +1,46 @@
+use crate::moderation::repository::ModerationRepository;
+use spacetimedb::{ReducerContext, Timestamp, reducer, table};
+use std::time::Duration;
+use stdb_common::prelude::{ServiceResult, Uuid, ValidateExt};
+
+pub mod repository;
+
+pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+/// Player ban table - players that can't sign in until the ban expires.
+///
+/// Only visible to the server. A `None` expiration means the ban is permanent.
+#[table(name = stdb_player_ban_v1)]
+#[derive(Debug, Clone)]
+pub struct StdbPlayerBanV1 {
+    #[primary_key]
+    pub player_id: Uuid,
+
+    pub reason: String,
+
+    pub banned_at: Timestamp,
+    pub expires_at: Option<Timestamp>,
+}
+
+/// Bans a player and signs out all of their sessions. `duration_secs` of `None` bans permanently.
+#[reducer]
+pub fn ban_player_v1(ctx: &ReducerContext, player_id: Uuid, reason: String, duration_secs: Option<u64>) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.ban_player(player_id, reason, duration_secs.map(Duration::from_secs))?;
+    Ok(())
+}
+
+#[reducer]
+pub fn unban_player_v1(ctx: &ReducerContext, player_id: Uuid) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.unban_player(&player_id);
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use crate::{
+    moderation::{StdbPlayerBanV1, stdb_player_ban_v1},
+    player::repository::PlayerSessionRepository,
+};
+use spacetimedb::{ReducerContext, Timestamp};
+use std::time::Duration;
+use stdb_common::prelude::{ResultExt, ServiceError, ServiceResult, Uuid, validate_str, validate_uuid};
+
+/// Repository trait for managing player bans.
+///
+/// Banned players are rejected by `sign_in_session` until their ban expires.
+pub trait ModerationRepository {
+    /// Finds the ban of a player, if it didn't expire yet.
+    ///
+    /// Returns `None` if the player isn't banned.
+    fn find_active_ban(&self, player_id: &Uuid) -> Option<StdbPlayerBanV1>;
+
+    /// Whether the player is currently banned.
+    fn is_banned(&self, player_id: &Uuid) -> bool;
+
+    /// Bans a player for `duration`, or permanently when `None`, and signs out all of their sessions.
+    ///
+    /// Banning an already banned player replaces the previous ban. Validates reason (1-256 chars).
+    ///
+    /// # Errors
+    /// Returns error if validation fails or database operations fail.
+    fn ban_player(
+        &self,
+        player_id: Uuid,
+        reason: impl Into<String>,
+        duration: Option<Duration>,
+    ) -> ServiceResult<StdbPlayerBanV1>;
+
+    /// Lifts the ban of a player. Returns `false` if the player wasn't banned.
+    fn unban_player(&self, player_id: &Uuid) -> bool;
+}
+
+impl ModerationRepository for ReducerContext {
+    fn find_active_ban(&self, player_id: &Uuid) -> Option<StdbPlayerBanV1> {
+        self.db
+            .stdb_player_ban_v1()
+            .player_id()
+            .find(player_id)
+            .filter(|ban| is_ban_active(ban.expires_at, self.timestamp))
+    }
+
+    fn is_banned(&self, player_id: &Uuid) -> bool {
+        self.find_active_ban(player_id).is_some()
+    }
+
+    fn ban_player(
+        &self,
+        player_id: Uuid,
+        reason: impl Into<String>,
+        duration: Option<Duration>,
+    ) -> ServiceResult<StdbPlayerBanV1> {
+        let reason = reason.into();
+        validate_uuid("player_id", &player_id)?;
+        validate_str("reason", &reason, 1, 256)?;
+
+        let ban = self
+            .db
+            .stdb_player_ban_v1()
+            .player_id()
+            .try_insert_or_update(StdbPlayerBanV1 {
+                player_id,
+                reason,
+                banned_at: self.timestamp,
+                expires_at: duration.map(|duration| self.timestamp + duration),
+            })
+            .map_conflict_ctx("failed to insert or update player ban")?;
+
+        self.invalidate_all_sessions_for_player(&ban.player_id)
+            .map_err(|error| ServiceError::chain(error, "failed to sign out banned player"))?;
+        Ok(ban)
+    }
+
+    fn unban_player(&self, player_id: &Uuid) -> bool {
+        self.db.stdb_player_ban_v1().player_id().delete(player_id)
+    }
+}
+
+/// A ban without expiration never ends, otherwise it's active until `expires_at`.
+pub(crate) fn is_ban_active(expires_at: Option<Timestamp>, now: Timestamp) -> bool {
+    expires_at.is_none_or(|expires_at| now < expires_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_ban_active() {
+        let now = Timestamp::from_micros_since_unix_epoch(1_745_712_000_000_000);
+
+        assert!(is_ban_active(None, now));
+        assert!(is_ban_active(Some(now + Duration::from_secs(1)), now));
+        assert!(!is_ban_active(Some(now), now));
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{notification::repository::NotificationRepository, prelude::PlayerExt};
+use log::info;
+use spacetimedb::{
+    Filter, ReducerContext, ScheduleAt, SpacetimeType, Table, Timestamp, client_visibility_filter, reducer, table,
+};
+use std::time::Duration;
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, ValidateExt};
+
+pub mod repository;
+
+/// Read notifications are deleted this many days after being read.
+pub const NOTIFICATION_READ_RETENTION_DAYS: u64 = 7;
+
+/// Unread notifications are deleted this many days after being created.
+pub const NOTIFICATION_UNREAD_RETENTION_DAYS: u64 = 30;
+
+/// Interval between two runs of `cleanup_expired_notifications_v1`.
+pub const NOTIFICATION_CLEANUP_INTERVAL_SECS: u64 = 60 * 60;
+
+pub(crate) fn stdb_init(ctx: &ReducerContext) -> ServiceResult<()> {
+    if ctx.db.stdb_priv_notification_cleanup_schedule_v1().count() == 0 {
+        ctx.db
+            .stdb_priv_notification_cleanup_schedule_v1()
+            .try_insert(StdbPrivNotificationCleanupScheduleV1 {
+                scheduled_id: 0,
+                scheduled_at: Duration::from_secs(NOTIFICATION_CLEANUP_INTERVAL_SECS).into(),
+            })
+            .map_conflict_ctx("failed to schedule expired notifications cleanup")?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+#[client_visibility_filter]
+const STDB_NOTIFICATION_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select n.*
+    from stdb_notification_v1 n
+    join stdb_own_player_session_v1 s
+        on s.player_id = n.player_id
+    where s.session_id = :sender
+"#,
+);
+
+#[client_visibility_filter]
+const STDB_NOTIFICATION_PREFS_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select p.*
+    from stdb_notification_prefs_v1 p
+    join stdb_own_player_session_v1 s
+        on s.player_id = p.player_id
+    where s.session_id = :sender
+"#,
+);
+
+/// Notification table - events the player should see on their next login.
+///
+/// Only visible to the owning player. `read_at` is set once the client acknowledges it.
+#[table(name = stdb_notification_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbNotificationV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub notification_id: u64,
+
+    #[index(btree)]
+    pub player_id: Uuid,
+
+    pub kind: NotificationKindV1,
+
+    /// Kind-specific data, e.g. the ID of the player who sent a VIP invite.
+    pub payload: String,
+
+    pub created_at: Timestamp,
+    pub read_at: Option<Timestamp>,
+}
+
+/// Notification preferences table - which notification kinds a player wants to receive.
+///
+/// Only visible to the owning player. Players without a row receive every kind,
+/// see [`StdbNotificationPrefsV1::new`].
+#[table(name = stdb_notification_prefs_v1, public)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StdbNotificationPrefsV1 {
+    #[primary_key]
+    pub player_id: Uuid,
+
+    pub vip_invites: bool,
+    pub vip_accepted: bool,
+    pub achievements: bool,
+    pub system: bool,
+    pub friend_status: bool,
+}
+
+impl StdbNotificationPrefsV1 {
+    /// Default preferences, with every notification kind enabled.
+    pub fn new(player_id: Uuid) -> Self {
+        Self {
+            player_id,
+            vip_invites: true,
+            vip_accepted: true,
+            achievements: true,
+            system: true,
+            friend_status: true,
+        }
+    }
+
+    /// Whether the player wants to receive notifications of the given kind.
+    pub fn is_enabled(&self, kind: NotificationKindV1) -> bool {
+        match kind {
+            NotificationKindV1::VipInviteReceived => self.vip_invites,
+            NotificationKindV1::VipAccepted | NotificationKindV1::VipDeclined => self.vip_accepted,
+            NotificationKindV1::AchievementUnlocked => self.achievements,
+            NotificationKindV1::SystemMessage => self.system,
+            NotificationKindV1::FriendWentOffline => self.friend_status,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, SpacetimeType)]
+pub enum NotificationKindV1 {
+    /// Another player sent a VIP invite, the payload is the sender ID
+    VipInviteReceived,
+
+    /// Another player accepted a VIP invite, the payload is their player ID
+    VipAccepted,
+
+    /// An achievement got unlocked, the payload is the achievement ID
+    AchievementUnlocked,
+
+    /// Free-form message sent by the game
+    SystemMessage,
+
+    /// A VIP friend disconnected, the payload is their player ID
+    FriendWentOffline,
+
+    /// Another player declined a VIP invite, the payload is their player ID.
+    /// Shares the `vip_accepted` preference, both are answers to the player's invites.
+    VipDeclined,
+}
+
+/// Schedule table driving `cleanup_expired_notifications_v1`.
+#[table(name = stdb_priv_notification_cleanup_schedule_v1, scheduled(cleanup_expired_notifications_v1))]
+#[derive(Debug, Clone)]
+pub struct StdbPrivNotificationCleanupScheduleV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub scheduled_id: u64,
+
+    pub scheduled_at: ScheduleAt,
+}
+
+#[reducer]
+pub fn mark_notification_read_v1(ctx: &ReducerContext, notification_id: u64) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.mark_notification_read(&session.player_id, notification_id)?;
+    Ok(())
+}
+
+/// Updates the notification preferences of the current player.
+///
+/// `prefs_json` is an object with any of `vip_invites`, `vip_accepted`, `achievements`, `system` and `friend_status`.
+/// Missing fields keep their current value, e.g. `{"system": false}`.
+#[reducer]
+pub fn update_notification_prefs_v1(ctx: &ReducerContext, prefs_json: String) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.update_notification_prefs(session.player_id, &prefs_json)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn cleanup_expired_notifications_v1(
+    ctx: &ReducerContext,
+    _schedule: StdbPrivNotificationCleanupScheduleV1,
+) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    let deleted = ctx.cleanup_expired_notifications(
+        Duration::from_secs(NOTIFICATION_READ_RETENTION_DAYS * 24 * 60 * 60),
+        Duration::from_secs(NOTIFICATION_UNREAD_RETENTION_DAYS * 24 * 60 * 60),
+    );
+    info!("deleted {} expired notifications", deleted);
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use crate::{
+    error::PlayerError,
+    notification::{
+        NotificationKindV1, StdbNotificationPrefsV1, StdbNotificationV1, stdb_notification_prefs_v1, stdb_notification_v1,
+    },
+};
+use serde::Deserialize;
+use spacetimedb::{ReducerContext, Table, Timestamp};
+use std::time::Duration;
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, validate_str, validate_uuid};
+
+/// Repository trait for managing player notifications.
+///
+/// Notifications are inserted by other features (VIP, achievements, the game itself)
+/// and acknowledged by the owning player.
+pub trait NotificationRepository {
+    /// Finds a notification by its ID.
+    ///
+    /// Returns `None` if no notification exists with the given ID.
+    fn find_notification(&self, notification_id: u64) -> Option<StdbNotificationV1>;
+
+    /// Returns all notifications of a player, newest first.
+    fn find_notifications_for_player(&self, player_id: &Uuid) -> Vec<StdbNotificationV1>;
+
+    /// Sends a notification to a player.
+    ///
+    /// Validates payload (max 1024 chars). Nothing is inserted when the player
+    /// opted out of the notification kind.
+    ///
+    /// # Errors
+    /// Returns error if validation fails or database operations fail.
+    fn notify_player(&self, player_id: Uuid, kind: NotificationKindV1, payload: impl Into<String>) -> ServiceResult<()>;
+
+    /// Marks a notification of the player as read. Already read notifications are left untouched.
+    ///
+    /// # Errors
+    /// Returns error if the notification doesn't exist or doesn't belong to the player,
+    /// or database operations fail.
+    fn mark_notification_read(&self, player_id: &Uuid, notification_id: u64) -> ServiceResult<StdbNotificationV1>;
+
+    /// Deletes notifications read longer than `read_retention` ago and unread ones created longer than
+    /// `unread_retention` ago, returning how many were deleted.
+    fn cleanup_expired_notifications(&self, read_retention: Duration, unread_retention: Duration) -> usize;
+
+    /// Returns the notification preferences of a player, or the defaults when none were stored.
+    fn find_prefs_or_default(&self, player_id: &Uuid) -> StdbNotificationPrefsV1;
+
+    /// Applies a JSON patch of preferences on top of the current ones of the player.
+    ///
+    /// # Errors
+    /// Returns error if the JSON is invalid or database operations fail.
+    fn update_notification_prefs(&self, player_id: Uuid, prefs_json: &str) -> ServiceResult<StdbNotificationPrefsV1>;
+}
+
+impl NotificationRepository for ReducerContext {
+    fn find_notification(&self, notification_id: u64) -> Option<StdbNotificationV1> {
+        self.db.stdb_notification_v1().notification_id().find(notification_id)
+    }
+
+    fn find_notifications_for_player(&self, player_id: &Uuid) -> Vec<StdbNotificationV1> {
+        let mut notifications: Vec<_> = self.db.stdb_notification_v1().player_id().filter(player_id).collect();
+        notifications.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then(b.notification_id.cmp(&a.notification_id))
+        });
+        notifications
+    }
+
+    fn notify_player(&self, player_id: Uuid, kind: NotificationKindV1, payload: impl Into<String>) -> ServiceResult<()> {
+        let payload = payload.into();
+        validate_uuid("player_id", &player_id)?;
+        validate_str("payload", &payload, 0, 1024)?;
+
+        if !self.find_prefs_or_default(&player_id).is_enabled(kind) {
+            return Ok(());
+        }
+
+        self.db
+            .stdb_notification_v1()
+            .try_insert(StdbNotificationV1 {
+                notification_id: 0,
+                player_id,
+                kind,
+                payload,
+                created_at: self.timestamp,
+                read_at: None,
+            })
+            .map_conflict_ctx("failed to insert notification")?;
+
+        Ok(())
+    }
+
+    fn mark_notification_read(&self, player_id: &Uuid, notification_id: u64) -> ServiceResult<StdbNotificationV1> {
+        let mut notification = match self.find_notification(notification_id) {
+            Some(notification) if notification.player_id == *player_id => notification,
+            _ => return Err(PlayerError::notification_not_found(notification_id)),
+        };
+
+        if notification.read_at.is_some() {
+            return Ok(notification);
+        }
+        notification.read_at = Some(self.timestamp);
+
+        self.db
+            .stdb_notification_v1()
+            .notification_id()
+            .try_insert_or_update(notification)
+            .map_conflict_ctx("failed to mark notification as read")
+    }
+
+    fn cleanup_expired_notifications(&self, read_retention: Duration, unread_retention: Duration) -> usize {
+        let expired: Vec<_> = self
+            .db
+            .stdb_notification_v1()
+            .iter()
+            .filter(|notification| is_notification_expired(notification, self.timestamp, read_retention, unread_retention))
+            .collect();
+
+        expired
+            .into_iter()
+            .filter(|notification| self.db.stdb_notification_v1().delete(notification.clone()))
+            .count()
+    }
+
+    fn find_prefs_or_default(&self, player_id: &Uuid) -> StdbNotificationPrefsV1 {
+        self.db
+            .stdb_notification_prefs_v1()
+            .player_id()
+            .find(player_id)
+            .unwrap_or(StdbNotificationPrefsV1::new(player_id.clone()))
+    }
+
+    fn update_notification_prefs(&self, player_id: Uuid, prefs_json: &str) -> ServiceResult<StdbNotificationPrefsV1> {
+        validate_uuid("player_id", &player_id)?;
+        let prefs = apply_prefs_json(self.find_prefs_or_default(&player_id), prefs_json)?;
+
+        self.db
+            .stdb_notification_prefs_v1()
+            .player_id()
+            .try_insert_or_update(prefs)
+            .map_conflict_ctx("failed to insert or update notification preferences")
+    }
+}
+
+/// Whether a notification is past its retention at `now`, counted from `read_at`, or from `created_at` while unread.
+pub(crate) fn is_notification_expired(
+    notification: &StdbNotificationV1,
+    now: Timestamp,
+    read_retention: Duration,
+    unread_retention: Duration,
+) -> bool {
+    let (since, retention) = match notification.read_at {
+        Some(read_at) => (read_at, read_retention),
+        None => (notification.created_at, unread_retention),
+    };
+    now.duration_since(since).is_some_and(|elapsed| elapsed > retention)
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NotificationPrefsPatch {
+    vip_invites: Option<bool>,
+    vip_accepted: Option<bool>,
+    achievements: Option<bool>,
+    system: Option<bool>,
+    friend_status: Option<bool>,
+}
+
+/// Parses `prefs_json` and overrides the fields it contains on top of `prefs`.
+pub(crate) fn apply_prefs_json(mut prefs: StdbNotificationPrefsV1, prefs_json: &str) -> ServiceResult<StdbNotificationPrefsV1> {
+    let patch: NotificationPrefsPatch =
+        serde_json::from_str(prefs_json).map_err(|error| PlayerError::invalid_notification_prefs(error.to_string()))?;
+
+    prefs.vip_invites = patch.vip_invites.unwrap_or(prefs.vip_invites);
+    prefs.vip_accepted = patch.vip_accepted.unwrap_or(prefs.vip_accepted);
+    prefs.achievements = patch.achievements.unwrap_or(prefs.achievements);
+    prefs.system = patch.system.unwrap_or(prefs.system);
+    prefs.friend_status = patch.friend_status.unwrap_or(prefs.friend_status);
+    Ok(prefs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_prefs_enable_every_kind() {
+        let prefs = StdbNotificationPrefsV1::new("player".to_string());
+
+        assert!(prefs.is_enabled(NotificationKindV1::VipInviteReceived));
+        assert!(prefs.is_enabled(NotificationKindV1::VipAccepted));
+        assert!(prefs.is_enabled(NotificationKindV1::AchievementUnlocked));
+        assert!(prefs.is_enabled(NotificationKindV1::SystemMessage));
+        assert!(prefs.is_enabled(NotificationKindV1::FriendWentOffline));
+    }
+
+    #[test]
+    fn test_apply_prefs_json_partial() {
+        let prefs = apply_prefs_json(StdbNotificationPrefsV1::new("player".to_string()), r#"{"system": false}"#).unwrap();
+
+        assert!(!prefs.is_enabled(NotificationKindV1::SystemMessage));
+        assert!(prefs.is_enabled(NotificationKindV1::VipInviteReceived));
+        assert!(prefs.is_enabled(NotificationKindV1::AchievementUnlocked));
+    }
+
+    #[test]
+    fn test_apply_prefs_json_keeps_previous_values() {
+        let mut prefs = StdbNotificationPrefsV1::new("player".to_string());
+        prefs.achievements = false;
+
+        let prefs = apply_prefs_json(prefs, r#"{"vip_invites": false}"#).unwrap();
+        assert!(!prefs.achievements);
+        assert!(!prefs.vip_invites);
+        assert!(prefs.vip_accepted);
+    }
+
+    #[test]
+    fn test_apply_prefs_json_invalid() {
+        let prefs = StdbNotificationPrefsV1::new("player".to_string());
+
+        assert!(apply_prefs_json(prefs.clone(), "not json").is_err());
+        assert!(apply_prefs_json(prefs.clone(), r#"{"system": "no"}"#).is_err());
+        assert!(apply_prefs_json(prefs, r#"{"unknown": true}"#).is_err());
+    }
+
+    fn notification(created_at: Timestamp, read_at: Option<Timestamp>) -> StdbNotificationV1 {
+        StdbNotificationV1 {
+            notification_id: 1,
+            player_id: "player".to_string(),
+            kind: NotificationKindV1::SystemMessage,
+            payload: String::new(),
+            created_at,
+            read_at,
+        }
+    }
+
+    #[test]
+    fn test_is_notification_expired() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let created_at = Timestamp::from_micros_since_unix_epoch(1_000_000_000);
+        let read_at = created_at + day;
+
+        let unread = notification(created_at, None);
+        assert!(!is_notification_expired(&unread, created_at + day * 30, day * 7, day * 30));
+        assert!(is_notification_expired(&unread, created_at + day * 31, day * 7, day * 30));
+
+        let read = notification(created_at, Some(read_at));
+        assert!(!is_notification_expired(&read, read_at + day * 7, day * 7, day * 30));
+        assert!(is_notification_expired(&read, read_at + day * 8, day * 7, day * 30));
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{
+    error::PlayerError, party::repository::PartyRepository, player::repository::PlayerSessionRepository, prelude::PlayerExt,
+};
+use log::warn;
+use spacetimedb::{Filter, ReducerContext, Timestamp, client_visibility_filter, reducer, table};
+use stdb_common::prelude::{ServiceResult, Uuid};
+
+pub mod repository;
+
+/// Maximum number of members of a single party, the leader included.
+pub const PARTY_MAX_SIZE: u32 = 16;
+
+pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+/// Once the last online session of a party leader disconnects, the leadership passes to the next member,
+/// see [`PartyRepository::hand_off_party`].
+/// Parties without other members are disbanded.
+pub(crate) fn stdb_identity_disconnected(ctx: &ReducerContext) {
+    let Some(session) = ctx.find_session(ctx.sender) else {
+        return;
+    };
+
+    let other_online = ctx
+        .find_all_sessions_for_player(&session.player_id)
+        .iter()
+        .any(|other| other.session_id != ctx.sender && other.is_online);
+    if other_online {
+        return;
+    }
+
+    let Some(party) = ctx.find_party_led_by(&session.player_id) else {
+        return;
+    };
+    if let Err(error) = ctx.hand_off_party(&party.party_id) {
+        warn!("failed to hand off party of disconnected leader: {:?}", error.unwrap_chain());
+    }
+}
+
+#[client_visibility_filter]
+const STDB_PARTY_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select p.*
+    from stdb_party_v1 p
+    join stdb_party_member_v1 m
+        on m.party_id = p.party_id
+    join stdb_own_player_session_v1 s
+        on s.player_id = m.player_id
+    where s.session_id = :sender
+"#,
+);
+
+#[client_visibility_filter]
+const STDB_PARTY_MEMBER_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select m.*
+    from stdb_party_member_v1 m
+    join stdb_party_member_v1 o
+        on o.party_id = m.party_id
+    join stdb_own_player_session_v1 s
+        on s.player_id = o.player_id
+    where s.session_id = :sender
+"#,
+);
+
+#[client_visibility_filter]
+const STDB_PARTY_INVITE_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select i.*
+    from stdb_party_invite_v1 i
+    join stdb_own_player_session_v1 s
+        on s.player_id = i.invitee_id
+    where s.session_id = :sender
+"#,
+);
+
+/// Members also see the pending invites of their party, so the leader knows who is yet to answer.
+#[client_visibility_filter]
+const STDB_PARTY_SENT_INVITE_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select i.*
+    from stdb_party_invite_v1 i
+    join stdb_party_member_v1 m
+        on m.party_id = i.party_id
+    join stdb_own_player_session_v1 s
+        on s.player_id = m.player_id
+    where s.session_id = :sender
+"#,
+);
+
+/// Party table - transient groups of players, only visible to their members.
+///
+/// The leader is also a member. A party is disbanded once its last member leaves.
+#[table(name = stdb_party_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbPartyV1 {
+    #[primary_key]
+    pub party_id: Uuid,
+
+    #[index(btree)]
+    pub leader_id: Uuid,
+
+    pub created_at: Timestamp,
+    pub max_size: u32,
+}
+
+/// Party member table - the members of each party, only visible to the members of the same party.
+///
+/// A player is in at most one party.
+#[table(name = stdb_party_member_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbPartyMemberV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub id: u64,
+
+    #[index(btree)]
+    pub party_id: Uuid,
+
+    #[unique]
+    pub player_id: Uuid,
+
+    pub joined_at: Timestamp,
+}
+
+/// Party invite table - pending invites, visible to the invited player and the members of the party.
+///
+/// The invitee only joins once they accept. Invites are deleted when answered, when the invitee joins
+/// any party or when the party is disbanded.
+#[table(
+    name = stdb_party_invite_v1,
+    public,
+    index(name = party_invitee_index, btree(columns = [party_id, invitee_id])),
+)]
+#[derive(Debug, Clone)]
+pub struct StdbPartyInviteV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub id: u64,
+
+    #[index(btree)]
+    pub party_id: Uuid,
+
+    #[index(btree)]
+    pub invitee_id: Uuid,
+
+    pub created_at: Timestamp,
+}
+
+/// Creates a party led by the current player.
+#[reducer]
+pub fn create_party_v1(ctx: &ReducerContext, max_size: u32) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.create_party(session.player_id, max_size)?;
+    Ok(())
+}
+
+/// Invites a player to the party of the current player, only the leader can invite.
+///
+/// The invitee joins with `accept_party_invite_v1`.
+#[reducer]
+pub fn invite_to_party_v1(ctx: &ReducerContext, party_id: Uuid, invitee_id: Uuid) -> ServiceResult<()> {
+    require_party_leader(ctx, &party_id)?;
+    ctx.require_not_blocked(&invitee_id)?;
+    ctx.invite_to_party(&party_id, invitee_id)?;
+    Ok(())
+}
+
+/// Joins a party the current player was invited to.
+#[reducer]
+pub fn accept_party_invite_v1(ctx: &ReducerContext, party_id: Uuid) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.accept_party_invite(&party_id, session.player_id)?;
+    Ok(())
+}
+
+/// Declines the invite of the current player to a party.
+#[reducer]
+pub fn decline_party_invite_v1(ctx: &ReducerContext, party_id: Uuid) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.decline_party_invite(&party_id, &session.player_id)?;
+    Ok(())
+}
+
+/// Leaves the party of the current player. When the leader leaves, the leadership passes to the next member.
+#[reducer]
+pub fn leave_party_v1(ctx: &ReducerContext) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.leave_party(&session.player_id)?;
+    Ok(())
+}
+
+/// Disbands the party of the current player, only the leader can disband.
+#[reducer]
+pub fn disband_party_v1(ctx: &ReducerContext, party_id: Uuid) -> ServiceResult<()> {
+    require_party_leader(ctx, &party_id)?;
+    ctx.disband_party(&party_id)?;
+    Ok(())
+}
+
+fn require_party_leader(ctx: &ReducerContext, party_id: &Uuid) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    let Some(party) = ctx.find_party(party_id) else {
+        return Err(PlayerError::party_not_found(party_id.clone()));
+    };
+    if party.leader_id != session.player_id {
+        return Err(PlayerError::not_party_leader(party_id.clone()));
+    }
+
+    Ok(())
+}
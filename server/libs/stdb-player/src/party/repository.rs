@@ -0,0 +1,500 @@
+use crate::{
+    error::PlayerError,
+    party::{
+        PARTY_MAX_SIZE, StdbPartyInviteV1, StdbPartyMemberV1, StdbPartyV1, stdb_party_invite_v1, stdb_party_member_v1,
+        stdb_party_v1,
+    },
+    player::repository::is_player_online,
+};
+use spacetimedb::{ReducerContext, Table};
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, UuidExt, validate_u32, validate_uuid};
+
+/// Repository trait for managing parties.
+pub trait PartyRepository {
+    fn find_party(&self, party_id: &Uuid) -> Option<StdbPartyV1>;
+
+    fn find_party_led_by(&self, leader_id: &Uuid) -> Option<StdbPartyV1>;
+
+    fn find_party_member(&self, player_id: &Uuid) -> Option<StdbPartyMemberV1>;
+
+    /// Returns the members of a party, ordered by when they joined.
+    fn find_party_members(&self, party_id: &Uuid) -> Vec<StdbPartyMemberV1>;
+
+    /// Creates a party, the leader is its first member.
+    ///
+    /// Validates max size (2-16).
+    ///
+    /// # Errors
+    /// Returns error if validation fails, the leader is already in a party or database operations fail.
+    fn create_party(&self, leader_id: Uuid, max_size: u32) -> ServiceResult<StdbPartyV1>;
+
+    fn find_party_invite(&self, party_id: &Uuid, invitee_id: &Uuid) -> Option<StdbPartyInviteV1>;
+
+    /// Invites a player to a party, returning the existing invite if there's one already.
+    ///
+    /// # Errors
+    /// Returns error if the party doesn't exist, it's full, the invitee is already in a party
+    /// or database operations fail.
+    fn invite_to_party(&self, party_id: &Uuid, invitee_id: Uuid) -> ServiceResult<StdbPartyInviteV1>;
+
+    /// Adds an invited player to the party, deleting all of their pending invites.
+    ///
+    /// # Errors
+    /// Returns error if the player wasn't invited, the party doesn't exist, it's full,
+    /// the player is already in a party or database operations fail.
+    fn accept_party_invite(&self, party_id: &Uuid, player_id: Uuid) -> ServiceResult<StdbPartyMemberV1>;
+
+    /// Deletes the invite of a player to a party.
+    ///
+    /// # Errors
+    /// Returns error if the player wasn't invited.
+    fn decline_party_invite(&self, party_id: &Uuid, player_id: &Uuid) -> ServiceResult<()>;
+
+    /// Removes a player from their party. When the leader leaves, the party is handed off.
+    ///
+    /// # Errors
+    /// Returns error if the player isn't in a party or database operations fail.
+    fn leave_party(&self, player_id: &Uuid) -> ServiceResult<()>;
+
+    /// Passes the leadership to the online member who joined first, or to the member who joined first
+    /// if none is online. Disbands the party if the leader is its only member.
+    ///
+    /// Returns the party, or `None` if it was disbanded.
+    ///
+    /// # Errors
+    /// Returns error if the party doesn't exist or database operations fail.
+    fn hand_off_party(&self, party_id: &Uuid) -> ServiceResult<Option<StdbPartyV1>>;
+
+    /// Deletes a party, all of its members and its pending invites.
+    ///
+    /// # Errors
+    /// Returns error if the party doesn't exist.
+    fn disband_party(&self, party_id: &Uuid) -> ServiceResult<()>;
+}
+
+impl PartyRepository for ReducerContext {
+    fn find_party(&self, party_id: &Uuid) -> Option<StdbPartyV1> {
+        self.db.stdb_party_v1().party_id().find(party_id)
+    }
+
+    fn find_party_led_by(&self, leader_id: &Uuid) -> Option<StdbPartyV1> {
+        self.db.stdb_party_v1().leader_id().filter(leader_id).next()
+    }
+
+    fn find_party_member(&self, player_id: &Uuid) -> Option<StdbPartyMemberV1> {
+        self.db.stdb_party_member_v1().player_id().find(player_id)
+    }
+
+    fn find_party_members(&self, party_id: &Uuid) -> Vec<StdbPartyMemberV1> {
+        let mut members: Vec<_> = self.db.stdb_party_member_v1().party_id().filter(party_id).collect();
+        members.sort_by_key(|member| member.id);
+        members
+    }
+
+    fn create_party(&self, leader_id: Uuid, max_size: u32) -> ServiceResult<StdbPartyV1> {
+        validate_uuid("leader_id", &leader_id)?;
+        validate_u32("max_size", max_size, 2, PARTY_MAX_SIZE)?;
+        if self.find_party_member(&leader_id).is_some() {
+            return Err(PlayerError::already_in_party(leader_id));
+        }
+
+        let party = self
+            .db
+            .stdb_party_v1()
+            .try_insert(StdbPartyV1 {
+                party_id: self.new_uuid_v7(),
+                leader_id: leader_id.clone(),
+                created_at: self.timestamp,
+                max_size,
+            })
+            .map_conflict_ctx("failed to insert party")?;
+        insert_member(self, &party.party_id, leader_id)?;
+
+        Ok(party)
+    }
+
+    fn find_party_invite(&self, party_id: &Uuid, invitee_id: &Uuid) -> Option<StdbPartyInviteV1> {
+        self.db
+            .stdb_party_invite_v1()
+            .party_invitee_index()
+            .filter((party_id, invitee_id))
+            .next()
+    }
+
+    fn invite_to_party(&self, party_id: &Uuid, invitee_id: Uuid) -> ServiceResult<StdbPartyInviteV1> {
+        validate_uuid("invitee_id", &invitee_id)?;
+        let Some(party) = self.find_party(party_id) else {
+            return Err(PlayerError::party_not_found(party_id.clone()));
+        };
+        if self.find_party_member(&invitee_id).is_some() {
+            return Err(PlayerError::already_in_party(invitee_id));
+        }
+        require_party_slot(&party, self.find_party_members(party_id).len())?;
+        if let Some(invite) = self.find_party_invite(party_id, &invitee_id) {
+            return Ok(invite);
+        }
+
+        self.db
+            .stdb_party_invite_v1()
+            .try_insert(StdbPartyInviteV1 {
+                id: 0,
+                party_id: party_id.clone(),
+                invitee_id,
+                created_at: self.timestamp,
+            })
+            .map_conflict_ctx("failed to insert party invite")
+    }
+
+    fn accept_party_invite(&self, party_id: &Uuid, player_id: Uuid) -> ServiceResult<StdbPartyMemberV1> {
+        check_party_invite_acceptable(
+            party_id,
+            &player_id,
+            self.find_party_invite(party_id, &player_id).is_some(),
+            self.find_party(party_id).as_ref(),
+            self.find_party_member(&player_id).is_some(),
+            self.find_party_members(party_id).len(),
+        )?;
+
+        let invites: Vec<_> = self.db.stdb_party_invite_v1().invitee_id().filter(&player_id).collect();
+        for invite_id in invites_cleared_on_join(&invites, &player_id) {
+            self.db.stdb_party_invite_v1().id().delete(invite_id);
+        }
+        insert_member(self, party_id, player_id)
+    }
+
+    fn decline_party_invite(&self, party_id: &Uuid, player_id: &Uuid) -> ServiceResult<()> {
+        let Some(invite) = self.find_party_invite(party_id, player_id) else {
+            return Err(PlayerError::party_invite_not_found(party_id.clone()));
+        };
+        self.db.stdb_party_invite_v1().id().delete(invite.id);
+
+        Ok(())
+    }
+
+    fn leave_party(&self, player_id: &Uuid) -> ServiceResult<()> {
+        let Some(member) = self.find_party_member(player_id) else {
+            return Err(PlayerError::not_in_party(player_id.clone()));
+        };
+
+        let Some(party) = self.find_party(&member.party_id) else {
+            self.db.stdb_party_member_v1().id().delete(member.id);
+            return Ok(());
+        };
+        let members = self.find_party_members(&party.party_id);
+        let change = party_change_on_leave(&party, player_id, &members, |member_id| is_player_online(self, member_id));
+        apply_party_change(self, party, change)?;
+        self.db.stdb_party_member_v1().id().delete(member.id);
+
+        Ok(())
+    }
+
+    fn hand_off_party(&self, party_id: &Uuid) -> ServiceResult<Option<StdbPartyV1>> {
+        let Some(party) = self.find_party(party_id) else {
+            return Err(PlayerError::party_not_found(party_id.clone()));
+        };
+
+        let members = self.find_party_members(party_id);
+        let change = party_change_on_hand_off(&party, &members, |member_id| is_player_online(self, member_id));
+        apply_party_change(self, party, change)
+    }
+
+    fn disband_party(&self, party_id: &Uuid) -> ServiceResult<()> {
+        if !self.db.stdb_party_v1().party_id().delete(party_id) {
+            return Err(PlayerError::party_not_found(party_id.clone()));
+        }
+        self.db.stdb_party_member_v1().party_id().delete(party_id);
+
+        let invites: Vec<_> = self.db.stdb_party_invite_v1().party_id().filter(party_id).collect();
+        for invite_id in invites_cleared_on_disband(&invites, party_id) {
+            self.db.stdb_party_invite_v1().id().delete(invite_id);
+        }
+
+        Ok(())
+    }
+}
+
+/// What happens to a party when its leader leaves or goes offline.
+#[derive(Debug, PartialEq)]
+pub(crate) enum PartyChange {
+    Unchanged,
+    NewLeader(Uuid),
+    Disbanded,
+}
+
+/// Writes the change, returning the party or `None` if it was disbanded.
+fn apply_party_change(ctx: &ReducerContext, mut party: StdbPartyV1, change: PartyChange) -> ServiceResult<Option<StdbPartyV1>> {
+    match change {
+        PartyChange::Unchanged => Ok(Some(party)),
+        PartyChange::NewLeader(leader_id) => {
+            party.leader_id = leader_id;
+            ctx.db
+                .stdb_party_v1()
+                .party_id()
+                .try_insert_or_update(party)
+                .map_conflict_ctx("failed to update party leader")
+                .map(Some)
+        },
+        PartyChange::Disbanded => {
+            ctx.disband_party(&party.party_id)?;
+            Ok(None)
+        },
+    }
+}
+
+fn insert_member(ctx: &ReducerContext, party_id: &Uuid, player_id: Uuid) -> ServiceResult<StdbPartyMemberV1> {
+    ctx.db
+        .stdb_party_member_v1()
+        .try_insert(StdbPartyMemberV1 {
+            id: 0,
+            party_id: party_id.clone(),
+            player_id,
+            joined_at: ctx.timestamp,
+        })
+        .map_conflict_ctx("failed to insert party member")
+}
+
+/// Fails if the party already has `members` members out of its max size.
+pub(crate) fn require_party_slot(party: &StdbPartyV1, members: usize) -> ServiceResult<()> {
+    if members >= party.max_size as usize {
+        return Err(PlayerError::party_full(party.party_id.clone()));
+    }
+    Ok(())
+}
+
+/// Fails unless the player was invited, the party still exists and has a free slot, and the player
+/// isn't in a party yet.
+pub(crate) fn check_party_invite_acceptable(
+    party_id: &Uuid,
+    player_id: &Uuid,
+    is_invited: bool,
+    party: Option<&StdbPartyV1>,
+    is_in_party: bool,
+    members: usize,
+) -> ServiceResult<()> {
+    if !is_invited {
+        return Err(PlayerError::party_invite_not_found(party_id.clone()));
+    }
+    let Some(party) = party else {
+        return Err(PlayerError::party_not_found(party_id.clone()));
+    };
+    if is_in_party {
+        return Err(PlayerError::already_in_party(player_id.clone()));
+    }
+    require_party_slot(party, members)
+}
+
+/// The online member who joined first other than the current leader, or the member who joined first if none
+/// is online. `members` must be ordered by when they joined.
+pub(crate) fn next_leader(leader_id: &Uuid, members: &[StdbPartyMemberV1], is_online: impl Fn(&Uuid) -> bool) -> Option<Uuid> {
+    let mut candidates = members.iter().filter(|member| member.player_id != *leader_id);
+    let first = candidates.clone().next();
+    candidates
+        .find(|member| is_online(&member.player_id))
+        .or(first)
+        .map(|member| member.player_id.clone())
+}
+
+/// Hands the party off to the [`next_leader`], or disbands it if there's none.
+pub(crate) fn party_change_on_hand_off(
+    party: &StdbPartyV1,
+    members: &[StdbPartyMemberV1],
+    is_online: impl Fn(&Uuid) -> bool,
+) -> PartyChange {
+    match next_leader(&party.leader_id, members, is_online) {
+        Some(leader_id) => PartyChange::NewLeader(leader_id),
+        None => PartyChange::Disbanded,
+    }
+}
+
+/// Members leave without changing the party, the leader leaving hands it off.
+pub(crate) fn party_change_on_leave(
+    party: &StdbPartyV1,
+    player_id: &Uuid,
+    members: &[StdbPartyMemberV1],
+    is_online: impl Fn(&Uuid) -> bool,
+) -> PartyChange {
+    if party.leader_id != *player_id {
+        return PartyChange::Unchanged;
+    }
+    party_change_on_hand_off(party, members, is_online)
+}
+
+/// Once a player joins a party, every invite they received is deleted, whichever party sent it.
+pub(crate) fn invites_cleared_on_join(invites: &[StdbPartyInviteV1], player_id: &Uuid) -> Vec<u64> {
+    invites
+        .iter()
+        .filter(|invite| invite.invitee_id == *player_id)
+        .map(|invite| invite.id)
+        .collect()
+}
+
+/// Once a party is disbanded, every invite it sent is deleted.
+pub(crate) fn invites_cleared_on_disband(invites: &[StdbPartyInviteV1], party_id: &Uuid) -> Vec<u64> {
+    invites
+        .iter()
+        .filter(|invite| invite.party_id == *party_id)
+        .map(|invite| invite.id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{OTHER_PLAYER_ID, PLAYER_ID, at, uuid};
+
+    const THIRD_PLAYER_ID: &str = "01890a5d-ac96-774b-bcce-b302099a8059";
+    const PARTY_ID: &str = "01890a5d-ac96-774b-bcce-b302099a8060";
+    const OTHER_PARTY_ID: &str = "01890a5d-ac96-774b-bcce-b302099a8061";
+
+    fn party(leader_id: &str) -> StdbPartyV1 {
+        StdbPartyV1 {
+            party_id: uuid(PARTY_ID),
+            leader_id: uuid(leader_id),
+            created_at: at(0),
+            max_size: 4,
+        }
+    }
+
+    fn members(player_ids: &[&str]) -> Vec<StdbPartyMemberV1> {
+        player_ids
+            .iter()
+            .enumerate()
+            .map(|(index, player_id)| StdbPartyMemberV1 {
+                id: index as u64 + 1,
+                party_id: uuid(PARTY_ID),
+                player_id: uuid(player_id),
+                joined_at: at(index as i64),
+            })
+            .collect()
+    }
+
+    fn invite(id: u64, party_id: &str, invitee_id: &str) -> StdbPartyInviteV1 {
+        StdbPartyInviteV1 {
+            id,
+            party_id: uuid(party_id),
+            invitee_id: uuid(invitee_id),
+            created_at: at(0),
+        }
+    }
+
+    fn online(player_ids: &'static [&'static str]) -> impl Fn(&Uuid) -> bool {
+        move |player_id| player_ids.contains(&player_id.as_str())
+    }
+
+    #[test]
+    fn test_require_party_slot() {
+        let party = party(PLAYER_ID);
+        assert!(require_party_slot(&party, 1).is_ok());
+        assert!(require_party_slot(&party, 3).is_ok());
+        assert!(require_party_slot(&party, 4).is_err());
+        assert!(require_party_slot(&party, 5).is_err());
+    }
+
+    #[test]
+    fn test_next_leader_prefers_online_members() {
+        let leader_id = uuid(PLAYER_ID);
+        let members = members(&[PLAYER_ID, OTHER_PLAYER_ID, THIRD_PLAYER_ID]);
+
+        assert_eq!(next_leader(&leader_id, &members, online(&[])), Some(uuid(OTHER_PLAYER_ID)));
+        assert_eq!(
+            next_leader(&leader_id, &members, online(&[OTHER_PLAYER_ID, THIRD_PLAYER_ID])),
+            Some(uuid(OTHER_PLAYER_ID))
+        );
+        assert_eq!(
+            next_leader(&leader_id, &members, online(&[PLAYER_ID, THIRD_PLAYER_ID])),
+            Some(uuid(THIRD_PLAYER_ID))
+        );
+        assert_eq!(next_leader(&leader_id, &members[..1], online(&[PLAYER_ID])), None);
+        assert_eq!(next_leader(&leader_id, &[], online(&[])), None);
+    }
+
+    #[test]
+    fn test_member_leaving_keeps_the_party() {
+        let party = party(PLAYER_ID);
+        let members = members(&[PLAYER_ID, OTHER_PLAYER_ID]);
+        assert_eq!(
+            party_change_on_leave(&party, &uuid(OTHER_PLAYER_ID), &members, online(&[])),
+            PartyChange::Unchanged
+        );
+    }
+
+    #[test]
+    fn test_leader_leaving_hands_off_the_party() {
+        let party = party(PLAYER_ID);
+        let members = members(&[PLAYER_ID, OTHER_PLAYER_ID, THIRD_PLAYER_ID]);
+        assert_eq!(
+            party_change_on_leave(&party, &uuid(PLAYER_ID), &members, online(&[THIRD_PLAYER_ID])),
+            PartyChange::NewLeader(uuid(THIRD_PLAYER_ID))
+        );
+
+        // A disconnected leader is handed off the same way
+        assert_eq!(
+            party_change_on_hand_off(&party, &members, online(&[])),
+            PartyChange::NewLeader(uuid(OTHER_PLAYER_ID))
+        );
+    }
+
+    #[test]
+    fn test_last_member_leaving_disbands_the_party() {
+        let party = party(PLAYER_ID);
+        assert_eq!(
+            party_change_on_leave(&party, &uuid(PLAYER_ID), &members(&[PLAYER_ID]), online(&[PLAYER_ID])),
+            PartyChange::Disbanded
+        );
+        assert_eq!(
+            party_change_on_hand_off(&party, &members(&[PLAYER_ID]), online(&[])),
+            PartyChange::Disbanded
+        );
+    }
+
+    #[test]
+    fn test_check_party_invite_acceptable() {
+        let party_id = uuid(PARTY_ID);
+        let player_id = uuid(OTHER_PLAYER_ID);
+        let party = party(PLAYER_ID);
+        let check = |is_invited, party, is_in_party, members| {
+            check_party_invite_acceptable(&party_id, &player_id, is_invited, party, is_in_party, members)
+                .map_err(|error| error.message().to_string())
+        };
+
+        assert!(check(true, Some(&party), false, 3).is_ok());
+        assert_eq!(
+            check(false, Some(&party), false, 1).unwrap_err(),
+            PlayerError::party_invite_not_found(party_id.clone()).message()
+        );
+        assert_eq!(
+            check(true, None, false, 0).unwrap_err(),
+            PlayerError::party_not_found(party_id.clone()).message()
+        );
+        assert_eq!(
+            check(true, Some(&party), true, 1).unwrap_err(),
+            PlayerError::already_in_party(player_id.clone()).message()
+        );
+        assert_eq!(
+            check(true, Some(&party), false, 4).unwrap_err(),
+            PlayerError::party_full(party_id.clone()).message()
+        );
+    }
+
+    #[test]
+    fn test_invites_cleared_on_join() {
+        let invites = [
+            invite(1, PARTY_ID, OTHER_PLAYER_ID),
+            invite(2, OTHER_PARTY_ID, OTHER_PLAYER_ID),
+            invite(3, PARTY_ID, THIRD_PLAYER_ID),
+        ];
+        assert_eq!(invites_cleared_on_join(&invites, &uuid(OTHER_PLAYER_ID)), [1, 2]);
+        assert!(invites_cleared_on_join(&invites, &uuid(PLAYER_ID)).is_empty());
+    }
+
+    #[test]
+    fn test_invites_cleared_on_disband() {
+        let invites = [
+            invite(1, PARTY_ID, OTHER_PLAYER_ID),
+            invite(2, OTHER_PARTY_ID, OTHER_PLAYER_ID),
+            invite(3, PARTY_ID, THIRD_PLAYER_ID),
+        ];
+        assert_eq!(invites_cleared_on_disband(&invites, &uuid(PARTY_ID)), [1, 3]);
+        assert_eq!(invites_cleared_on_disband(&invites, &uuid(OTHER_PARTY_ID)), [2]);
+    }
+}
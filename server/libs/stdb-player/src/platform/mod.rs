@@ -0,0 +1,98 @@
+use crate::{platform::repository::PlatformRepository, prelude::PlayerExt};
+use spacetimedb::{Filter, ReducerContext, SpacetimeType, Timestamp, client_visibility_filter, reducer, table};
+use stdb_common::prelude::{ServiceResult, Uuid, validate_str};
+
+pub mod repository;
+
+pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+#[client_visibility_filter]
+const STDB_PLATFORM_AUTH_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select p.*
+    from stdb_platform_auth_v1 p
+    join stdb_own_player_session_v1 s
+        on s.player_id = p.player_id
+    where s.session_id = :sender
+"#,
+);
+
+/// Platform auth table - links players to their Game Center or Google Play accounts.
+///
+/// Only visible to the owning player. A platform account is linked to at most one player,
+/// and a player has at most one linked account per platform.
+#[table(
+    name = stdb_platform_auth_v1,
+    public,
+    index(name = platform_user_index, btree(columns = [platform, platform_user_id])),
+    index(name = player_platform_index, btree(columns = [player_id, platform])),
+)]
+#[derive(Debug, Clone)]
+pub struct StdbPlatformAuthV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub id: u64,
+
+    pub player_id: Uuid,
+
+    pub platform: PlatformV1,
+    pub platform_user_id: String,
+
+    pub linked_at: Timestamp,
+}
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, SpacetimeType)]
+pub enum PlatformV1 {
+    GameCenter,
+    GooglePlay,
+}
+
+/// Links a platform account to the current player.
+///
+/// `platform_token` must be verified against the platform by an external call before this reducer
+/// is invoked, modules can't reach the platform APIs. The token itself is not stored.
+#[reducer]
+pub fn link_platform_account_v1(
+    ctx: &ReducerContext,
+    platform: PlatformV1,
+    platform_user_id: String,
+    platform_token: String,
+) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    validate_str("platform_token", &platform_token, 1, 4096)?;
+    ctx.link_platform_account(session.player_id, platform, platform_user_id)?;
+    Ok(())
+}
+
+/// Upgrades the anonymous player of the current session by claiming a platform account.
+///
+/// Fails with a conflict holding both player IDs when the platform account already belongs to
+/// another player. Merging the two players is a separate flow and isn't handled here.
+/// `platform_token` must be verified the same way as in `link_platform_account_v1`.
+#[reducer]
+pub fn claim_account_v1(
+    ctx: &ReducerContext,
+    platform: PlatformV1,
+    platform_user_id: String,
+    platform_token: String,
+) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    validate_str("platform_token", &platform_token, 1, 4096)?;
+    ctx.claim_account(session.player_id, platform, platform_user_id)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn unlink_platform_account_v1(ctx: &ReducerContext, platform: PlatformV1) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.unlink_platform_account(&session.player_id, platform)?;
+    Ok(())
+}
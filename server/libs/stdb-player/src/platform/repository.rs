@@ -0,0 +1,136 @@
+use crate::{
+    error::PlayerError,
+    platform::{PlatformV1, StdbPlatformAuthV1, stdb_platform_auth_v1},
+};
+use spacetimedb::{ReducerContext, Table};
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, validate_str, validate_uuid};
+
+/// Repository trait for managing platform account links.
+///
+/// Links are stored once the platform token was verified, this repository doesn't talk to the platforms.
+pub trait PlatformRepository {
+    /// Finds the link of a platform account.
+    ///
+    /// Returns `None` if the platform account isn't linked to any player.
+    fn find_by_platform_id(&self, platform: PlatformV1, platform_user_id: &str) -> Option<StdbPlatformAuthV1>;
+
+    /// Finds the link of a player on a platform.
+    ///
+    /// Returns `None` if the player has no account linked on the platform.
+    fn find_platform_link_for_player(&self, player_id: &Uuid, platform: PlatformV1) -> Option<StdbPlatformAuthV1>;
+
+    /// Links a platform account to a player.
+    ///
+    /// Linking the same account again is a no-op and returns the existing link.
+    /// Validates platform user ID (1-128 chars).
+    ///
+    /// # Errors
+    /// Returns error if the platform account is linked to another player, the player already has
+    /// a different account on the platform, validation fails or database operations fail.
+    fn link_platform_account(
+        &self,
+        player_id: Uuid,
+        platform: PlatformV1,
+        platform_user_id: impl Into<String>,
+    ) -> ServiceResult<StdbPlatformAuthV1>;
+
+    /// Claims a platform account for a player who signed in anonymously.
+    ///
+    /// Same as [`PlatformRepository::link_platform_account`], except that an account already linked
+    /// to another player reports both player IDs, so the client can offer to merge the accounts.
+    ///
+    /// # Errors
+    /// Returns a conflict error if the platform account is linked to another player, or any error
+    /// of [`PlatformRepository::link_platform_account`].
+    fn claim_account(
+        &self,
+        player_id: Uuid,
+        platform: PlatformV1,
+        platform_user_id: impl Into<String>,
+    ) -> ServiceResult<StdbPlatformAuthV1>;
+
+    /// Removes the link of a player on a platform.
+    ///
+    /// Returns `false` if the player had no account linked on the platform.
+    ///
+    /// # Errors
+    /// Returns error if validation fails.
+    fn unlink_platform_account(&self, player_id: &Uuid, platform: PlatformV1) -> ServiceResult<bool>;
+}
+
+impl PlatformRepository for ReducerContext {
+    fn find_by_platform_id(&self, platform: PlatformV1, platform_user_id: &str) -> Option<StdbPlatformAuthV1> {
+        self.db
+            .stdb_platform_auth_v1()
+            .platform_user_index()
+            .filter((platform, platform_user_id))
+            .next()
+    }
+
+    fn find_platform_link_for_player(&self, player_id: &Uuid, platform: PlatformV1) -> Option<StdbPlatformAuthV1> {
+        self.db
+            .stdb_platform_auth_v1()
+            .player_platform_index()
+            .filter((player_id, platform))
+            .next()
+    }
+
+    fn link_platform_account(
+        &self,
+        player_id: Uuid,
+        platform: PlatformV1,
+        platform_user_id: impl Into<String>,
+    ) -> ServiceResult<StdbPlatformAuthV1> {
+        let platform_user_id = platform_user_id.into();
+        validate_uuid("player_id", &player_id)?;
+        validate_str("platform_user_id", &platform_user_id, 1, 128)?;
+
+        if let Some(link) = self.find_by_platform_id(platform, &platform_user_id) {
+            if link.player_id == player_id {
+                return Ok(link);
+            }
+            return Err(PlayerError::platform_account_already_linked(platform_user_id));
+        }
+
+        if self.find_platform_link_for_player(&player_id, platform).is_some() {
+            return Err(PlayerError::platform_already_linked(player_id));
+        }
+
+        self.db
+            .stdb_platform_auth_v1()
+            .try_insert(StdbPlatformAuthV1 {
+                id: 0,
+                player_id,
+                platform,
+                platform_user_id,
+                linked_at: self.timestamp,
+            })
+            .map_conflict_ctx("failed to link platform account")
+    }
+
+    fn claim_account(
+        &self,
+        player_id: Uuid,
+        platform: PlatformV1,
+        platform_user_id: impl Into<String>,
+    ) -> ServiceResult<StdbPlatformAuthV1> {
+        let platform_user_id = platform_user_id.into();
+
+        if let Some(link) = self.find_by_platform_id(platform, &platform_user_id)
+            && link.player_id != player_id
+        {
+            return Err(PlayerError::account_claimed_by_another_player(player_id, link.player_id));
+        }
+
+        self.link_platform_account(player_id, platform, platform_user_id)
+    }
+
+    fn unlink_platform_account(&self, player_id: &Uuid, platform: PlatformV1) -> ServiceResult<bool> {
+        validate_uuid("player_id", player_id)?;
+
+        let Some(link) = self.find_platform_link_for_player(player_id, platform) else {
+            return Ok(false);
+        };
+        Ok(self.db.stdb_platform_auth_v1().id().delete(link.id))
+    }
+}
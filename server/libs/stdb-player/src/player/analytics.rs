@@ -0,0 +1,145 @@
+use spacetimedb::{ReducerContext, SpacetimeType, Table, Timestamp, table};
+use std::time::Duration;
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid};
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, SpacetimeType)]
+pub enum SessionEventV1 {
+    SignedIn,
+    SignedOut,
+}
+
+/// Session event table - every sign-in and sign-out of each player, for analytics.
+///
+/// Only visible to the server. Sign-outs of sessions that were already offline aren't recorded.
+#[table(name = stdb_session_event_v1)]
+#[derive(Debug, Clone)]
+pub struct StdbSessionEventV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub event_id: u64,
+
+    #[index(btree)]
+    pub player_id: Uuid,
+
+    pub event: SessionEventV1,
+    pub occurred_at: Timestamp,
+}
+
+/// Repository trait for session analytics.
+pub trait SessionAnalyticsRepository {
+    /// Returns the session events of a player from `from` (inclusive) to `to` (exclusive),
+    /// ordered by when they occurred.
+    fn session_events_for_player(&self, player_id: &Uuid, from: Timestamp, to: Timestamp) -> Vec<StdbSessionEventV1>;
+
+    /// Returns how long each past session of a player lasted, oldest first.
+    ///
+    /// Sessions on different devices that overlap count as a single session, from the first
+    /// sign-in until the last sign-out. A session that is still online isn't included.
+    fn compute_session_durations(&self, player_id: &Uuid) -> Vec<Duration>;
+
+    /// Appends a session event of a player at the current timestamp.
+    ///
+    /// # Errors
+    /// Returns error if database operations fail.
+    fn record_session_event(&self, player_id: Uuid, event: SessionEventV1) -> ServiceResult<StdbSessionEventV1>;
+}
+
+impl SessionAnalyticsRepository for ReducerContext {
+    fn session_events_for_player(&self, player_id: &Uuid, from: Timestamp, to: Timestamp) -> Vec<StdbSessionEventV1> {
+        let mut events: Vec<_> = self
+            .db
+            .stdb_session_event_v1()
+            .player_id()
+            .filter(player_id)
+            .filter(|event| (from..to).contains(&event.occurred_at))
+            .collect();
+        events.sort_by_key(|event| (event.occurred_at, event.event_id));
+        events
+    }
+
+    fn compute_session_durations(&self, player_id: &Uuid) -> Vec<Duration> {
+        let mut events: Vec<_> = self.db.stdb_session_event_v1().player_id().filter(player_id).collect();
+        events.sort_by_key(|event| (event.occurred_at, event.event_id));
+        pair_session_events(&events)
+    }
+
+    fn record_session_event(&self, player_id: Uuid, event: SessionEventV1) -> ServiceResult<StdbSessionEventV1> {
+        self.db
+            .stdb_session_event_v1()
+            .try_insert(StdbSessionEventV1 {
+                event_id: 0,
+                player_id,
+                event,
+                occurred_at: self.timestamp,
+            })
+            .map_conflict_ctx("failed to insert session event")
+    }
+}
+
+/// Pairs sign-ins with sign-outs, `events` must be ordered by when they occurred.
+///
+/// Tracks how many sessions are online, a session starts when the count leaves 0 and ends when it gets back to 0.
+/// Sign-outs without a sign-in, e.g. recorded before the feature was enabled, are skipped.
+pub(crate) fn pair_session_events(events: &[StdbSessionEventV1]) -> Vec<Duration> {
+    let mut durations = Vec::new();
+    let mut online = 0_u32;
+    let mut started_at = Timestamp::UNIX_EPOCH;
+
+    for event in events {
+        match event.event {
+            SessionEventV1::SignedIn => {
+                if online == 0 {
+                    started_at = event.occurred_at;
+                }
+                online = online.saturating_add(1);
+            },
+            SessionEventV1::SignedOut if online > 0 => {
+                online -= 1;
+                if online == 0 {
+                    durations.push(event.occurred_at.duration_since(started_at).unwrap_or_default());
+                }
+            },
+            SessionEventV1::SignedOut => {},
+        }
+    }
+
+    durations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{PLAYER_ID, at, uuid};
+
+    fn event(secs: i64, event: SessionEventV1) -> StdbSessionEventV1 {
+        StdbSessionEventV1 {
+            event_id: 0,
+            player_id: uuid(PLAYER_ID),
+            event,
+            occurred_at: at(secs),
+        }
+    }
+
+    #[test]
+    fn test_pair_session_events() {
+        use SessionEventV1::{SignedIn, SignedOut};
+
+        let events = [
+            event(5, SignedOut),
+            event(10, SignedIn),
+            event(70, SignedOut),
+            event(100, SignedIn),
+            // A second device signs in while the first one is still online
+            event(110, SignedIn),
+            event(150, SignedOut),
+            event(200, SignedOut),
+            event(300, SignedIn),
+        ];
+
+        assert_eq!(
+            pair_session_events(&events),
+            vec![Duration::from_secs(60), Duration::from_secs(100)]
+        );
+        assert!(pair_session_events(&[]).is_empty());
+    }
+}
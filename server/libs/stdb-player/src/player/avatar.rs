@@ -0,0 +1,122 @@
+use spacetimedb::{ReducerContext, Table, reducer, table};
+use stdb_common::prelude::{ResultExt, ServiceError, ServiceResult, Uuid, ValidateExt, validate_str};
+
+/// Avatar preset table - the avatars players can pick with `update_avatar_v1`, visible to all players.
+///
+/// Managed by the server with `register_avatar_preset_v1`. While no preset is registered, any avatar is accepted.
+#[table(name = stdb_avatar_preset_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbAvatarPresetV1 {
+    #[primary_key]
+    pub avatar_id: String,
+
+    pub display_name: String,
+
+    /// Premium avatars are locked until the player reaches `unlock_level`.
+    pub is_premium: bool,
+    pub unlock_level: u32,
+}
+
+/// Creates or replaces an avatar preset. Server-only.
+#[reducer]
+pub fn register_avatar_preset_v1(
+    ctx: &ReducerContext,
+    avatar_id: String,
+    display_name: String,
+    is_premium: bool,
+    unlock_level: u32,
+) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.upsert_avatar_preset(StdbAvatarPresetV1 {
+        avatar_id,
+        display_name,
+        is_premium,
+        unlock_level,
+    })?;
+    Ok(())
+}
+
+/// Repository trait for avatar presets.
+pub trait AvatarRepository {
+    fn find_avatar_preset(&self, avatar_id: &str) -> Option<StdbAvatarPresetV1>;
+
+    /// Creates or replaces an avatar preset.
+    ///
+    /// Validates avatar ID (8-64 chars, like player avatars) and display name (1-64 chars).
+    ///
+    /// # Errors
+    /// Returns error if validation fails or database operations fail.
+    fn upsert_avatar_preset(&self, preset: StdbAvatarPresetV1) -> ServiceResult<StdbAvatarPresetV1>;
+
+    /// Requires that the player can pick the avatar.
+    ///
+    /// # Errors
+    /// Returns `ServiceError::BadRequest` if presets are registered and the avatar isn't one of them,
+    /// or it's premium and the player's level is below its unlock level.
+    fn require_avatar_unlocked(&self, player_id: &Uuid, avatar_id: &str) -> ServiceResult<()>;
+}
+
+impl AvatarRepository for ReducerContext {
+    fn find_avatar_preset(&self, avatar_id: &str) -> Option<StdbAvatarPresetV1> {
+        self.db.stdb_avatar_preset_v1().avatar_id().find(avatar_id.to_string())
+    }
+
+    fn upsert_avatar_preset(&self, preset: StdbAvatarPresetV1) -> ServiceResult<StdbAvatarPresetV1> {
+        validate_str("avatar_id", &preset.avatar_id, 8, 64)?;
+        validate_str("display_name", &preset.display_name, 1, 64)?;
+
+        self.db
+            .stdb_avatar_preset_v1()
+            .avatar_id()
+            .try_insert_or_update(preset)
+            .map_conflict_ctx("failed to insert or update avatar preset")
+    }
+
+    fn require_avatar_unlocked(&self, player_id: &Uuid, avatar_id: &str) -> ServiceResult<()> {
+        if self.db.stdb_avatar_preset_v1().count() == 0 {
+            return Ok(());
+        }
+
+        match self.find_avatar_preset(avatar_id) {
+            Some(preset) if is_avatar_unlocked(&preset, player_level(self, player_id)) => Ok(()),
+            _ => Err(ServiceError::BadRequest("invalid avatar".into())),
+        }
+    }
+}
+
+#[cfg(feature = "experience")]
+fn player_level(ctx: &ReducerContext, player_id: &Uuid) -> u32 {
+    use crate::experience::repository::ExperienceRepository;
+    ctx.find_player_xp(player_id).map_or(0, |player_xp| player_xp.level)
+}
+
+/// Without the experience feature every player is level 0, so only premium avatars unlocked at level 0 can be picked.
+#[cfg(not(feature = "experience"))]
+fn player_level(_ctx: &ReducerContext, _player_id: &Uuid) -> u32 {
+    0
+}
+
+pub(crate) fn is_avatar_unlocked(preset: &StdbAvatarPresetV1, level: u32) -> bool {
+    !preset.is_premium || level >= preset.unlock_level
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_avatar_unlocked() {
+        let mut preset = StdbAvatarPresetV1 {
+            avatar_id: "golden_knight".to_string(),
+            display_name: "Golden Knight".to_string(),
+            is_premium: false,
+            unlock_level: 10,
+        };
+        assert!(is_avatar_unlocked(&preset, 0));
+
+        preset.is_premium = true;
+        assert!(!is_avatar_unlocked(&preset, 9));
+        assert!(is_avatar_unlocked(&preset, 10));
+        assert!(is_avatar_unlocked(&preset, 11));
+    }
+}
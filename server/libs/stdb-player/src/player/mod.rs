@@ -1,23 +1,132 @@
+//! Player and session tables, lifecycle hooks and reducers.
+//!
+//! # Migration
+//! While the library is pre-1.0, new columns are added to the existing V1 tables
+//! (e.g. `device_type`, `client_version` and `expired_at` on `StdbOwnPlayerSessionV1`,
+//! `display_name_key`, `bio`, `login_streak`, `referred_by` and `locale` on `StdbOwnPlayerV1`,
+//! `rank` and `locale` on `StdbPubPlayerCardV1`).
+//! SpacetimeDB can't add columns to an existing table, so modules published with a previous schema
+//! must be republished with `spacetime publish --delete-data`.
+
 use crate::{
-    player::repository::{PlayerRepository, PlayerSessionRepository},
+    player::{
+        avatar::AvatarRepository,
+        rank::RankV1,
+        repository::{PlayerRepository, PlayerSessionRepository, is_display_name_rename, normalize_display_name},
+        stats::{
+            ONLINE_COUNT_SNAPSHOT_INTERVAL_SECS, StdbPrivOnlineCountSnapshotScheduleV1,
+            stdb_priv_online_count_snapshot_schedule_v1,
+        },
+    },
     prelude::PlayerExt,
 };
-use spacetimedb::{Filter, Identity, ReducerContext, Timestamp, client_visibility_filter, reducer, table};
-use stdb_common::prelude::{ServiceResult, Uuid};
+use log::warn;
+use spacetimedb::{
+    Filter, Identity, ReducerContext, ScheduleAt, SpacetimeType, Table, Timestamp, client_visibility_filter, reducer, table,
+};
+use std::time::Duration;
+use stdb_common::{
+    duration::DurationExt,
+    prelude::{ResultExt, ServiceResult, Uuid, ValidateExt},
+};
 
+#[cfg(feature = "analytics")]
+pub mod analytics;
+pub mod avatar;
+pub mod rank;
 pub mod repository;
+pub mod stats;
+pub mod words;
+
+/// Sessions without a heartbeat for this long are marked as offline.
+pub const SESSION_HEARTBEAT_TIMEOUT_SECS: u64 = 5 * 60;
+
+/// Offline sessions of players signed out for longer than this are expired and must re-authenticate.
+pub const SESSION_EXPIRY: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Interval between two runs of `cleanup_stale_sessions_v1`.
+pub const SESSION_CLEANUP_INTERVAL_SECS: u64 = 60;
+
+/// Minimum time between two display name changes of the same player (7 days).
+pub const DISPLAY_NAME_CHANGE_COOLDOWN: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// How long the display name of a deactivated player stays reserved (90 days).
+pub const DISPLAY_NAME_TOMBSTONE_DURATION: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+/// Interval between two runs of `prune_display_name_tombstones_v1`.
+pub const DISPLAY_NAME_TOMBSTONE_CLEANUP_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+/// Number of previous display names kept per player.
+pub const DISPLAY_NAME_HISTORY_CAP: usize = 10;
+
+/// Locale of new players, until they pick one with `update_player_locale_v1`.
+pub const DEFAULT_PLAYER_LOCALE: &str = "en";
+
+/// Maximum size of the player settings JSON, in bytes.
+pub const PLAYER_SETTINGS_MAX_BYTES: u64 = 4096;
+
+/// Maximum number of metadata entries per player.
+pub const PLAYER_METADATA_CAP: usize = 100;
+
+/// Maximum number of players each player can refer.
+pub const REFERRALS_CAP: usize = 100;
+
+/// XP granted to the referrer by `grant_referral_reward_v1`.
+#[cfg(feature = "experience")]
+pub const REFERRAL_REWARD_XP: u64 = 500;
+
+/// Soft currency granted to the referrer by `grant_referral_reward_v1`.
+#[cfg(feature = "currency")]
+pub const REFERRAL_REWARD_SOFT_CURRENCY: u64 = 100;
+
+pub(crate) fn stdb_init(ctx: &ReducerContext) -> ServiceResult<()> {
+    if ctx.db.stdb_priv_session_cleanup_schedule_v1().count() == 0 {
+        ctx.db
+            .stdb_priv_session_cleanup_schedule_v1()
+            .try_insert(StdbPrivSessionCleanupScheduleV1 {
+                scheduled_id: 0,
+                scheduled_at: Duration::from_secs(SESSION_CLEANUP_INTERVAL_SECS).into(),
+            })
+            .map_conflict_ctx("failed to schedule stale sessions cleanup")?;
+    }
+
+    if ctx.db.stdb_priv_display_name_tombstone_cleanup_schedule_v1().count() == 0 {
+        ctx.db
+            .stdb_priv_display_name_tombstone_cleanup_schedule_v1()
+            .try_insert(StdbPrivDisplayNameTombstoneCleanupScheduleV1 {
+                scheduled_id: 0,
+                scheduled_at: Duration::from_secs(DISPLAY_NAME_TOMBSTONE_CLEANUP_INTERVAL_SECS).into(),
+            })
+            .map_conflict_ctx("failed to schedule display name tombstones cleanup")?;
+    }
+
+    if ctx.db.stdb_priv_online_count_snapshot_schedule_v1().count() == 0 {
+        ctx.db
+            .stdb_priv_online_count_snapshot_schedule_v1()
+            .try_insert(StdbPrivOnlineCountSnapshotScheduleV1 {
+                scheduled_id: 0,
+                scheduled_at: Duration::from_secs(ONLINE_COUNT_SNAPSHOT_INTERVAL_SECS).into(),
+            })
+            .map_conflict_ctx("failed to schedule online count snapshots")?;
+    }
 
-pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
     Ok(())
 }
 
-pub(crate) fn stdb_identity_connected(ctx: &ReducerContext) -> ServiceResult<()> {
-    ctx.sign_in_session(ctx.sender)?;
-    Ok(())
+pub(crate) fn stdb_identity_connected(
+    ctx: &ReducerContext,
+    device_type: DeviceTypeV1,
+    client_version: String,
+) -> ServiceResult<bool> {
+    let (_, is_new_player) = ctx.sign_in_session(ctx.sender, device_type, client_version)?;
+    Ok(is_new_player)
 }
 
 pub(crate) fn stdb_identity_disconnected(ctx: &ReducerContext) {
-    let _ = ctx.sign_out_session(ctx.sender);
+    if let Err(error) = ctx.sign_out_session(ctx.sender) {
+        warn!("failed to sign out disconnected session: {:?}", error.unwrap_chain());
+    }
+    ctx.db.stdb_session_heartbeat_v1().session_id().delete(ctx.sender);
 }
 
 #[client_visibility_filter]
@@ -38,6 +147,17 @@ const STDB_OWN_PLAYER_V1_FILTER: Filter = Filter::Sql(
 "#,
 );
 
+#[client_visibility_filter]
+const STDB_PLAYER_SETTINGS_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select p.*
+    from stdb_player_settings_v1 p
+    join stdb_own_player_session_v1 s
+        on s.player_id = p.player_id
+    where s.session_id = :sender
+"#,
+);
+
 /// Session mapping table - tracks active sessions for player authentication.
 ///
 /// Different devices/clients for the same player can have separate sessions.
@@ -51,13 +171,44 @@ pub struct StdbOwnPlayerSessionV1 {
     #[index(btree)]
     pub player_id: Uuid,
 
+    #[index(btree)]
     pub is_online: bool,
+
+    pub device_type: DeviceTypeV1,
+
+    /// Semver-like version of the client that opened the session.
+    pub client_version: String,
+
+    /// Set by `cleanup_stale_sessions_v1` once the session expired, see [`SESSION_EXPIRY`].
+    /// The row is kept so the identity gets its player back, signing in again clears it.
+    ///
+    /// The mark keeps the session expired even if the player signs out of another session later, and
+    /// blocks heartbeats from setting it back online: only connecting again, which signs it in, does.
+    pub expired_at: Option<Timestamp>,
+}
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, SpacetimeType)]
+pub enum DeviceTypeV1 {
+    /// The client didn't report its device type
+    Unknown,
+
+    /// Windows, macOS or Linux
+    Desktop,
+
+    /// Android or iOS
+    Mobile,
+
+    /// Any gaming console
+    Console,
+
+    /// Browser based client
+    Web,
 }
 
 /// Private player data table - contains full player information and timestamps.
 ///
 /// Stores complete player records including authentication timestamps.
-/// Display names must be unique across all players.
+/// Display names must be unique across all players, ignoring case.
 #[table(name = stdb_own_player_v1, public)]
 #[derive(Debug, Clone)]
 pub struct StdbOwnPlayerV1 {
@@ -66,12 +217,44 @@ pub struct StdbOwnPlayerV1 {
 
     #[unique]
     pub display_name: String,
+
+    /// Lowercased `display_name`, kept in sync by [`PlayerRepository::update_player`] so
+    /// case-insensitive lookups can use the index.
+    #[unique]
+    pub display_name_key: String,
+
     pub avatar: String,
 
+    /// Free text self-description, up to 256 chars.
+    pub bio: String,
+
     pub created_at: Timestamp,
 
     pub signed_in_at: Timestamp,
     pub last_signed_out_at: Timestamp,
+
+    /// Consecutive days the player signed in, starting at 1.
+    pub login_streak: u32,
+    pub total_sessions: u32,
+
+    /// Cumulative online time, updated when a session signs out.
+    pub total_play_time_secs: u64,
+
+    /// Set when the player deactivated their account. Deactivated players can't sign in.
+    pub deleted_at: Option<Timestamp>,
+
+    /// Player whose referral code was used when this player was created.
+    pub referred_by: Option<Uuid>,
+
+    /// Preferred language, a BCP-47 tag such as `en` or `pt-BR`.
+    pub locale: String,
+}
+
+impl StdbOwnPlayerV1 {
+    /// Total play time formatted as `"1w 2d 3h 4m 5s"`.
+    pub fn total_play_time_formatted(&self) -> String {
+        Duration::from_secs(self.total_play_time_secs).to_human_readable()
+    }
 }
 
 /// Public player card table - contains publicly visible player information.
@@ -86,21 +269,312 @@ pub struct StdbPubPlayerCardV1 {
 
     pub display_name: String,
     pub avatar: String,
+    pub bio: String,
+
+    pub login_streak: u32,
+
+    /// Whether any session of the player is online. Denormalized from the session table
+    /// so clients don't need to subscribe to it.
+    pub is_online: bool,
+
+    /// Denormalized from `StdbPlayerRankV1`.
+    pub rank: RankV1,
+
+    /// Preferred language of the player, so other clients can show content in it.
+    pub locale: String,
 }
 
+/// The conversion can't know about sessions or ranks, `is_online` and `rank` are filled in by
+/// [`PlayerRepository::update_player`].
 impl From<StdbOwnPlayerV1> for StdbPubPlayerCardV1 {
     fn from(player: StdbOwnPlayerV1) -> Self {
         Self {
             player_id: player.player_id,
             display_name: player.display_name,
             avatar: player.avatar,
+            bio: player.bio,
+            login_streak: player.login_streak,
+            is_online: false,
+            rank: RankV1::default(),
+            locale: player.locale,
         }
     }
 }
 
+/// Player settings table - game-specific client preferences persisted on the server.
+///
+/// The JSON is opaque to the server and only visible to the owning player.
+#[table(name = stdb_player_settings_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbPlayerSettingsV1 {
+    #[primary_key]
+    pub player_id: Uuid,
+
+    pub settings_json: String,
+}
+
+/// Player metadata table - arbitrary server-owned key-value data per player.
+///
+/// Used for things like tutorial progress or server-set flags. Only visible to the server,
+/// with a single value per `(player_id, key)` and at most [`PLAYER_METADATA_CAP`] keys per player.
+#[table(
+    name = stdb_player_metadata_v1,
+    index(name = player_key_index, btree(columns = [player_id, key]))
+)]
+#[derive(Debug, Clone)]
+pub struct StdbPlayerMetadataV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub id: u64,
+
+    #[index(btree)]
+    pub player_id: Uuid,
+
+    pub key: String,
+    pub value: String,
+
+    pub updated_at: Timestamp,
+}
+
+/// Display name history table - previous display names of each player, for moderation lookup.
+///
+/// Only visible to the server. Capped at [`DISPLAY_NAME_HISTORY_CAP`] entries per player.
+#[table(name = stdb_display_name_history_v1)]
+#[derive(Debug, Clone)]
+pub struct StdbDisplayNameHistoryV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub id: u64,
+
+    #[index(btree)]
+    pub player_id: Uuid,
+
+    pub old_name: String,
+
+    pub changed_at: Timestamp,
+}
+
+/// Profane word table - words display names can't contain, see [`crate::validate::register_profane_words`].
+///
+/// Only visible to the server. Words are stored lowercased, display names are checked ignoring case.
+#[table(name = stdb_profane_word_v1)]
+#[derive(Debug, Clone)]
+pub struct StdbProfaneWordV1 {
+    #[primary_key]
+    pub word: String,
+}
+
+/// Referral table - which player brought each new player to the game.
+///
+/// Only visible to the server. Each player is referred at most once, and each referrer has at most
+/// [`REFERRALS_CAP`] referrals.
+#[table(name = stdb_referral_v1)]
+#[derive(Debug, Clone)]
+pub struct StdbReferralV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub referral_id: u64,
+
+    #[index(btree)]
+    pub referrer_id: Uuid,
+
+    #[unique]
+    pub referred_id: Uuid,
+
+    pub created_at: Timestamp,
+
+    /// Set once the referrer got the referral reward.
+    pub reward_granted_at: Option<Timestamp>,
+}
+
+/// Display name tombstone table - display names of deactivated players, reserved so nobody can impersonate them.
+///
+/// Only visible to the server. The key is the lowercased display name, names are reserved ignoring case.
+/// Expired tombstones are deleted by `prune_display_name_tombstones_v1`.
+#[table(name = stdb_display_name_tombstone_v1)]
+#[derive(Debug, Clone)]
+pub struct StdbDisplayNameTombstoneV1 {
+    #[primary_key]
+    pub display_name: String,
+
+    #[index(btree)]
+    pub player_id: Uuid,
+
+    pub reserved_until: Timestamp,
+}
+
+/// Schedule table driving `prune_display_name_tombstones_v1`.
+#[table(
+    name = stdb_priv_display_name_tombstone_cleanup_schedule_v1,
+    scheduled(prune_display_name_tombstones_v1)
+)]
+#[derive(Debug, Clone)]
+pub struct StdbPrivDisplayNameTombstoneCleanupScheduleV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub scheduled_id: u64,
+
+    pub scheduled_at: ScheduleAt,
+}
+
+/// Session heartbeat table - last time each session reported being alive.
+///
+/// Clients call `session_heartbeat_v1` periodically. Sessions that stop sending heartbeats,
+/// e.g. because the client crashed without disconnecting, are signed out by `cleanup_stale_sessions_v1`.
+#[table(name = stdb_session_heartbeat_v1)]
+#[derive(Debug, Clone)]
+pub struct StdbSessionHeartbeatV1 {
+    #[primary_key]
+    pub session_id: Identity,
+
+    pub last_heartbeat_at: Timestamp,
+}
+
+/// Schedule table driving `cleanup_stale_sessions_v1`.
+#[table(name = stdb_priv_session_cleanup_schedule_v1, scheduled(cleanup_stale_sessions_v1))]
+#[derive(Debug, Clone)]
+pub struct StdbPrivSessionCleanupScheduleV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub scheduled_id: u64,
+
+    pub scheduled_at: ScheduleAt,
+}
+
+/// Deprecated: use `update_display_name_v1` and `update_avatar_v1` instead.
+///
+/// Updating both fields together forces clients to resend the display name when only
+/// the avatar changes, which can overwrite a concurrent rename.
 #[reducer]
 pub fn update_player_card_v1(ctx: &ReducerContext, display_name: String, avatar: String) -> ServiceResult<()> {
     let session = ctx.require_session()?;
+    let player = ctx.require_player(&session)?;
+    if is_display_name_rename(&player, &normalize_display_name(&display_name)) {
+        ctx.require_cooldown(&session.player_id, "display_name_change", DISPLAY_NAME_CHANGE_COOLDOWN)?;
+    }
     ctx.upsert_player_card(session.player_id, display_name, avatar)?;
     Ok(())
 }
+
+/// Renames the current player, at most once per [`DISPLAY_NAME_CHANGE_COOLDOWN`].
+///
+/// Resending the current name is a no-op, and case-only changes don't start the cooldown.
+#[reducer]
+pub fn update_display_name_v1(ctx: &ReducerContext, display_name: String) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    let player = ctx.require_player(&session)?;
+    let display_name = normalize_display_name(&display_name);
+    if display_name == player.display_name {
+        return Ok(());
+    }
+    if is_display_name_rename(&player, &display_name) {
+        ctx.require_cooldown(&session.player_id, "display_name_change", DISPLAY_NAME_CHANGE_COOLDOWN)?;
+    }
+    ctx.update_display_name(&session.player_id, display_name)?;
+    Ok(())
+}
+
+/// Updates the avatar of the current player, see [`AvatarRepository::require_avatar_unlocked`].
+#[reducer]
+pub fn update_avatar_v1(ctx: &ReducerContext, avatar: String) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.require_avatar_unlocked(&session.player_id, &avatar)?;
+    ctx.update_avatar(&session.player_id, avatar)?;
+    Ok(())
+}
+
+/// Deactivates the account of the current player, signing out all of their sessions.
+#[reducer]
+pub fn deactivate_account_v1(ctx: &ReducerContext) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.soft_delete_player(&session.player_id)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn update_player_bio_v1(ctx: &ReducerContext, bio: String) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.update_player_bio(&session.player_id, bio)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn update_player_locale_v1(ctx: &ReducerContext, locale: String) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.update_player_locale(&session.player_id, locale)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn update_player_settings_v1(ctx: &ReducerContext, settings_json: String) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.upsert_player_settings(session.player_id, settings_json)?;
+    Ok(())
+}
+
+/// Sets a metadata value of a player. Server-only, the value is never written by clients.
+#[reducer]
+pub fn set_player_metadata_v1(ctx: &ReducerContext, player_id: Uuid, key: String, value: String) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.set_player_metadata(player_id, key, value)?;
+    Ok(())
+}
+
+/// Reports the real device and client version of the current session.
+///
+/// Lifecycle reducers can't receive arguments, so clients call this right after connecting
+/// when the game couldn't provide these values to `stdb_identity_connected`.
+#[reducer]
+pub fn update_session_client_v1(ctx: &ReducerContext, device_type: DeviceTypeV1, client_version: String) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.update_session_client(session.session_id, device_type, client_version)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn session_heartbeat_v1(ctx: &ReducerContext) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.session_heartbeat(session.session_id)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn cleanup_stale_sessions_v1(ctx: &ReducerContext, _schedule: StdbPrivSessionCleanupScheduleV1) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.cleanup_stale_sessions(Duration::from_secs(SESSION_HEARTBEAT_TIMEOUT_SECS))?;
+    ctx.mark_expired_sessions(SESSION_EXPIRY);
+    Ok(())
+}
+
+#[reducer]
+pub fn prune_display_name_tombstones_v1(
+    ctx: &ReducerContext,
+    _schedule: StdbPrivDisplayNameTombstoneCleanupScheduleV1,
+) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.prune_expired_display_name_tombstones();
+    Ok(())
+}
+
+/// Sets the referrer of the current player, only while they weren't referred yet.
+#[reducer]
+pub fn redeem_referral_code_v1(ctx: &ReducerContext, referral_code: Uuid) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.redeem_referral_code(&session.player_id, referral_code)?;
+    Ok(())
+}
+
+/// Grants the reward of a referral to the referrer, once.
+#[reducer]
+pub fn grant_referral_reward_v1(ctx: &ReducerContext, referral_id: u64) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.grant_referral_reward(referral_id)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn admin_invalidate_sessions_v1(ctx: &ReducerContext, player_id: Uuid) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.invalidate_all_sessions_for_player(&player_id)?;
+    Ok(())
+}
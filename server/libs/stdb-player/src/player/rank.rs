@@ -0,0 +1,172 @@
+use crate::{
+    error::PlayerError,
+    player::{repository::PlayerRepository, stdb_pub_player_card_v1},
+};
+use spacetimedb::{ReducerContext, SpacetimeType, Timestamp, reducer, table};
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, ValidateExt, validate_uuid};
+
+/// Competitive ranks, from lowest to highest.
+#[derive(Debug, Copy, Clone, Default, Hash, PartialEq, Eq, PartialOrd, Ord, SpacetimeType)]
+pub enum RankV1 {
+    #[default]
+    Bronze,
+    Silver,
+    Gold,
+    Platinum,
+    Diamond,
+    Master,
+    Grandmaster,
+}
+
+/// Player rank table - the rank of each player and how many times they prestiged.
+///
+/// Only visible to the server, the rank is synced into `StdbPubPlayerCardV1`.
+/// Players without a row are [`RankV1::Bronze`] with no prestige.
+#[table(name = stdb_player_rank_v1)]
+#[derive(Debug, Clone)]
+pub struct StdbPlayerRankV1 {
+    #[primary_key]
+    pub player_id: Uuid,
+
+    pub rank: RankV1,
+
+    /// Times the player was promoted past [`RankV1::Grandmaster`], starting over at Bronze.
+    pub prestige: u32,
+
+    pub updated_at: Timestamp,
+}
+
+/// Sets the rank of a player, keeping their prestige. Server-only.
+#[reducer]
+pub fn admin_set_player_rank_v1(ctx: &ReducerContext, player_id: Uuid, rank: RankV1) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.set_rank(player_id, rank)?;
+    Ok(())
+}
+
+/// Promotes a player to the next rank, or prestiges them at Grandmaster. Server-only.
+#[reducer]
+pub fn promote_player_rank_v1(ctx: &ReducerContext, player_id: Uuid) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.promote_rank(player_id)?;
+    Ok(())
+}
+
+/// Repository trait for player ranks.
+pub trait RankRepository {
+    /// Returns the rank of a player, Bronze with no prestige if they were never ranked.
+    fn get_rank(&self, player_id: &Uuid) -> StdbPlayerRankV1;
+
+    /// Sets the rank of a player, keeping their prestige, and syncs it into their player card.
+    ///
+    /// # Errors
+    /// Returns error if the player doesn't exist or database operations fail.
+    fn set_rank(&self, player_id: Uuid, rank: RankV1) -> ServiceResult<StdbPlayerRankV1>;
+
+    /// Promotes a player to the next rank. Grandmasters prestige instead, starting over at Bronze.
+    ///
+    /// # Errors
+    /// Returns error if the player doesn't exist or database operations fail.
+    fn promote_rank(&self, player_id: Uuid) -> ServiceResult<StdbPlayerRankV1>;
+}
+
+impl RankRepository for ReducerContext {
+    fn get_rank(&self, player_id: &Uuid) -> StdbPlayerRankV1 {
+        self.db
+            .stdb_player_rank_v1()
+            .player_id()
+            .find(player_id)
+            .unwrap_or(StdbPlayerRankV1 {
+                player_id: player_id.clone(),
+                rank: RankV1::default(),
+                prestige: 0,
+                updated_at: Timestamp::UNIX_EPOCH,
+            })
+    }
+
+    fn set_rank(&self, player_id: Uuid, rank: RankV1) -> ServiceResult<StdbPlayerRankV1> {
+        let prestige = self.get_rank(&player_id).prestige;
+        write_rank(self, player_id, rank, prestige)
+    }
+
+    fn promote_rank(&self, player_id: Uuid) -> ServiceResult<StdbPlayerRankV1> {
+        let current = self.get_rank(&player_id);
+        let (rank, prestige) = next_rank(current.rank, current.prestige);
+        write_rank(self, player_id, rank, prestige)
+    }
+}
+
+fn write_rank(ctx: &ReducerContext, player_id: Uuid, rank: RankV1, prestige: u32) -> ServiceResult<StdbPlayerRankV1> {
+    validate_uuid("player_id", &player_id)?;
+    let Some(mut card) = ctx.find_player_card(&player_id) else {
+        return Err(PlayerError::player_not_found(player_id));
+    };
+
+    let rank = ctx
+        .db
+        .stdb_player_rank_v1()
+        .player_id()
+        .try_insert_or_update(StdbPlayerRankV1 {
+            player_id,
+            rank,
+            prestige,
+            updated_at: ctx.timestamp,
+        })
+        .map_conflict_ctx("failed to insert or update player rank")?;
+
+    card.rank = rank.rank;
+    ctx.db
+        .stdb_pub_player_card_v1()
+        .player_id()
+        .try_insert_or_update(card)
+        .map_conflict_ctx("failed to update player card rank")?;
+
+    Ok(rank)
+}
+
+/// Rank and prestige after a promotion. Prestige saturates at `u32::MAX`.
+pub(crate) fn next_rank(rank: RankV1, prestige: u32) -> (RankV1, u32) {
+    match rank {
+        RankV1::Bronze => (RankV1::Silver, prestige),
+        RankV1::Silver => (RankV1::Gold, prestige),
+        RankV1::Gold => (RankV1::Platinum, prestige),
+        RankV1::Platinum => (RankV1::Diamond, prestige),
+        RankV1::Diamond => (RankV1::Master, prestige),
+        RankV1::Master => (RankV1::Grandmaster, prestige),
+        RankV1::Grandmaster => (RankV1::Bronze, prestige.saturating_add(1)),
+    }
+}
+
+/// Name of the rank as shown to players.
+pub fn rank_to_display_string(rank: RankV1) -> &'static str {
+    match rank {
+        RankV1::Bronze => "Bronze",
+        RankV1::Silver => "Silver",
+        RankV1::Gold => "Gold",
+        RankV1::Platinum => "Platinum",
+        RankV1::Diamond => "Diamond",
+        RankV1::Master => "Master",
+        RankV1::Grandmaster => "Grandmaster",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_rank() {
+        assert_eq!(next_rank(RankV1::Bronze, 0), (RankV1::Silver, 0));
+        assert_eq!(next_rank(RankV1::Master, 2), (RankV1::Grandmaster, 2));
+        assert_eq!(next_rank(RankV1::Grandmaster, 2), (RankV1::Bronze, 3));
+        assert_eq!(next_rank(RankV1::Grandmaster, u32::MAX), (RankV1::Bronze, u32::MAX));
+    }
+
+    #[test]
+    fn test_ranks_are_ordered() {
+        assert!(RankV1::Bronze < RankV1::Silver);
+        assert!(RankV1::Master < RankV1::Grandmaster);
+        assert_eq!(RankV1::default(), RankV1::Bronze);
+        assert_eq!(rank_to_display_string(RankV1::Grandmaster), "Grandmaster");
+    }
+}
@@ -1,10 +1,30 @@
-use crate::player::{
-    StdbOwnPlayerSessionV1, StdbOwnPlayerV1, StdbPubPlayerCardV1, stdb_own_player_session_v1, stdb_own_player_v1,
-    stdb_pub_player_card_v1,
+#[cfg(feature = "moderation")]
+use crate::moderation::repository::ModerationRepository;
+#[cfg(feature = "analytics")]
+use crate::player::analytics::{SessionAnalyticsRepository, SessionEventV1};
+use crate::{
+    error::PlayerError,
+    player::{
+        DEFAULT_PLAYER_LOCALE, DISPLAY_NAME_HISTORY_CAP, DISPLAY_NAME_TOMBSTONE_DURATION, DeviceTypeV1, PLAYER_METADATA_CAP,
+        PLAYER_SETTINGS_MAX_BYTES, REFERRALS_CAP, StdbDisplayNameHistoryV1, StdbDisplayNameTombstoneV1, StdbOwnPlayerSessionV1,
+        StdbOwnPlayerV1, StdbPlayerMetadataV1, StdbPlayerSettingsV1, StdbPubPlayerCardV1, StdbReferralV1,
+        StdbSessionHeartbeatV1,
+        rank::RankRepository,
+        stdb_display_name_history_v1, stdb_display_name_tombstone_v1, stdb_own_player_session_v1, stdb_own_player_v1,
+        stdb_player_metadata_v1, stdb_player_settings_v1, stdb_pub_player_card_v1, stdb_referral_v1, stdb_session_heartbeat_v1,
+        words::{RegisteredWords, WordList, find_registered_words},
+    },
+    validate::validate_display_name_content,
+};
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+use std::{borrow::Borrow, collections::HashMap, time::Duration};
+use stdb_common::{
+    duration::TimestampExt,
+    prelude::{
+        ResultExt, ServiceError, ServiceResult, Uuid, UuidExt, ValidationError, sanitize_str, validate_locale, validate_str,
+        validate_text, validate_version,
+    },
 };
-use spacetimedb::{Identity, ReducerContext, Timestamp};
-use std::borrow::Borrow;
-use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, UuidExt, validate_str};
 
 /// Repository trait for managing player session operations.
 ///
@@ -16,22 +36,84 @@ pub trait PlayerSessionRepository {
     /// Returns `None` if no session exists for the given ID.
     fn find_session(&self, session_id: Identity) -> Option<StdbOwnPlayerSessionV1>;
 
+    /// Finds every session linked to a player, one per device/client.
+    fn find_all_sessions_for_player(&self, player_id: &Uuid) -> Vec<StdbOwnPlayerSessionV1>;
+
     /// Signs in a player session, creating a new one if it doesn't exist.
     ///
-    /// Sets the session to online and updates the player's sign-in timestamp.
+    /// Sets the session to online, records the device and client version and its first heartbeat,
+    /// and updates the player's sign-in timestamp, session count and login streak.
     /// If this is a new session, also creates a new player record.
     ///
+    /// Returns the session and whether a new player was just created.
+    ///
     /// # Errors
-    /// Returns error if database operations fail.
-    fn sign_in_session(&self, session_id: Identity) -> ServiceResult<StdbOwnPlayerSessionV1>;
+    /// Returns error if `client_version` isn't a valid version, the player is banned
+    /// (with the `moderation` feature) or database operations fail.
+    fn sign_in_session(
+        &self,
+        session_id: Identity,
+        device_type: DeviceTypeV1,
+        client_version: impl Into<String>,
+    ) -> ServiceResult<(StdbOwnPlayerSessionV1, bool)>;
+
+    /// Updates the device and client version of an existing session.
+    ///
+    /// # Errors
+    /// Returns `ServiceError::unauthorized()` if the session doesn't exist.
+    /// Returns error if `client_version` isn't a valid version or database operations fail.
+    fn update_session_client(
+        &self,
+        session_id: Identity,
+        device_type: DeviceTypeV1,
+        client_version: impl Into<String>,
+    ) -> ServiceResult<StdbOwnPlayerSessionV1>;
 
     /// Signs out a player session by setting it to offline.
     ///
-    /// Updates the player's last sign-out timestamp. No-op if session doesn't exist.
+    /// Updates the player's last sign-out timestamp and adds the time since the last
+    /// sign-in to their total play time. No-op if session doesn't exist.
     ///
     /// # Errors
     /// Returns error if database operations fail.
     fn sign_out_session(&self, session_id: Identity) -> ServiceResult<()>;
+
+    /// Records a heartbeat for the session at the current timestamp.
+    ///
+    /// Sessions signed out by [`PlayerSessionRepository::cleanup_stale_sessions`] are set back online,
+    /// the client was still connected. Unlike [`PlayerSessionRepository::sign_in_session`], this doesn't
+    /// count as a new sign-in. Expired sessions stay offline until they sign in again.
+    ///
+    /// # Errors
+    /// Returns error if database operations fail.
+    fn session_heartbeat(&self, session_id: Identity) -> ServiceResult<StdbSessionHeartbeatV1>;
+
+    /// Signs out every session whose last heartbeat is older than `timeout`.
+    ///
+    /// Heartbeat rows of the signed out sessions are deleted.
+    /// Returns the number of sessions signed out.
+    ///
+    /// # Errors
+    /// Returns error if database operations fail.
+    fn cleanup_stale_sessions(&self, timeout: Duration) -> ServiceResult<usize>;
+
+    /// Whether the session was marked as expired, or is offline and its player signed out longer than `expiry` ago.
+    fn is_session_expired(&self, session: &StdbOwnPlayerSessionV1, expiry: Duration) -> bool;
+
+    /// Sets `expired_at` on every expired session not marked yet, see [`PlayerSessionRepository::is_session_expired`].
+    ///
+    /// Only offline sessions can expire, the player of each session is looked up once.
+    /// The rows are kept, they're the only link between the identity and its player.
+    /// Returns the number of sessions marked.
+    fn mark_expired_sessions(&self, expiry: Duration) -> usize;
+
+    /// Signs out every online session of a player, logging them out everywhere.
+    ///
+    /// Returns the number of sessions that were online.
+    ///
+    /// # Errors
+    /// Returns error if database operations fail.
+    fn invalidate_all_sessions_for_player(&self, player_id: &Uuid) -> ServiceResult<usize>;
 }
 
 /// Repository trait for managing player data operations.
@@ -50,24 +132,88 @@ pub trait PlayerRepository {
     /// Returns `None` if no player has the given display name.
     fn find_player_by_display_name(&self, display_name: impl Borrow<String>) -> Option<StdbOwnPlayerV1>;
 
+    /// Finds a player by their display name, ignoring case, so "Swift Wolf" matches "swift wolf".
+    ///
+    /// Looks up the unique index on `display_name_key`.
+    fn find_player_by_display_name_ci(&self, display_name: &str) -> Option<StdbOwnPlayerV1>;
+
     /// Finds a public player card by player ID.
     ///
     /// Player cards contain publicly visible information like display name and avatar.
     /// Returns `None` if no card exists for the given player ID.
     fn find_player_card(&self, player_id: &Uuid) -> Option<StdbPubPlayerCardV1>;
 
+    /// Finds all players with the given IDs, in the order of `ids`.
+    ///
+    /// Missing IDs are silently skipped.
+    fn find_players_by_ids(&self, ids: &[Uuid]) -> Vec<StdbOwnPlayerV1>;
+
+    /// Finds all public player cards with the given IDs, in the order of `ids`.
+    ///
+    /// Missing IDs are silently skipped.
+    fn find_player_cards_by_ids(&self, ids: &[Uuid]) -> Vec<StdbPubPlayerCardV1>;
+
+    /// Finds the players created in `from..to`, e.g. the players who registered this week.
+    ///
+    /// Scans every player, SpacetimeDB can't filter indexes by `Timestamp` columns yet.
+    fn find_players_created_between(&self, from: Timestamp, to: Timestamp) -> Vec<StdbOwnPlayerV1>;
+
+    /// Finds the players who signed in at or after `cutoff`, for daily and weekly active player counts.
+    ///
+    /// Scans every player, like [`PlayerRepository::find_players_created_between`].
+    fn find_players_active_since(&self, cutoff: Timestamp) -> Vec<StdbOwnPlayerV1>;
+
+    /// Returns how long the player has been signed in, in seconds.
+    ///
+    /// Returns 0 if the player doesn't exist or has no online session.
+    fn player_session_duration_secs(&self, player_id: &Uuid) -> u64;
+
     /// Creates a new player with the given ID, or returns existing player.
     ///
     /// Generates a unique random display name and creates both private player
     /// record and public player card. Idempotent - returns existing player if found.
     ///
+    /// `referral_code` is the player ID of the referrer. New players referred by someone get
+    /// `referred_by` set and a [`StdbReferralV1`] row.
+    ///
+    /// # Errors
+    /// Returns error if the referrer doesn't exist, is the player itself or reached [`REFERRALS_CAP`],
+    /// or if database operations fail or validation fails.
+    fn insert_player(&self, player_id: Uuid, referral_code: Option<Uuid>) -> ServiceResult<StdbOwnPlayerV1>;
+
+    /// Sets the referrer of an existing player who wasn't referred yet, e.g. when the referral code is
+    /// entered after the first sign-in. `referral_code` is the player ID of the referrer.
+    ///
     /// # Errors
-    /// Returns error if database operations fail or validation fails.
-    fn insert_player(&self, player_id: Uuid) -> ServiceResult<StdbOwnPlayerV1>;
+    /// Returns error if the player doesn't exist or was already referred, the referrer doesn't exist,
+    /// is the player itself or reached [`REFERRALS_CAP`], or database operations fail.
+    fn redeem_referral_code(&self, player_id: &Uuid, referral_code: Uuid) -> ServiceResult<StdbOwnPlayerV1>;
+
+    /// Returns the referrals made by a player.
+    fn find_referrals(&self, referrer_id: &Uuid) -> Vec<StdbReferralV1>;
+
+    /// Marks the reward of a referral as granted and grants it to the referrer.
+    ///
+    /// With the `experience` feature the referrer gets [`REFERRAL_REWARD_XP`](crate::player::REFERRAL_REWARD_XP),
+    /// and with the `currency` feature [`REFERRAL_REWARD_SOFT_CURRENCY`](crate::player::REFERRAL_REWARD_SOFT_CURRENCY)
+    /// soft currency. Without either, games grant their own reward after calling this.
+    ///
+    /// # Errors
+    /// Returns error if the referral doesn't exist, its reward was already granted or database operations fail.
+    fn grant_referral_reward(&self, referral_id: u64) -> ServiceResult<StdbReferralV1>;
+
+    /// Deactivates a player account, keeping its records.
+    ///
+    /// Sets `deleted_at`, replaces the display name and avatar with placeholders
+    /// and signs out every session of the player.
+    ///
+    /// # Errors
+    /// Returns error if the player doesn't exist or database operations fail.
+    fn soft_delete_player(&self, player_id: &Uuid) -> ServiceResult<()>;
 
     /// Creates or updates a player with custom display name and avatar.
     ///
-    /// Validates display name (8-64 chars) and avatar (8-64 chars).
+    /// Trims and validates display name (8-64 chars) and avatar (8-64 chars).
     /// Updates both private player record and public player card.
     ///
     /// # Errors
@@ -78,6 +224,99 @@ pub trait PlayerRepository {
         display_name: impl Into<String>,
         avatar: impl Into<String>,
     ) -> ServiceResult<StdbOwnPlayerV1>;
+
+    /// Updates the display name of an existing player, keeping the other fields untouched.
+    ///
+    /// Validates display name (8-64 chars). The previous name is recorded in the display name history.
+    /// Updates both private player record and public player card.
+    ///
+    /// # Errors
+    /// Returns error if the player doesn't exist, validation fails or database operations fail.
+    fn update_display_name(&self, player_id: &Uuid, display_name: impl Into<String>) -> ServiceResult<StdbOwnPlayerV1>;
+
+    /// Updates the avatar of an existing player, keeping the other fields untouched.
+    ///
+    /// Validates avatar (8-64 chars).
+    /// Updates both private player record and public player card.
+    ///
+    /// # Errors
+    /// Returns error if the player doesn't exist, validation fails or database operations fail.
+    fn update_avatar(&self, player_id: &Uuid, avatar: impl Into<String>) -> ServiceResult<StdbOwnPlayerV1>;
+
+    /// Updates the bio of an existing player.
+    ///
+    /// Validates bio (0-256 chars, no control characters except line breaks).
+    /// Updates both private player record and public player card.
+    ///
+    /// # Errors
+    /// Returns error if the player doesn't exist, validation fails or database operations fail.
+    fn update_player_bio(&self, player_id: &Uuid, bio: impl Into<String>) -> ServiceResult<StdbOwnPlayerV1>;
+
+    /// Updates the preferred language of an existing player.
+    ///
+    /// Validates locale (e.g. `en` or `pt-BR`, see [`validate_locale`]).
+    /// Updates both private player record and public player card.
+    ///
+    /// # Errors
+    /// Returns error if the player doesn't exist, validation fails or database operations fail.
+    fn update_player_locale(&self, player_id: &Uuid, locale: impl Into<String>) -> ServiceResult<StdbOwnPlayerV1>;
+
+    /// Returns the previous display names of a player, most recent first.
+    fn find_display_name_history(&self, player_id: &Uuid) -> Vec<StdbDisplayNameHistoryV1>;
+
+    /// Finds the tombstone reserving a display name, ignoring case. Expired tombstones are returned
+    /// until they're pruned.
+    fn find_display_name_tombstone(&self, display_name: &str) -> Option<StdbDisplayNameTombstoneV1>;
+
+    /// Deletes the tombstones whose reservation ended.
+    ///
+    /// Returns the number of tombstones deleted.
+    fn prune_expired_display_name_tombstones(&self) -> usize;
+
+    /// Finds the settings of a player.
+    ///
+    /// Returns `None` if the player never stored any settings.
+    fn find_player_settings(&self, player_id: &Uuid) -> Option<StdbPlayerSettingsV1>;
+
+    /// Creates or replaces the settings of a player.
+    ///
+    /// The JSON is stored as-is, without structural validation.
+    ///
+    /// # Errors
+    /// Returns error if the settings are empty or larger than [`PLAYER_SETTINGS_MAX_BYTES`],
+    /// or database operations fail.
+    fn upsert_player_settings(&self, player_id: Uuid, settings_json: impl Into<String>) -> ServiceResult<StdbPlayerSettingsV1>;
+
+    /// Finds a metadata entry of a player by key.
+    ///
+    /// Returns `None` if the player has no value for the given key.
+    fn get_player_metadata(&self, player_id: &Uuid, key: &str) -> Option<StdbPlayerMetadataV1>;
+
+    /// Creates or replaces a metadata value of a player.
+    ///
+    /// Validates key (1-64 chars) and value (max 4096 chars).
+    ///
+    /// # Errors
+    /// Returns error if the player doesn't exist, already has [`PLAYER_METADATA_CAP`] other keys,
+    /// validation fails or database operations fail.
+    fn set_player_metadata(
+        &self,
+        player_id: Uuid,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> ServiceResult<StdbPlayerMetadataV1>;
+
+    /// Refreshes `is_online` on the public card of a player from their sessions.
+    ///
+    /// # Errors
+    /// Returns error if database operations fail.
+    fn update_card_online_status(&self, player_id: &Uuid) -> ServiceResult<()>;
+
+    /// Writes a player record and syncs its public player card, including its online status.
+    ///
+    /// # Errors
+    /// Returns error if database operations fail.
+    fn update_player(&self, player: StdbOwnPlayerV1) -> ServiceResult<StdbOwnPlayerV1>;
 }
 
 impl PlayerSessionRepository for ReducerContext {
@@ -85,13 +324,36 @@ impl PlayerSessionRepository for ReducerContext {
         self.db.stdb_own_player_session_v1().session_id().find(session_id)
     }
 
-    fn sign_in_session(&self, session_id: Identity) -> ServiceResult<StdbOwnPlayerSessionV1> {
+    fn find_all_sessions_for_player(&self, player_id: &Uuid) -> Vec<StdbOwnPlayerSessionV1> {
+        self.db.stdb_own_player_session_v1().player_id().filter(player_id).collect()
+    }
+
+    fn sign_in_session(
+        &self,
+        session_id: Identity,
+        device_type: DeviceTypeV1,
+        client_version: impl Into<String>,
+    ) -> ServiceResult<(StdbOwnPlayerSessionV1, bool)> {
+        let client_version = client_version.into();
+        validate_version("client_version", &client_version)?;
+
         let mut session = self.find_session(session_id).unwrap_or_else(|| StdbOwnPlayerSessionV1 {
             session_id,
             player_id: self.new_uuid_v7(),
             is_online: true,
+            device_type,
+            client_version: client_version.clone(),
+            expired_at: None,
         });
         session.is_online = true;
+        session.expired_at = None;
+        session.device_type = device_type;
+        session.client_version = client_version;
+
+        #[cfg(feature = "moderation")]
+        if let Some(ban) = self.find_active_ban(&session.player_id) {
+            return Err(PlayerError::account_suspended(ban.reason));
+        }
 
         let session = self
             .db
@@ -99,18 +361,50 @@ impl PlayerSessionRepository for ReducerContext {
             .session_id()
             .try_insert_or_update(session)
             .map_bad_request_ctx("failed to sign in player session")?;
+        // Clients that crash before their first heartbeat must still be picked up by the stale sessions cleanup
+        write_heartbeat(self, session_id)?;
 
-        match self.find_player(&session.player_id) {
+        let is_new_player = match self.find_player(&session.player_id) {
+            Some(player) if player.deleted_at.is_some() => {
+                return Err(PlayerError::account_deactivated(player.player_id));
+            },
             Some(mut player) => {
+                player.login_streak = next_login_streak(player.login_streak, player.signed_in_at, self.timestamp);
+                player.total_sessions = player.total_sessions.saturating_add(1);
                 player.signed_in_at = self.timestamp;
-                self.db.stdb_own_player_v1().player_id().update(player);
+                self.update_player(player)?;
+                false
             },
             None => {
-                self.insert_player(session.player_id.clone())?;
+                self.insert_player(session.player_id.clone(), None)?;
+                true
             },
-        }
+        };
 
-        Ok(session)
+        #[cfg(feature = "analytics")]
+        self.record_session_event(session.player_id.clone(), SessionEventV1::SignedIn)?;
+
+        Ok((session, is_new_player))
+    }
+
+    fn update_session_client(
+        &self,
+        session_id: Identity,
+        device_type: DeviceTypeV1,
+        client_version: impl Into<String>,
+    ) -> ServiceResult<StdbOwnPlayerSessionV1> {
+        let client_version = client_version.into();
+        validate_version("client_version", &client_version)?;
+
+        let mut session = self.find_session(session_id).ok_or(ServiceError::unauthorized())?;
+        session.device_type = device_type;
+        session.client_version = client_version;
+
+        self.db
+            .stdb_own_player_session_v1()
+            .session_id()
+            .try_insert_or_update(session)
+            .map_bad_request_ctx("failed to update player session client")
     }
 
     fn sign_out_session(&self, session_id: Identity) -> ServiceResult<()> {
@@ -118,6 +412,7 @@ impl PlayerSessionRepository for ReducerContext {
             return Ok(());
         };
 
+        let was_online = session.is_online;
         session.is_online = false;
         let session = self
             .db
@@ -127,12 +422,100 @@ impl PlayerSessionRepository for ReducerContext {
             .map_bad_request_ctx("failed to sign out player session")?;
 
         if let Some(mut player) = self.find_player(&session.player_id) {
+            // Signing out an offline session again must not count the same play time twice
+            if was_online {
+                let played = self.timestamp.duration_since(player.signed_in_at).unwrap_or_default();
+                player.total_play_time_secs = player.total_play_time_secs.saturating_add(played.as_secs());
+            }
             player.last_signed_out_at = self.timestamp;
             self.db.stdb_own_player_v1().player_id().update(player);
+            self.update_card_online_status(&session.player_id)?;
+        }
+
+        #[cfg(feature = "analytics")]
+        if was_online {
+            self.record_session_event(session.player_id.clone(), SessionEventV1::SignedOut)?;
         }
 
         Ok(())
     }
+
+    fn session_heartbeat(&self, session_id: Identity) -> ServiceResult<StdbSessionHeartbeatV1> {
+        let heartbeat = write_heartbeat(self, session_id)?;
+
+        // The stale sessions cleanup signed out a client that was only late, set it back online
+        if let Some(session) = self.find_session(session_id)
+            && !session.is_online
+            && session.expired_at.is_none()
+        {
+            revive_session(self, session)?;
+        }
+
+        Ok(heartbeat)
+    }
+
+    fn cleanup_stale_sessions(&self, timeout: Duration) -> ServiceResult<usize> {
+        let stale: Vec<_> = self
+            .db
+            .stdb_session_heartbeat_v1()
+            .iter()
+            .filter(|heartbeat| is_heartbeat_stale(heartbeat.last_heartbeat_at, self.timestamp, timeout))
+            .collect();
+
+        for heartbeat in &stale {
+            self.sign_out_session(heartbeat.session_id).map_err(|error| {
+                ServiceError::chain(error, format!("failed to sign out stale session {}", heartbeat.session_id))
+            })?;
+            self.db.stdb_session_heartbeat_v1().session_id().delete(heartbeat.session_id);
+        }
+
+        Ok(stale.len())
+    }
+
+    fn is_session_expired(&self, session: &StdbOwnPlayerSessionV1, expiry: Duration) -> bool {
+        let last_signed_out_at = self.find_player(&session.player_id).map(|player| player.last_signed_out_at);
+        is_expired_session(session, last_signed_out_at, self.timestamp, expiry)
+    }
+
+    fn mark_expired_sessions(&self, expiry: Duration) -> usize {
+        let mut last_signed_out_at = HashMap::new();
+        let expired: Vec<_> = self
+            .db
+            .stdb_own_player_session_v1()
+            .is_online()
+            .filter(false)
+            .filter(|session| session.expired_at.is_none())
+            .filter(|session| {
+                let signed_out_at = *last_signed_out_at
+                    .entry(session.player_id.clone())
+                    .or_insert_with(|| self.find_player(&session.player_id).map(|player| player.last_signed_out_at));
+                is_expired_session(session, signed_out_at, self.timestamp, expiry)
+            })
+            .collect();
+
+        let count = expired.len();
+        for mut session in expired {
+            session.expired_at = Some(self.timestamp);
+            self.db.stdb_own_player_session_v1().session_id().update(session);
+        }
+
+        count
+    }
+
+    fn invalidate_all_sessions_for_player(&self, player_id: &Uuid) -> ServiceResult<usize> {
+        let online_sessions: Vec<_> = self
+            .find_all_sessions_for_player(player_id)
+            .into_iter()
+            .filter(|session| session.is_online)
+            .collect();
+
+        for session in &online_sessions {
+            self.sign_out_session(session.session_id)
+                .map_err(|error| ServiceError::chain(error, format!("failed to sign out session {}", session.session_id)))?;
+        }
+
+        Ok(online_sessions.len())
+    }
 }
 
 impl PlayerRepository for ReducerContext {
@@ -144,18 +527,144 @@ impl PlayerRepository for ReducerContext {
         self.db.stdb_own_player_v1().display_name().find(display_name)
     }
 
+    fn find_player_by_display_name_ci(&self, display_name: &str) -> Option<StdbOwnPlayerV1> {
+        self.db
+            .stdb_own_player_v1()
+            .display_name_key()
+            .find(display_name_key(display_name))
+    }
+
     fn find_player_card(&self, player_id: &Uuid) -> Option<StdbPubPlayerCardV1> {
         self.db.stdb_pub_player_card_v1().player_id().find(player_id)
     }
 
-    fn insert_player(&self, player_id: Uuid) -> ServiceResult<StdbOwnPlayerV1> {
-        match self.find_player(&player_id) {
-            Some(player) => Ok(player),
-            None => {
-                let display_name = build_unique_display_name(self);
-                self.upsert_player_card(player_id, display_name, "default_avatar")
-            },
+    fn find_players_by_ids(&self, ids: &[Uuid]) -> Vec<StdbOwnPlayerV1> {
+        find_all_by_ids(ids, |id| self.find_player(id))
+    }
+
+    fn find_player_cards_by_ids(&self, ids: &[Uuid]) -> Vec<StdbPubPlayerCardV1> {
+        find_all_by_ids(ids, |id| self.find_player_card(id))
+    }
+
+    fn find_players_created_between(&self, from: Timestamp, to: Timestamp) -> Vec<StdbOwnPlayerV1> {
+        self.db
+            .stdb_own_player_v1()
+            .iter()
+            .filter(|player| (from..to).contains(&player.created_at))
+            .collect()
+    }
+
+    fn find_players_active_since(&self, cutoff: Timestamp) -> Vec<StdbOwnPlayerV1> {
+        self.db
+            .stdb_own_player_v1()
+            .iter()
+            .filter(|player| player.signed_in_at >= cutoff)
+            .collect()
+    }
+
+    fn player_session_duration_secs(&self, player_id: &Uuid) -> u64 {
+        let is_online = self
+            .find_all_sessions_for_player(player_id)
+            .iter()
+            .any(|session| session.is_online);
+        match self.find_player(player_id) {
+            Some(player) if is_online => self
+                .timestamp
+                .duration_since(player.signed_in_at)
+                .unwrap_or_default()
+                .as_secs(),
+            _ => 0,
+        }
+    }
+
+    fn insert_player(&self, player_id: Uuid, referral_code: Option<Uuid>) -> ServiceResult<StdbOwnPlayerV1> {
+        if let Some(player) = self.find_player(&player_id) {
+            return Ok(player);
+        }
+
+        let referrer_id = match referral_code {
+            Some(referrer_id) => Some(require_referrer(self, &player_id, referrer_id)?),
+            None => None,
+        };
+
+        let display_name = build_unique_display_name(self, &player_id);
+        let player = self.upsert_player_card(player_id, display_name, "default_avatar")?;
+        match referrer_id {
+            Some(referrer_id) => record_referral(self, player, referrer_id),
+            None => Ok(player),
+        }
+    }
+
+    fn redeem_referral_code(&self, player_id: &Uuid, referral_code: Uuid) -> ServiceResult<StdbOwnPlayerV1> {
+        let Some(player) = self.find_player(player_id) else {
+            return Err(PlayerError::player_not_found(player_id.clone()));
+        };
+        check_referral_redeemable(&player)?;
+
+        let referrer_id = require_referrer(self, player_id, referral_code)?;
+        record_referral(self, player, referrer_id)
+    }
+
+    fn find_referrals(&self, referrer_id: &Uuid) -> Vec<StdbReferralV1> {
+        self.db.stdb_referral_v1().referrer_id().filter(referrer_id).collect()
+    }
+
+    fn grant_referral_reward(&self, referral_id: u64) -> ServiceResult<StdbReferralV1> {
+        let referral = grant_referral(
+            referral_id,
+            self.db.stdb_referral_v1().referral_id().find(referral_id),
+            self.timestamp,
+        )?;
+        let referral = self
+            .db
+            .stdb_referral_v1()
+            .referral_id()
+            .try_insert_or_update(referral)
+            .map_conflict_ctx("failed to grant referral reward")?;
+
+        #[cfg(feature = "experience")]
+        {
+            use crate::{experience::repository::ExperienceRepository, player::REFERRAL_REWARD_XP};
+            self.grant_xp(referral.referrer_id.clone(), REFERRAL_REWARD_XP)?;
+        }
+
+        #[cfg(feature = "currency")]
+        {
+            use crate::{
+                currency::{CurrencyTypeV1, repository::CurrencyRepository},
+                player::REFERRAL_REWARD_SOFT_CURRENCY,
+            };
+            self.grant(
+                referral.referrer_id.clone(),
+                CurrencyTypeV1::Soft,
+                REFERRAL_REWARD_SOFT_CURRENCY,
+                "referral",
+            )?;
+        }
+
+        Ok(referral)
+    }
+
+    fn soft_delete_player(&self, player_id: &Uuid) -> ServiceResult<()> {
+        let Some(mut player) = self.find_player(player_id) else {
+            return Err(PlayerError::player_not_found(player_id.clone()));
+        };
+        if player.deleted_at.is_some() {
+            return Ok(());
         }
+
+        self.db
+            .stdb_display_name_tombstone_v1()
+            .display_name()
+            .try_insert_or_update(display_name_tombstone(&player, self.timestamp))
+            .map_conflict_ctx("failed to insert display name tombstone")?;
+
+        player.deleted_at = Some(self.timestamp);
+        player.display_name = deleted_display_name(player_id);
+        player.avatar = DELETED_PLAYER_AVATAR.to_string();
+        self.update_player(player)?;
+        self.invalidate_all_sessions_for_player(player_id)?;
+        Ok(())
     }
 
     fn upsert_player_card(
@@ -164,28 +673,212 @@ impl PlayerRepository for ReducerContext {
         display_name: impl Into<String>,
         avatar: impl Into<String>,
     ) -> ServiceResult<StdbOwnPlayerV1> {
-        let display_name = display_name.into();
-        let avatar = avatar.into();
+        let display_name = sanitize_str("display_name", normalize_display_name(&display_name.into()), 8, 64)?.into_inner();
+        let avatar = sanitize_str("avatar", avatar.into(), 8, 64)?.into_inner();
+        validate_display_name_content(self, &display_name)?;
 
-        validate_str("display_name", &display_name, 8, 64)?;
-        validate_str("avatar", &avatar, 8, 64)?;
+        ensure_display_name_available(self, &player_id, &display_name)?;
 
         let player = match self.find_player(&player_id) {
             Some(mut player) => {
+                record_display_name_change(self, &player, &display_name)?;
                 player.display_name = display_name;
                 player.avatar = avatar;
                 player
             },
             None => StdbOwnPlayerV1 {
                 player_id,
+                display_name_key: display_name_key(&display_name),
                 display_name,
                 avatar,
+                bio: String::new(),
                 created_at: self.timestamp,
                 signed_in_at: self.timestamp,
                 last_signed_out_at: Timestamp::UNIX_EPOCH,
+                login_streak: 1,
+                total_sessions: 1,
+                total_play_time_secs: 0,
+                deleted_at: None,
+                referred_by: None,
+                locale: DEFAULT_PLAYER_LOCALE.to_string(),
             },
         };
 
+        self.update_player(player)
+    }
+
+    fn update_display_name(&self, player_id: &Uuid, display_name: impl Into<String>) -> ServiceResult<StdbOwnPlayerV1> {
+        let display_name = normalize_display_name(&display_name.into());
+        validate_str("display_name", &display_name, 8, 64)?;
+        validate_display_name_content(self, &display_name)?;
+
+        let Some(mut player) = self.find_player(player_id) else {
+            return Err(PlayerError::player_not_found(player_id.clone()));
+        };
+        ensure_display_name_available(self, player_id, &display_name)?;
+        record_display_name_change(self, &player, &display_name)?;
+        player.display_name = display_name;
+
+        self.update_player(player)
+    }
+
+    fn update_avatar(&self, player_id: &Uuid, avatar: impl Into<String>) -> ServiceResult<StdbOwnPlayerV1> {
+        let avatar = avatar.into();
+        validate_str("avatar", &avatar, 8, 64)?;
+
+        let Some(mut player) = self.find_player(player_id) else {
+            return Err(PlayerError::player_not_found(player_id.clone()));
+        };
+        player.avatar = avatar;
+
+        self.update_player(player)
+    }
+
+    fn update_player_bio(&self, player_id: &Uuid, bio: impl Into<String>) -> ServiceResult<StdbOwnPlayerV1> {
+        let bio = bio.into();
+        validate_text("bio", &bio, 0, 256)?;
+
+        let Some(mut player) = self.find_player(player_id) else {
+            return Err(PlayerError::player_not_found(player_id.clone()));
+        };
+        player.bio = bio;
+
+        self.update_player(player)
+    }
+
+    fn update_player_locale(&self, player_id: &Uuid, locale: impl Into<String>) -> ServiceResult<StdbOwnPlayerV1> {
+        let locale = locale.into();
+        validate_locale("locale", &locale)?;
+
+        let Some(mut player) = self.find_player(player_id) else {
+            return Err(PlayerError::player_not_found(player_id.clone()));
+        };
+        player.locale = locale;
+
+        self.update_player(player)
+    }
+
+    fn find_display_name_history(&self, player_id: &Uuid) -> Vec<StdbDisplayNameHistoryV1> {
+        let mut history: Vec<_> = self.db.stdb_display_name_history_v1().player_id().filter(player_id).collect();
+        history.sort_by(|a, b| b.changed_at.cmp(&a.changed_at).then(b.id.cmp(&a.id)));
+        history
+    }
+
+    fn find_display_name_tombstone(&self, display_name: &str) -> Option<StdbDisplayNameTombstoneV1> {
+        self.db
+            .stdb_display_name_tombstone_v1()
+            .display_name()
+            .find(display_name_key(display_name))
+    }
+
+    fn prune_expired_display_name_tombstones(&self) -> usize {
+        let expired: Vec<_> = self
+            .db
+            .stdb_display_name_tombstone_v1()
+            .iter()
+            .filter(|tombstone| is_display_name_tombstone_expired(tombstone, self.timestamp))
+            .collect();
+
+        for tombstone in &expired {
+            self.db
+                .stdb_display_name_tombstone_v1()
+                .display_name()
+                .delete(&tombstone.display_name);
+        }
+        expired.len()
+    }
+
+    fn find_player_settings(&self, player_id: &Uuid) -> Option<StdbPlayerSettingsV1> {
+        self.db.stdb_player_settings_v1().player_id().find(player_id)
+    }
+
+    fn upsert_player_settings(&self, player_id: Uuid, settings_json: impl Into<String>) -> ServiceResult<StdbPlayerSettingsV1> {
+        let settings_json = settings_json.into();
+        validate_str("settings_json", &settings_json, 1, PLAYER_SETTINGS_MAX_BYTES)?;
+
+        self.db
+            .stdb_player_settings_v1()
+            .player_id()
+            .try_insert_or_update(StdbPlayerSettingsV1 {
+                player_id,
+                settings_json,
+            })
+            .map_conflict_ctx("failed to insert or update player settings")
+    }
+
+    fn get_player_metadata(&self, player_id: &Uuid, key: &str) -> Option<StdbPlayerMetadataV1> {
+        self.db
+            .stdb_player_metadata_v1()
+            .player_key_index()
+            .filter((player_id, key))
+            .next()
+    }
+
+    fn set_player_metadata(
+        &self,
+        player_id: Uuid,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> ServiceResult<StdbPlayerMetadataV1> {
+        let key = key.into();
+        let value = value.into();
+        validate_str("key", &key, 1, 64)?;
+        validate_str("value", &value, 0, 4096)?;
+
+        if self.find_player(&player_id).is_none() {
+            return Err(PlayerError::player_not_found(player_id));
+        }
+
+        let entry = match self.get_player_metadata(&player_id, &key) {
+            Some(mut entry) => {
+                entry.value = value;
+                entry.updated_at = self.timestamp;
+                entry
+            },
+            None => {
+                let count = self.db.stdb_player_metadata_v1().player_id().filter(&player_id).count();
+                if count >= PLAYER_METADATA_CAP {
+                    return Err(PlayerError::player_metadata_limit_reached(player_id));
+                }
+
+                StdbPlayerMetadataV1 {
+                    id: 0,
+                    player_id,
+                    key,
+                    value,
+                    updated_at: self.timestamp,
+                }
+            },
+        };
+
+        self.db
+            .stdb_player_metadata_v1()
+            .id()
+            .try_insert_or_update(entry)
+            .map_conflict_ctx("failed to insert or update player metadata")
+    }
+
+    fn update_card_online_status(&self, player_id: &Uuid) -> ServiceResult<()> {
+        let Some(mut card) = self.find_player_card(player_id) else {
+            return Ok(());
+        };
+
+        let is_online = is_player_online(self, player_id);
+        if card.is_online == is_online {
+            return Ok(());
+        }
+        card.is_online = is_online;
+
+        self.db
+            .stdb_pub_player_card_v1()
+            .player_id()
+            .try_insert_or_update(card)
+            .map_conflict_ctx("failed to update player card online status")?;
+        Ok(())
+    }
+
+    fn update_player(&self, mut player: StdbOwnPlayerV1) -> ServiceResult<StdbOwnPlayerV1> {
+        player.display_name_key = display_name_key(&player.display_name);
         let player = self
             .db
             .stdb_own_player_v1()
@@ -193,7 +886,9 @@ impl PlayerRepository for ReducerContext {
             .try_insert_or_update(player)
             .map_conflict_ctx("failed to insert or update player")?;
 
-        let card = StdbPubPlayerCardV1::from(player.clone());
+        let mut card = StdbPubPlayerCardV1::from(player.clone());
+        card.is_online = is_player_online(self, &player.player_id);
+        card.rank = self.get_rank(&player.player_id).rank;
         self.db
             .stdb_pub_player_card_v1()
             .player_id()
@@ -204,39 +899,403 @@ impl PlayerRepository for ReducerContext {
     }
 }
 
-fn build_unique_display_name(ctx: &ReducerContext) -> String {
-    for _ in 0..12 {
-        let display_name = build_random_display_name(ctx);
-        let existing = ctx.find_player_by_display_name(&display_name);
-        if existing.is_none() {
+/// Looks up every ID once, keeping only the rows that were found.
+pub(crate) fn find_all_by_ids<T>(ids: &[Uuid], find: impl Fn(&Uuid) -> Option<T>) -> Vec<T> {
+    ids.iter().filter_map(find).collect()
+}
+
+fn write_heartbeat(ctx: &ReducerContext, session_id: Identity) -> ServiceResult<StdbSessionHeartbeatV1> {
+    ctx.db
+        .stdb_session_heartbeat_v1()
+        .session_id()
+        .try_insert_or_update(StdbSessionHeartbeatV1 {
+            session_id,
+            last_heartbeat_at: ctx.timestamp,
+        })
+        .map_conflict_ctx("failed to record session heartbeat")
+}
+
+/// Sets a session signed out by the stale sessions cleanup back online and refreshes the player card.
+///
+/// The player's sign-in timestamp, session count and login streak are left untouched.
+fn revive_session(ctx: &ReducerContext, mut session: StdbOwnPlayerSessionV1) -> ServiceResult<()> {
+    session.is_online = true;
+    let session = ctx
+        .db
+        .stdb_own_player_session_v1()
+        .session_id()
+        .try_insert_or_update(session)
+        .map_bad_request_ctx("failed to revive player session")?;
+    ctx.update_card_online_status(&session.player_id)
+}
+
+/// Whether any session of the player is online.
+pub(crate) fn is_player_online(ctx: &ReducerContext, player_id: &Uuid) -> bool {
+    ctx.find_all_sessions_for_player(player_id)
+        .iter()
+        .any(|session| session.is_online)
+}
+
+/// Records the current display name of the player in the history when it's about to change.
+///
+/// Only the last [`DISPLAY_NAME_HISTORY_CAP`] entries are kept.
+fn record_display_name_change(ctx: &ReducerContext, player: &StdbOwnPlayerV1, new_display_name: &str) -> ServiceResult<()> {
+    if player.display_name == new_display_name {
+        return Ok(());
+    }
+
+    ctx.db
+        .stdb_display_name_history_v1()
+        .try_insert(StdbDisplayNameHistoryV1 {
+            id: 0,
+            player_id: player.player_id.clone(),
+            old_name: player.display_name.clone(),
+            changed_at: ctx.timestamp,
+        })
+        .map_conflict_ctx("failed to insert display name history")?;
+
+    for history in ctx
+        .find_display_name_history(&player.player_id)
+        .into_iter()
+        .skip(DISPLAY_NAME_HISTORY_CAP)
+    {
+        ctx.db.stdb_display_name_history_v1().id().delete(history.id);
+    }
+
+    Ok(())
+}
+
+/// Returns the ID of the player referring `player_id`, if they can still refer players.
+fn require_referrer(ctx: &ReducerContext, player_id: &Uuid, referrer_id: Uuid) -> ServiceResult<Uuid> {
+    let referrer = ctx.find_player(&referrer_id);
+    let referrals = ctx.db.stdb_referral_v1().referrer_id().filter(&referrer_id).count();
+    check_referrer(player_id, &referrer_id, referrer.as_ref(), referrals)?;
+    Ok(referrer_id)
+}
+
+/// Records that `referrer_id` referred `player` and sets its `referred_by`.
+fn record_referral(ctx: &ReducerContext, mut player: StdbOwnPlayerV1, referrer_id: Uuid) -> ServiceResult<StdbOwnPlayerV1> {
+    ctx.db
+        .stdb_referral_v1()
+        .try_insert(StdbReferralV1 {
+            referral_id: 0,
+            referrer_id: referrer_id.clone(),
+            referred_id: player.player_id.clone(),
+            created_at: ctx.timestamp,
+            reward_granted_at: None,
+        })
+        .map_conflict_ctx("failed to insert referral")?;
+
+    player.referred_by = Some(referrer_id);
+    ctx.update_player(player)
+}
+
+/// Checks that `player` can still redeem a referral code, players are referred at most once.
+///
+/// # Errors
+/// Returns error if the player was already referred.
+pub(crate) fn check_referral_redeemable(player: &StdbOwnPlayerV1) -> ServiceResult<()> {
+    if player.referred_by.is_some() {
+        return Err(PlayerError::already_referred(player.player_id.clone()));
+    }
+    Ok(())
+}
+
+/// Checks that `referrer`, who already referred `referrals` players, can refer `player_id`.
+///
+/// # Errors
+/// Returns error if the player refers themselves, the referrer doesn't exist or was deactivated,
+/// or they reached [`REFERRALS_CAP`].
+pub(crate) fn check_referrer(
+    player_id: &Uuid,
+    referrer_id: &Uuid,
+    referrer: Option<&StdbOwnPlayerV1>,
+    referrals: usize,
+) -> ServiceResult<()> {
+    if referrer_id == player_id {
+        return Err(PlayerError::self_referral());
+    }
+    if referrer.is_none_or(|referrer| referrer.deleted_at.is_some()) {
+        return Err(PlayerError::player_not_found(referrer_id.clone()));
+    }
+    if referrals >= REFERRALS_CAP {
+        return Err(PlayerError::referral_limit_reached(referrer_id.clone()));
+    }
+
+    Ok(())
+}
+
+/// Marks the reward of a referral as granted at `now`.
+///
+/// # Errors
+/// Returns error if the referral doesn't exist or its reward was already granted.
+pub(crate) fn grant_referral(
+    referral_id: u64,
+    referral: Option<StdbReferralV1>,
+    now: Timestamp,
+) -> ServiceResult<StdbReferralV1> {
+    let Some(mut referral) = referral else {
+        return Err(PlayerError::referral_not_found(referral_id));
+    };
+    if referral.reward_granted_at.is_some() {
+        return Err(PlayerError::referral_reward_already_granted(referral_id));
+    }
+
+    referral.reward_granted_at = Some(now);
+    Ok(referral)
+}
+
+/// Fails if another player already uses `display_name` in any case, before the unique indexes reject
+/// the write with a generic conflict.
+fn ensure_display_name_available(ctx: &ReducerContext, player_id: &Uuid, display_name: &str) -> ServiceResult<()> {
+    check_display_name_available(
+        player_id,
+        display_name,
+        ctx.find_display_name_tombstone(display_name).as_ref(),
+        ctx.find_player_by_display_name_ci(display_name).as_ref(),
+        ctx.timestamp,
+    )
+}
+
+/// Checks that `player_id` can use `display_name`, given its tombstone and the player holding it in any case.
+///
+/// # Errors
+/// Returns `ValidationError::DuplicateValue` while a tombstone reserves the name for someone else,
+/// or `PlayerError::DisplayNameTaken` if another player holds it.
+pub(crate) fn check_display_name_available(
+    player_id: &Uuid,
+    display_name: &str,
+    tombstone: Option<&StdbDisplayNameTombstoneV1>,
+    holder: Option<&StdbOwnPlayerV1>,
+    now: Timestamp,
+) -> ServiceResult<()> {
+    if tombstone.is_some_and(|tombstone| is_display_name_reserved(tombstone, player_id, now)) {
+        return Err(ValidationError::duplicate_value("display_name"));
+    }
+
+    match holder {
+        Some(other) if other.player_id != *player_id => Err(PlayerError::display_name_taken(display_name)),
+        _ => Ok(()),
+    }
+}
+
+/// Case-insensitive key of `display_name`, stored in `StdbOwnPlayerV1::display_name_key` and keying
+/// [`StdbDisplayNameTombstoneV1`].
+pub(crate) fn display_name_key(display_name: &str) -> String {
+    display_name.to_lowercase()
+}
+
+/// Whether changing the display name of `player` to the normalized `display_name` is a rename that starts
+/// the display name cooldown. Case-only changes keep the same [`display_name_key`] and aren't.
+pub(crate) fn is_display_name_rename(player: &StdbOwnPlayerV1, display_name: &str) -> bool {
+    display_name_key(display_name) != player.display_name_key
+}
+
+/// Tombstone reserving the display name of a player deactivated at `now`.
+pub(crate) fn display_name_tombstone(player: &StdbOwnPlayerV1, now: Timestamp) -> StdbDisplayNameTombstoneV1 {
+    StdbDisplayNameTombstoneV1 {
+        display_name: display_name_key(&player.display_name),
+        player_id: player.player_id.clone(),
+        reserved_until: now + DISPLAY_NAME_TOMBSTONE_DURATION,
+    }
+}
+
+/// Whether the tombstone still keeps `player_id` from using the display name, its former owner never is.
+pub(crate) fn is_display_name_reserved(tombstone: &StdbDisplayNameTombstoneV1, player_id: &Uuid, now: Timestamp) -> bool {
+    tombstone.player_id != *player_id && !is_display_name_tombstone_expired(tombstone, now)
+}
+
+/// Whether the tombstone stopped reserving its display name, [`DISPLAY_NAME_TOMBSTONE_DURATION`] after
+/// the player deactivated their account.
+pub(crate) fn is_display_name_tombstone_expired(tombstone: &StdbDisplayNameTombstoneV1, now: Timestamp) -> bool {
+    tombstone.reserved_until <= now
+}
+
+/// Computes the login streak of a player signing in at `now`, whose previous sign-in was at `signed_in_at`.
+///
+/// Days are UTC calendar days: signing in again the same day keeps the streak, the next day extends it,
+/// and any later day restarts it at 1.
+pub(crate) fn next_login_streak(login_streak: u32, signed_in_at: Timestamp, now: Timestamp) -> u32 {
+    match now.into_midnight().days_since(signed_in_at.into_midnight()) {
+        0 => login_streak.max(1),
+        1 => login_streak.saturating_add(1),
+        _ => 1,
+    }
+}
+
+/// Whether a heartbeat sent at `last_heartbeat_at` is older than `timeout` at `now`.
+pub(crate) fn is_heartbeat_stale(last_heartbeat_at: Timestamp, now: Timestamp, timeout: Duration) -> bool {
+    now.duration_since(last_heartbeat_at).is_some_and(|elapsed| elapsed > timeout)
+}
+
+/// Avatar shown for deactivated players.
+pub const DELETED_PLAYER_AVATAR: &str = "deleted";
+
+/// Placeholder display name of a deactivated player.
+///
+/// Uses the end of the ID, the start of a UUID v7 is its creation time and is shared by players created together.
+pub(crate) fn deleted_display_name(player_id: &str) -> String {
+    let suffix_start = player_id.len().saturating_sub(12);
+    format!("DeletedPlayer_{}", player_id.get(suffix_start..).unwrap_or(player_id))
+}
+
+/// Whether the session must sign in again, `last_signed_out_at` is the one of its player, if it exists.
+pub(crate) fn is_expired_session(
+    session: &StdbOwnPlayerSessionV1,
+    last_signed_out_at: Option<Timestamp>,
+    now: Timestamp,
+    expiry: Duration,
+) -> bool {
+    session.expired_at.is_some()
+        || (!session.is_online
+            && last_signed_out_at.is_some_and(|last_signed_out_at| is_sign_out_expired(last_signed_out_at, now, expiry)))
+}
+
+/// Whether a player signed out at `last_signed_out_at` has been away for longer than `expiry` at `now`.
+pub(crate) fn is_sign_out_expired(last_signed_out_at: Timestamp, now: Timestamp, expiry: Duration) -> bool {
+    now.duration_since(last_signed_out_at).is_some_and(|elapsed| elapsed > expiry)
+}
+
+/// Trims the display name and collapses whitespace runs inside it into a single space.
+///
+/// Applied before validation, so the length limits count the normalized name.
+pub fn normalize_display_name(display_name: &str) -> String {
+    display_name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Plain random names tried before adding a number suffix.
+const DISPLAY_NAME_PLAIN_ATTEMPTS: usize = 12;
+
+/// Total random names tried before falling back to a UUID based name.
+const DISPLAY_NAME_MAX_ATTEMPTS: usize = 24;
+
+/// Candidates go through the same availability check as player-chosen names, so names held in
+/// another case or reserved by a tombstone are skipped.
+fn build_unique_display_name(ctx: &ReducerContext, player_id: &Uuid) -> String {
+    let words = find_registered_words(ctx);
+    pick_unique_display_name(
+        |attempt| {
+            let display_name = random_display_name(|| ctx.random::<u32>(), &words);
+            if attempt < DISPLAY_NAME_PLAIN_ATTEMPTS {
+                display_name
+            } else {
+                // Random 3-digit suffix, 100-999
+                format!("{} {}", display_name, 100 + ctx.random::<u16>() % 900)
+            }
+        },
+        |display_name| ensure_display_name_available(ctx, player_id, display_name).is_err(),
+        || fallback_display_name(&ctx.new_uuid_v4()),
+    )
+}
+
+/// `Player` followed by the first 8 hex digits of `uuid`, used once every random candidate is taken.
+pub(crate) fn fallback_display_name(uuid: &str) -> String {
+    format!("Player{}", &uuid[..8])
+}
+
+/// Returns the first candidate that isn't taken, or the fallback after [`DISPLAY_NAME_MAX_ATTEMPTS`] candidates.
+pub(crate) fn pick_unique_display_name(
+    mut candidate: impl FnMut(usize) -> String,
+    is_taken: impl Fn(&String) -> bool,
+    fallback: impl FnOnce() -> String,
+) -> String {
+    for attempt in 0..DISPLAY_NAME_MAX_ATTEMPTS {
+        let display_name = candidate(attempt);
+        if !is_taken(&display_name) {
             return display_name;
         }
     }
 
-    // Hack... Horrible display name, but we can't find any cool ones.
-    ctx.new_uuid_v4()
+    fallback()
 }
 
-fn build_random_display_name(ctx: &ReducerContext) -> String {
-    let color_index = ctx.random::<u8>() as usize % COLORS.len();
-    let color = COLORS[color_index];
+/// Builds a random display name out of the word lists, drawing every choice from `random`.
+pub(crate) fn random_display_name(random: impl Fn() -> u32, words: &RegisteredWords) -> String {
+    let color = || words.with_words(WordList::Colors, COLORS, |list| pick_word(&random, list));
+    let adjective = || words.with_words(WordList::Adjectives, ADJECTIVES, |list| pick_word(&random, list));
+    let metal = || pick_word(&random, METALS);
+    let verb = || pick_word(&random, VERBS);
 
-    let adjective_index = ctx.random::<u8>() as usize % ADJECTIVES.len();
-    let adjective = ADJECTIVES[adjective_index];
+    // Creatures and plants are replaced by the nouns registered by the game, if any
+    let creature = || words.with_words(WordList::Nouns, CREATURES, |list| pick_word(&random, list));
+    let plant = || words.with_words(WordList::Nouns, PLANTS, |list| pick_word(&random, list));
 
-    // Randomly choose between creatures and plants
-    let use_creature = ctx.random::<bool>();
-    let noun = if use_creature {
-        let creature_index = ctx.random::<u8>() as usize % CREATURES.len();
-        CREATURES[creature_index]
-    } else {
-        let plant_index = ctx.random::<u8>() as usize % PLANTS.len();
-        PLANTS[plant_index]
-    };
+    match random() % 4 {
+        0 => format!("{} {} {}", color(), adjective(), creature()),
+        1 => format!("{} {} {}", color(), adjective(), plant()),
+        2 => format!("{} {} {}", metal(), verb(), creature()),
+        _ => {
+            let noun = if random().is_multiple_of(2) { creature() } else { plant() };
+            format!("{} {} {}", adjective(), metal(), noun)
+        },
+    }
+}
 
-    format!("{} {} {}", color, adjective, noun)
+fn pick_word<'a>(random: &impl Fn() -> u32, words: &[&'a str]) -> &'a str {
+    words[random_index(random, words.len())]
 }
 
+/// Uniform index in `0..len`, `len` must be greater than 0.
+///
+/// Draws falling in the last partial block of `len` values are rejected and drawn again, a plain modulo
+/// would favor the first `2^32 % len` indexes.
+pub(crate) fn random_index(random: &impl Fn() -> u32, len: usize) -> usize {
+    let len = len as u64;
+    let zone = (1u64 << 32) / len * len;
+    loop {
+        let value = u64::from(random());
+        if value < zone {
+            return (value % len) as usize;
+        }
+    }
+}
+
+const VERBS: &[&str] = &[
+    "Running",
+    "Leaping",
+    "Charging",
+    "Soaring",
+    "Roaming",
+    "Dashing",
+    "Prowling",
+    "Gliding",
+    "Howling",
+    "Blazing",
+    "Drifting",
+    "Striking",
+    "Wandering",
+    "Climbing",
+    "Diving",
+    "Hunting",
+    "Dancing",
+    "Rising",
+    "Spinning",
+    "Sneaking",
+];
+
+const METALS: &[&str] = &[
+    "Iron",
+    "Steel",
+    "Copper",
+    "Bronze",
+    "Silver",
+    "Golden",
+    "Platinum",
+    "Titanium",
+    "Cobalt",
+    "Nickel",
+    "Chrome",
+    "Mithril",
+    "Adamant",
+    "Tin",
+    "Zinc",
+    "Brass",
+    "Pewter",
+    "Obsidian",
+    "Tungsten",
+    "Orichalcum",
+];
+
 const COLORS: &[&str] = &[
     "Red",
     "Blue",
@@ -421,3 +1480,511 @@ const PLANTS: &[&str] = &[
     "Clover",
     "Daisy",
 ];
+
+/// Internals measured by `benches/display_name_bench.rs`, not part of the public API.
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench {
+    pub fn random_display_name(random: impl Fn() -> u32) -> String {
+        super::random_display_name(random, &super::RegisteredWords::default())
+    }
+
+    pub fn pick_unique_display_name(
+        candidate: impl FnMut(usize) -> String,
+        is_taken: impl Fn(&String) -> bool,
+        fallback: impl FnOnce() -> String,
+    ) -> String {
+        super::pick_unique_display_name(candidate, is_taken, fallback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixtures::{OTHER_PLAYER_ID, PLAYER_ID, uuid},
+        player::{DISPLAY_NAME_CHANGE_COOLDOWN, SESSION_EXPIRY},
+    };
+    use stdb_common::{cooldown::cooldown_remaining, duration::DurationExt};
+
+    fn longest(words: &[&str]) -> usize {
+        words.iter().map(|word| word.len()).max().unwrap_or_default()
+    }
+
+    #[test]
+    fn test_display_name_words_are_single_words() {
+        for words in [COLORS, ADJECTIVES, CREATURES, PLANTS, VERBS, METALS] {
+            assert!(!words.is_empty());
+            assert!(words.iter().all(|word| !word.is_empty() && !word.contains(' ')));
+        }
+    }
+
+    #[test]
+    fn test_pick_unique_display_name_adds_suffix_after_plain_attempts() {
+        let taken = "Red Swift Wolf".to_string();
+        let display_name = pick_unique_display_name(
+            |attempt| match attempt {
+                a if a < DISPLAY_NAME_PLAIN_ATTEMPTS => taken.clone(),
+                _ => format!("{} {}", taken, 123),
+            },
+            |display_name| *display_name == taken,
+            || unreachable!(),
+        );
+
+        assert_eq!(display_name, "Red Swift Wolf 123");
+    }
+
+    #[test]
+    fn test_pick_unique_display_name_fallback_when_saturated() {
+        let mut attempts = 0;
+        let display_name = pick_unique_display_name(
+            |_| {
+                attempts += 1;
+                format!("{} {} {} 999", COLORS[0], ADJECTIVES[0], CREATURES[0])
+            },
+            |_| true,
+            || fallback_display_name("0197f231-554c-7001-8203-040506070809"),
+        );
+
+        assert_eq!(attempts, DISPLAY_NAME_MAX_ATTEMPTS);
+        assert_eq!(display_name, "Player0197f231");
+
+        let suffix = display_name.strip_prefix("Player").unwrap();
+        assert_eq!(suffix.len(), 8);
+        assert!(suffix.chars().all(|c| c.is_ascii_hexdigit()));
+        assert!(validate_str("display_name", &display_name, 8, 64).is_ok());
+    }
+
+    #[test]
+    fn test_longest_suffixed_display_name_is_valid() {
+        let noun_len = longest(CREATURES).max(longest(PLANTS));
+        let longest_name = [
+            longest(COLORS) + longest(ADJECTIVES) + noun_len,
+            longest(METALS) + longest(VERBS) + longest(CREATURES),
+            longest(ADJECTIVES) + longest(METALS) + noun_len,
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or_default();
+
+        // Three words, two spaces and the " 999" suffix
+        let display_name = format!("{} 999", "a".repeat(longest_name + "  ".len()));
+        assert!(validate_str("display_name", &display_name, 8, 64).is_ok());
+    }
+
+    #[test]
+    fn test_find_all_by_ids_skips_missing() {
+        let existing = ["a".to_string(), "c".to_string()];
+        let ids = ["a".to_string(), "b".to_string(), "c".to_string(), "d".to_string()];
+
+        let found = find_all_by_ids(&ids, |id| existing.iter().find(|other| *other == id).cloned());
+        assert_eq!(found, vec!["a".to_string(), "c".to_string()]);
+        assert!(find_all_by_ids(&[], |id: &Uuid| Some(id.clone())).is_empty());
+    }
+
+    #[test]
+    fn test_display_name_change_cooldown_active() {
+        let changed_at = Timestamp::from_micros_since_unix_epoch(1_745_712_000_000_000);
+        let now = changed_at + Duration::from_days_ext(6);
+
+        assert_eq!(
+            cooldown_remaining(changed_at, now, DISPLAY_NAME_CHANGE_COOLDOWN),
+            Some(Duration::from_days_ext(1))
+        );
+    }
+
+    #[test]
+    fn test_display_name_change_cooldown_expired() {
+        let changed_at = Timestamp::from_micros_since_unix_epoch(1_745_712_000_000_000);
+
+        assert_eq!(
+            cooldown_remaining(
+                changed_at,
+                changed_at + Duration::from_days_ext(7),
+                DISPLAY_NAME_CHANGE_COOLDOWN
+            ),
+            None
+        );
+        assert_eq!(DISPLAY_NAME_CHANGE_COOLDOWN, Duration::from_weeks_ext(1));
+    }
+
+    /// 2024-01-01T00:00:00Z plus `hours`.
+    fn streak_time(hours: u64) -> Timestamp {
+        Timestamp::from_micros_since_unix_epoch(1_704_067_200_000_000) + Duration::from_hours_ext(hours)
+    }
+
+    #[test]
+    fn test_next_login_streak_same_day() {
+        assert_eq!(next_login_streak(3, streak_time(1), streak_time(2)), 3);
+        assert_eq!(next_login_streak(0, streak_time(0), streak_time(0)), 1);
+        assert_eq!(next_login_streak(3, streak_time(0), streak_time(23)), 3);
+    }
+
+    #[test]
+    fn test_next_login_streak_multiple_sign_ins_per_day() {
+        // Several sign-ins every day, the streak grows once per day
+        let mut streak = 1;
+        let mut signed_in_at = streak_time(8);
+        for day in 0..3 {
+            for hour in [8, 12, 20] {
+                let now = streak_time(day * 24 + hour);
+                streak = next_login_streak(streak, signed_in_at, now);
+                signed_in_at = now;
+            }
+            assert_eq!(streak, day as u32 + 1);
+        }
+    }
+
+    #[test]
+    fn test_next_login_streak_continuation() {
+        // Late night then early next morning is the next day
+        assert_eq!(next_login_streak(3, streak_time(23), streak_time(25)), 4);
+        assert_eq!(next_login_streak(3, streak_time(0), streak_time(47)), 4);
+        assert_eq!(next_login_streak(u32::MAX, streak_time(0), streak_time(30)), u32::MAX);
+    }
+
+    #[test]
+    fn test_next_login_streak_reset() {
+        assert_eq!(next_login_streak(3, streak_time(23), streak_time(48)), 1);
+        assert_eq!(next_login_streak(3, streak_time(0), streak_time(30 * 24)), 1);
+    }
+
+    #[test]
+    fn test_is_heartbeat_stale() {
+        let timeout = Duration::from_secs(300);
+        let last_heartbeat_at = Timestamp::from_micros_since_unix_epoch(1_000_000_000);
+
+        assert!(!is_heartbeat_stale(last_heartbeat_at, last_heartbeat_at, timeout));
+        assert!(!is_heartbeat_stale(last_heartbeat_at, last_heartbeat_at + timeout, timeout));
+        assert!(is_heartbeat_stale(
+            last_heartbeat_at,
+            last_heartbeat_at + timeout + Duration::from_micros(1),
+            timeout
+        ));
+    }
+
+    #[test]
+    fn test_is_heartbeat_stale_in_the_future() {
+        let timeout = Duration::from_secs(300);
+        let now = Timestamp::from_micros_since_unix_epoch(1_000_000_000);
+
+        assert!(!is_heartbeat_stale(now + timeout, now, timeout));
+    }
+
+    #[test]
+    fn test_deleted_display_name() {
+        assert_eq!(deleted_display_name(PLAYER_ID), "DeletedPlayer_b302099a8057");
+        assert_eq!(deleted_display_name("short"), "DeletedPlayer_short");
+    }
+
+    #[test]
+    fn test_is_sign_out_expired() {
+        let expiry = SESSION_EXPIRY;
+        let last_signed_out_at = Timestamp::from_micros_since_unix_epoch(1_000_000_000);
+
+        assert!(!is_sign_out_expired(last_signed_out_at, last_signed_out_at + expiry, expiry));
+        assert!(is_sign_out_expired(
+            last_signed_out_at,
+            last_signed_out_at + expiry + Duration::from_secs(1),
+            expiry
+        ));
+    }
+
+    #[test]
+    fn test_is_expired_session() {
+        let last_signed_out_at = Timestamp::from_micros_since_unix_epoch(1_000_000_000);
+        let expired_at = last_signed_out_at + SESSION_EXPIRY + Duration::from_secs(1);
+        let mut session = StdbOwnPlayerSessionV1 {
+            session_id: Identity::ZERO,
+            player_id: uuid(PLAYER_ID),
+            is_online: false,
+            device_type: DeviceTypeV1::Unknown,
+            client_version: "1.0.0".to_string(),
+            expired_at: None,
+        };
+
+        assert!(!is_expired_session(
+            &session,
+            Some(last_signed_out_at),
+            last_signed_out_at,
+            SESSION_EXPIRY
+        ));
+        assert!(is_expired_session(
+            &session,
+            Some(last_signed_out_at),
+            expired_at,
+            SESSION_EXPIRY
+        ));
+        assert!(!is_expired_session(&session, None, expired_at, SESSION_EXPIRY));
+
+        session.is_online = true;
+        assert!(!is_expired_session(
+            &session,
+            Some(last_signed_out_at),
+            expired_at,
+            SESSION_EXPIRY
+        ));
+
+        // Marked sessions stay expired until they sign in again
+        session.expired_at = Some(expired_at);
+        assert!(is_expired_session(&session, None, last_signed_out_at, SESSION_EXPIRY));
+    }
+
+    #[test]
+    fn test_random_index_rejects_biased_draws() {
+        use std::cell::Cell;
+
+        // 2^32 % 48 == 16, so the 16 largest values would favor the first indexes
+        let draws = Cell::new(0);
+        let random = || {
+            draws.set(draws.get() + 1);
+            if draws.get() == 1 { u32::MAX } else { 5 }
+        };
+        assert_eq!(random_index(&random, 48), 5);
+        assert_eq!(draws.get(), 2);
+
+        assert_eq!(random_index(&|| u32::MAX - 16, 48), (u32::MAX - 16) as usize % 48);
+    }
+
+    #[test]
+    fn test_random_index_chi_squared() {
+        use std::cell::Cell;
+
+        let state = Cell::new(0x2545_f491_u32);
+        let random = || {
+            let mut x = state.get();
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            state.set(x);
+            x
+        };
+
+        let draws_per_word = 200;
+        let mut counts = vec![0u32; COLORS.len()];
+        for _ in 0..COLORS.len() * draws_per_word {
+            counts[random_index(&random, COLORS.len())] += 1;
+        }
+
+        let expected = draws_per_word as f64;
+        let chi_squared: f64 = counts.iter().map(|count| (*count as f64 - expected).powi(2) / expected).sum();
+
+        // Critical value for 47 degrees of freedom at p = 0.001
+        assert!(chi_squared < 82.7, "chi squared {}", chi_squared);
+    }
+
+    fn player(player_id: &str, display_name: &str) -> StdbOwnPlayerV1 {
+        StdbOwnPlayerV1 {
+            player_id: player_id.to_string(),
+            display_name: display_name.to_string(),
+            display_name_key: display_name_key(display_name),
+            avatar: "default_avatar".to_string(),
+            bio: String::new(),
+            created_at: Timestamp::UNIX_EPOCH,
+            signed_in_at: Timestamp::UNIX_EPOCH,
+            last_signed_out_at: Timestamp::UNIX_EPOCH,
+            login_streak: 1,
+            total_sessions: 1,
+            total_play_time_secs: 0,
+            deleted_at: None,
+            referred_by: None,
+            locale: DEFAULT_PLAYER_LOCALE.to_string(),
+        }
+    }
+
+    fn tombstone(player_id: &str, display_name: &str, reserved_until: Timestamp) -> StdbDisplayNameTombstoneV1 {
+        StdbDisplayNameTombstoneV1 {
+            display_name: display_name_key(display_name),
+            player_id: player_id.to_string(),
+            reserved_until,
+        }
+    }
+
+    #[test]
+    fn test_check_display_name_available_case_variants() {
+        let now = Timestamp::UNIX_EPOCH;
+        let holder = player(OTHER_PLAYER_ID, "Brave Otter");
+
+        for display_name in ["Brave Otter", "brave otter", "BRAVE OTTER", "bRaVe oTtEr"] {
+            // The case-insensitive lookup finds the holder through its key
+            assert_eq!(display_name_key(display_name), holder.display_name_key);
+            let error =
+                check_display_name_available(&PLAYER_ID.to_string(), display_name, None, Some(&holder), now).unwrap_err();
+            assert_eq!(error.message(), PlayerError::display_name_taken(display_name).message());
+        }
+
+        // The holder can change the case of their own name
+        assert!(check_display_name_available(&OTHER_PLAYER_ID.to_string(), "BRAVE OTTER", None, Some(&holder), now).is_ok());
+        assert!(check_display_name_available(&PLAYER_ID.to_string(), "Brave Otter", None, None, now).is_ok());
+    }
+
+    #[test]
+    fn test_check_display_name_available_tombstoned() {
+        let now = Timestamp::from_micros_since_unix_epoch(1_000_000_000);
+        let reserved = tombstone(OTHER_PLAYER_ID, "Brave Otter", now + DISPLAY_NAME_TOMBSTONE_DURATION);
+
+        let error =
+            check_display_name_available(&PLAYER_ID.to_string(), "brave otter", Some(&reserved), None, now).unwrap_err();
+        assert_eq!(error.message(), ValidationError::duplicate_value("display_name").message());
+
+        // The former owner can take their name back, and the reservation ends with the tombstone
+        assert!(check_display_name_available(&OTHER_PLAYER_ID.to_string(), "Brave Otter", Some(&reserved), None, now).is_ok());
+        let expired = tombstone(OTHER_PLAYER_ID, "Brave Otter", now);
+        assert!(check_display_name_available(&PLAYER_ID.to_string(), "Brave Otter", Some(&expired), None, now).is_ok());
+    }
+
+    #[test]
+    fn test_is_display_name_tombstone_expired() {
+        assert_eq!(DISPLAY_NAME_TOMBSTONE_DURATION, Duration::from_hours_ext(90 * 24));
+
+        let deleted_at = Timestamp::from_micros_since_unix_epoch(1_000_000_000);
+        let reserved = tombstone(OTHER_PLAYER_ID, "Brave Otter", deleted_at + DISPLAY_NAME_TOMBSTONE_DURATION);
+        let almost = deleted_at + DISPLAY_NAME_TOMBSTONE_DURATION - Duration::from_secs(1);
+
+        assert!(!is_display_name_tombstone_expired(&reserved, deleted_at));
+        assert!(!is_display_name_tombstone_expired(&reserved, almost));
+        assert!(is_display_name_reserved(&reserved, &PLAYER_ID.to_string(), almost));
+        assert!(is_display_name_tombstone_expired(
+            &reserved,
+            deleted_at + DISPLAY_NAME_TOMBSTONE_DURATION
+        ));
+        assert!(!is_display_name_reserved(
+            &reserved,
+            &PLAYER_ID.to_string(),
+            deleted_at + DISPLAY_NAME_TOMBSTONE_DURATION
+        ));
+    }
+
+    #[test]
+    fn test_pick_unique_display_name_skips_unavailable_names() {
+        let now = Timestamp::from_micros_since_unix_epoch(1_000_000_000);
+        let reserved = tombstone(OTHER_PLAYER_ID, "Brave Otter", now + DISPLAY_NAME_TOMBSTONE_DURATION);
+        let holder = player(OTHER_PLAYER_ID, "Swift Wolf");
+        let is_taken = |display_name: &String| {
+            let key = display_name_key(display_name);
+            check_display_name_available(
+                &PLAYER_ID.to_string(),
+                display_name,
+                Some(&reserved).filter(|tombstone| tombstone.display_name == key),
+                Some(&holder).filter(|holder| holder.display_name_key == key),
+                now,
+            )
+            .is_err()
+        };
+
+        let candidates = ["BRAVE OTTER", "swift wolf", "Calm Heron"];
+        let display_name = pick_unique_display_name(|attempt| candidates[attempt].to_string(), is_taken, || unreachable!());
+        assert_eq!(display_name, "Calm Heron");
+    }
+
+    #[test]
+    fn test_display_name_tombstone() {
+        let now = Timestamp::from_micros_since_unix_epoch(1_000_000_000);
+        let tombstone = display_name_tombstone(&player(PLAYER_ID, "Brave Otter"), now);
+        assert_eq!(tombstone.display_name, "brave otter");
+        assert_eq!(tombstone.player_id, PLAYER_ID);
+        assert_eq!(tombstone.reserved_until, now + DISPLAY_NAME_TOMBSTONE_DURATION);
+    }
+
+    #[test]
+    fn test_check_referrer() {
+        let player_id = PLAYER_ID.to_string();
+        let referrer_id = OTHER_PLAYER_ID.to_string();
+        let mut referrer = player(OTHER_PLAYER_ID, "Brave Otter");
+        assert!(check_referrer(&player_id, &referrer_id, Some(&referrer), 0).is_ok());
+        assert!(check_referrer(&player_id, &referrer_id, Some(&referrer), REFERRALS_CAP - 1).is_ok());
+
+        let error = check_referrer(&player_id, &referrer_id, Some(&referrer), REFERRALS_CAP).unwrap_err();
+        assert_eq!(
+            error.message(),
+            PlayerError::referral_limit_reached(referrer_id.clone()).message()
+        );
+        let error = check_referrer(&player_id, &player_id, Some(&referrer), 0).unwrap_err();
+        assert_eq!(error.message(), PlayerError::self_referral().message());
+        assert!(check_referrer(&player_id, &referrer_id, None, 0).is_err());
+
+        referrer.deleted_at = Some(Timestamp::UNIX_EPOCH);
+        let error = check_referrer(&player_id, &referrer_id, Some(&referrer), 0).unwrap_err();
+        assert_eq!(error.message(), PlayerError::player_not_found(referrer_id).message());
+    }
+
+    #[test]
+    fn test_redeem_referral_code_checks() {
+        let referrer = player(OTHER_PLAYER_ID, "Brave Otter");
+        let mut redeemer = player(PLAYER_ID, "Swift Wolf");
+        let redeem = |redeemer: &StdbOwnPlayerV1, referrer_id: &str, referrals: usize| {
+            check_referral_redeemable(redeemer)?;
+            check_referrer(&redeemer.player_id, &referrer_id.to_string(), Some(&referrer), referrals)
+        };
+        assert!(redeem(&redeemer, OTHER_PLAYER_ID, 0).is_ok());
+
+        let error = redeem(&redeemer, PLAYER_ID, 0).unwrap_err();
+        assert_eq!(error.message(), PlayerError::self_referral().message());
+        let error = redeem(&redeemer, OTHER_PLAYER_ID, REFERRALS_CAP).unwrap_err();
+        assert_eq!(
+            error.message(),
+            PlayerError::referral_limit_reached(OTHER_PLAYER_ID.to_string()).message()
+        );
+
+        redeemer.referred_by = Some(OTHER_PLAYER_ID.to_string());
+        let error = redeem(&redeemer, OTHER_PLAYER_ID, 0).unwrap_err();
+        assert_eq!(
+            error.message(),
+            PlayerError::already_referred(PLAYER_ID.to_string()).message()
+        );
+    }
+
+    #[test]
+    fn test_grant_referral() {
+        let now = Timestamp::from_micros_since_unix_epoch(1_000_000_000);
+        let referral = StdbReferralV1 {
+            referral_id: 7,
+            referrer_id: OTHER_PLAYER_ID.to_string(),
+            referred_id: PLAYER_ID.to_string(),
+            created_at: Timestamp::UNIX_EPOCH,
+            reward_granted_at: None,
+        };
+
+        let granted = grant_referral(7, Some(referral), now).unwrap();
+        assert_eq!(granted.reward_granted_at, Some(now));
+        let error = grant_referral(7, Some(granted), now).unwrap_err();
+        assert_eq!(error.message(), PlayerError::referral_reward_already_granted(7).message());
+        let error = grant_referral(8, None, now).unwrap_err();
+        assert_eq!(error.message(), PlayerError::referral_not_found(8).message());
+    }
+
+    #[test]
+    fn test_is_display_name_rename() {
+        let player = player(PLAYER_ID, "Swift Wolf");
+        assert!(!is_display_name_rename(&player, "Swift Wolf"));
+        assert!(!is_display_name_rename(&player, "SWIFT wolf"));
+        assert!(is_display_name_rename(&player, "Swift Wolves"));
+    }
+
+    #[test]
+    fn test_display_name_key() {
+        assert_eq!(display_name_key("Swift Wolf"), "swift wolf");
+        assert_eq!(display_name_key("SWIFT WOLF"), display_name_key("swift wolf"));
+        assert_ne!(display_name_key("Swift Wolf"), display_name_key("Swift Wolves"));
+    }
+
+    #[test]
+    fn test_normalize_display_name() {
+        assert_eq!(normalize_display_name("  Swift  Wolf  "), "Swift Wolf");
+        assert_eq!(normalize_display_name("Swift\t\nWolf"), "Swift Wolf");
+        assert_eq!(normalize_display_name("Brave Otter"), "Brave Otter");
+        assert_eq!(normalize_display_name("   "), "");
+    }
+
+    #[test]
+    fn test_normalize_display_name_before_validation() {
+        // Long enough only because of the padding
+        assert!(validate_str("display_name", "  Wolf  ", 8, 64).is_ok());
+        assert!(validate_str("display_name", &normalize_display_name("  Wolf  "), 8, 64).is_err());
+
+        // Too long only because of the inner whitespace
+        let padded = format!("Swift{}Wolf", " ".repeat(60));
+        assert!(validate_str("display_name", &padded, 8, 64).is_err());
+        assert!(validate_str("display_name", &normalize_display_name(&padded), 8, 64).is_ok());
+    }
+}
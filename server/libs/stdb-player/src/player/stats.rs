@@ -0,0 +1,154 @@
+use crate::player::{StdbOwnPlayerSessionV1, stdb_own_player_session_v1, stdb_own_player_v1};
+use spacetimedb::{ReducerContext, ScheduleAt, Table, Timestamp, reducer, table};
+use stdb_common::prelude::{ResultExt, ServiceResult, ValidateExt};
+
+/// Interval between two runs of `record_online_count_snapshot_v1`.
+pub const ONLINE_COUNT_SNAPSHOT_INTERVAL_SECS: u64 = 5 * 60;
+
+/// Number of online count snapshots kept, 4 weeks of 5-minute intervals.
+pub const ONLINE_COUNT_SNAPSHOTS_CAP: u64 = 8064;
+
+/// Online count snapshot table - the number of online sessions over time, for analytics.
+///
+/// Only visible to the server. The oldest snapshots are deleted past [`ONLINE_COUNT_SNAPSHOTS_CAP`].
+#[table(name = stdb_online_count_snapshot_v1)]
+#[derive(Debug, Clone)]
+pub struct StdbOnlineCountSnapshotV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub snapshot_id: u64,
+
+    pub online_count: u32,
+    pub recorded_at: Timestamp,
+}
+
+/// Schedule table driving `record_online_count_snapshot_v1`.
+#[table(
+    name = stdb_priv_online_count_snapshot_schedule_v1,
+    scheduled(record_online_count_snapshot_v1)
+)]
+#[derive(Debug, Clone)]
+pub struct StdbPrivOnlineCountSnapshotScheduleV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub scheduled_id: u64,
+
+    pub scheduled_at: ScheduleAt,
+}
+
+#[reducer]
+pub fn record_online_count_snapshot_v1(
+    ctx: &ReducerContext,
+    _schedule: StdbPrivOnlineCountSnapshotScheduleV1,
+) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.record_online_count_snapshot()?;
+    Ok(())
+}
+
+/// Repository trait for player statistics, e.g. for dashboards and lobby UIs.
+///
+/// Counts iterate the underlying tables, so they're meant for occasional reads.
+pub trait StatsRepository {
+    /// Returns the number of online sessions.
+    ///
+    /// A player signed in on two devices counts twice.
+    fn count_online_players(&self) -> u32;
+
+    /// Returns the number of players that ever signed in.
+    fn count_total_players(&self) -> u32;
+
+    /// Returns the online count snapshots recorded from `from` (inclusive) to `to` (exclusive),
+    /// ordered by when they were recorded.
+    fn online_count_history(&self, from: Timestamp, to: Timestamp) -> Vec<StdbOnlineCountSnapshotV1>;
+
+    /// Records the current number of online sessions, deleting the oldest snapshots past
+    /// [`ONLINE_COUNT_SNAPSHOTS_CAP`].
+    ///
+    /// # Errors
+    /// Returns error if database operations fail.
+    fn record_online_count_snapshot(&self) -> ServiceResult<StdbOnlineCountSnapshotV1>;
+}
+
+impl StatsRepository for ReducerContext {
+    fn count_online_players(&self) -> u32 {
+        count_online(self.db.stdb_own_player_session_v1().iter())
+    }
+
+    fn count_total_players(&self) -> u32 {
+        self.db.stdb_own_player_v1().count() as u32
+    }
+
+    fn online_count_history(&self, from: Timestamp, to: Timestamp) -> Vec<StdbOnlineCountSnapshotV1> {
+        snapshots_between(self.db.stdb_online_count_snapshot_v1().iter(), from, to)
+    }
+
+    fn record_online_count_snapshot(&self) -> ServiceResult<StdbOnlineCountSnapshotV1> {
+        let snapshots = self.db.stdb_online_count_snapshot_v1();
+        let excess = (snapshots.count() + 1).saturating_sub(ONLINE_COUNT_SNAPSHOTS_CAP);
+        if excess > 0 {
+            let mut snapshot_ids: Vec<_> = snapshots.iter().map(|snapshot| snapshot.snapshot_id).collect();
+            snapshot_ids.sort_unstable();
+            for snapshot_id in snapshot_ids.into_iter().take(excess as usize) {
+                snapshots.snapshot_id().delete(snapshot_id);
+            }
+        }
+
+        snapshots
+            .try_insert(StdbOnlineCountSnapshotV1 {
+                snapshot_id: 0,
+                online_count: self.count_online_players(),
+                recorded_at: self.timestamp,
+            })
+            .map_conflict_ctx("failed to insert online count snapshot")
+    }
+}
+
+pub(crate) fn snapshots_between(
+    snapshots: impl Iterator<Item = StdbOnlineCountSnapshotV1>,
+    from: Timestamp,
+    to: Timestamp,
+) -> Vec<StdbOnlineCountSnapshotV1> {
+    let mut snapshots: Vec<_> = snapshots
+        .filter(|snapshot| (from..to).contains(&snapshot.recorded_at))
+        .collect();
+    snapshots.sort_by_key(|snapshot| (snapshot.recorded_at, snapshot.snapshot_id));
+    snapshots
+}
+
+pub(crate) fn count_online(sessions: impl Iterator<Item = StdbOwnPlayerSessionV1>) -> u32 {
+    sessions.filter(|session| session.is_online).count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{PLAYER_ID, at, session};
+
+    #[test]
+    fn test_count_online() {
+        let sessions = [true, false, true, true]
+            .into_iter()
+            .enumerate()
+            .map(|(index, is_online)| session(index as u64, PLAYER_ID, is_online));
+
+        assert_eq!(count_online(sessions), 3);
+    }
+
+    #[test]
+    fn test_snapshots_between() {
+        let snapshots = [(1, 30), (2, 10), (3, 20), (4, 40)]
+            .into_iter()
+            .map(|(snapshot_id, secs)| StdbOnlineCountSnapshotV1 {
+                snapshot_id,
+                online_count: snapshot_id as u32,
+                recorded_at: at(secs),
+            });
+
+        let ids: Vec<_> = snapshots_between(snapshots, at(10), at(40))
+            .into_iter()
+            .map(|snapshot| snapshot.snapshot_id)
+            .collect();
+        assert_eq!(ids, vec![2, 3, 1]);
+    }
+}
@@ -0,0 +1,190 @@
+//! Word lists of the display name generator.
+//!
+//! With the `display_name_words` feature, games can replace the built-in lists from their `stdb_init`,
+//! e.g. `register_nouns(ctx, &["Nebula", "Comet"])` for a sci-fi theme. Registered lists are stored in
+//! `stdb_display_name_words_v1`, so they survive restarts and republishing the module. Lists that aren't
+//! registered keep using the built-in words.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum WordList {
+    Colors,
+    Adjectives,
+    Nouns,
+}
+
+/// Word lists registered by the game, empty lists use the built-in words.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct RegisteredWords {
+    colors: Vec<String>,
+    adjectives: Vec<String>,
+    nouns: Vec<String>,
+}
+
+impl RegisteredWords {
+    /// Calls `f` with the registered words of `list`, or with `default` when none were registered.
+    pub(crate) fn with_words<'a, R>(
+        &'a self,
+        list: WordList,
+        default: &'static [&'static str],
+        f: impl FnOnce(&[&'a str]) -> R,
+    ) -> R {
+        let words = match list {
+            WordList::Colors => &self.colors,
+            WordList::Adjectives => &self.adjectives,
+            WordList::Nouns => &self.nouns,
+        };
+
+        if words.is_empty() {
+            f(default)
+        } else {
+            f(&words.iter().map(String::as_str).collect::<Vec<_>>())
+        }
+    }
+}
+
+#[cfg(feature = "display_name_words")]
+mod custom {
+    use super::{RegisteredWords, WordList};
+    use crate::error::PlayerError;
+    use spacetimedb::{ReducerContext, table};
+    use stdb_common::prelude::{ResultExt, ServiceResult, validate_usize};
+
+    /// Display name words table - the word lists registered by the game, keyed by list name.
+    ///
+    /// Only visible to the server.
+    #[table(name = stdb_display_name_words_v1)]
+    #[derive(Debug, Clone)]
+    pub struct StdbDisplayNameWordsV1 {
+        #[primary_key]
+        pub list: String,
+
+        pub words: Vec<String>,
+    }
+
+    /// Replaces the built-in colors, the first word of generated display names.
+    ///
+    /// # Errors
+    /// Returns error if `words` is empty or colors were already registered.
+    pub fn register_colors(ctx: &ReducerContext, words: &[&str]) -> ServiceResult<()> {
+        register(ctx, WordList::Colors, words)
+    }
+
+    /// Replaces the built-in adjectives, the second word of generated display names.
+    ///
+    /// # Errors
+    /// Returns error if `words` is empty or adjectives were already registered.
+    pub fn register_adjectives(ctx: &ReducerContext, words: &[&str]) -> ServiceResult<()> {
+        register(ctx, WordList::Adjectives, words)
+    }
+
+    /// Replaces the built-in creatures and plants, the last word of generated display names.
+    ///
+    /// # Errors
+    /// Returns error if `words` is empty or nouns were already registered.
+    pub fn register_nouns(ctx: &ReducerContext, words: &[&str]) -> ServiceResult<()> {
+        register(ctx, WordList::Nouns, words)
+    }
+
+    fn register(ctx: &ReducerContext, list: WordList, words: &[&str]) -> ServiceResult<()> {
+        let name = list_name(list);
+        let registered = ctx.db.stdb_display_name_words_v1().list().find(name.to_string()).is_some();
+        check_word_list(list, words, registered)?;
+
+        ctx.db
+            .stdb_display_name_words_v1()
+            .list()
+            .try_insert_or_update(StdbDisplayNameWordsV1 {
+                list: name.to_string(),
+                words: words.iter().map(|word| (*word).to_string()).collect(),
+            })
+            .map_conflict_ctx("failed to register display name words")?;
+        Ok(())
+    }
+
+    /// Fails if `words` is empty or the list was already registered.
+    pub(super) fn check_word_list(list: WordList, words: &[&str], registered: bool) -> ServiceResult<()> {
+        let name = list_name(list);
+        validate_usize(name, words.len(), 1, usize::MAX)?;
+        if registered {
+            return Err(PlayerError::word_list_already_registered(name));
+        }
+        Ok(())
+    }
+
+    fn list_name(list: WordList) -> &'static str {
+        match list {
+            WordList::Colors => "colors",
+            WordList::Adjectives => "adjectives",
+            WordList::Nouns => "nouns",
+        }
+    }
+
+    pub(crate) fn find_registered_words(ctx: &ReducerContext) -> RegisteredWords {
+        let find = |list: WordList| {
+            ctx.db
+                .stdb_display_name_words_v1()
+                .list()
+                .find(list_name(list).to_string())
+                .map(|row| row.words)
+                .unwrap_or_default()
+        };
+
+        RegisteredWords {
+            colors: find(WordList::Colors),
+            adjectives: find(WordList::Adjectives),
+            nouns: find(WordList::Nouns),
+        }
+    }
+}
+
+#[cfg(feature = "display_name_words")]
+pub use custom::{StdbDisplayNameWordsV1, register_adjectives, register_colors, register_nouns};
+
+#[cfg(feature = "display_name_words")]
+pub(crate) use custom::find_registered_words;
+
+/// Without the `display_name_words` feature, every list uses the built-in words.
+#[cfg(not(feature = "display_name_words"))]
+pub(crate) fn find_registered_words(_ctx: &spacetimedb::ReducerContext) -> RegisteredWords {
+    RegisteredWords::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT: &[&str] = &["Red", "Blue"];
+
+    #[test]
+    fn test_with_words_defaults() {
+        let words = RegisteredWords::default();
+        assert_eq!(words.with_words(WordList::Colors, DEFAULT, |words| words.to_vec()), DEFAULT);
+    }
+
+    #[test]
+    fn test_with_words_registered() {
+        let words = RegisteredWords {
+            nouns: vec!["Nebula".to_string(), "Comet".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            words.with_words(WordList::Nouns, DEFAULT, |words| words.to_vec()),
+            vec!["Nebula", "Comet"]
+        );
+        assert_eq!(
+            words.with_words(WordList::Adjectives, DEFAULT, |words| words.to_vec()),
+            DEFAULT
+        );
+    }
+
+    #[cfg(feature = "display_name_words")]
+    #[test]
+    fn test_check_word_list() {
+        use custom::check_word_list;
+
+        assert!(check_word_list(WordList::Nouns, &["Nebula", "Comet"], false).is_ok());
+        assert!(check_word_list(WordList::Colors, &[], false).is_err());
+        assert!(check_word_list(WordList::Nouns, &["Plasma"], true).is_err());
+    }
+}
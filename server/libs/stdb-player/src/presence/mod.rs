@@ -0,0 +1,175 @@
+use crate::{
+    error::PlayerError,
+    notification::{NotificationKindV1, repository::NotificationRepository},
+    player::repository::PlayerSessionRepository,
+    prelude::PlayerExt,
+    presence::repository::PresenceRepository,
+    vip::repository::VipRepository,
+};
+use log::warn;
+use spacetimedb::{Filter, ReducerContext, SpacetimeType, Table, Timestamp, client_visibility_filter, reducer, table};
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, validate_enum, validate_str};
+
+pub mod repository;
+
+/// Registers the game modes players can report in their presence, usually from the `stdb_init` of the game.
+///
+/// Game modes are stored in `stdb_game_mode_v1`, so they survive restarts and republishing the module.
+/// Without registered modes, any game mode up to 32 chars is accepted.
+///
+/// # Errors
+/// Returns error if a game mode is longer than 32 chars or game modes were already registered.
+pub fn register_valid_game_modes(ctx: &ReducerContext, game_modes: &[&str]) -> ServiceResult<()> {
+    if ctx.db.stdb_game_mode_v1().count() > 0 {
+        return Err(PlayerError::game_modes_already_registered());
+    }
+
+    for game_mode in game_modes {
+        validate_str("game_mode", game_mode, 1, 32)?;
+        ctx.db
+            .stdb_game_mode_v1()
+            .game_mode()
+            .try_insert_or_update(StdbGameModeV1 {
+                game_mode: (*game_mode).to_string(),
+            })
+            .map_conflict_ctx("failed to register game mode")?;
+    }
+    Ok(())
+}
+
+/// Rejects game modes that aren't in `game_modes`, an empty game mode is always valid.
+///
+/// Without registered game modes, any game mode is valid.
+///
+/// # Errors
+/// Returns a validation error if the game mode isn't registered.
+pub(crate) fn validate_game_mode(game_mode: &str, game_modes: &[String]) -> ServiceResult<()> {
+    if game_mode.is_empty() || game_modes.is_empty() {
+        return Ok(());
+    }
+    let allowed: Vec<_> = game_modes.iter().map(String::as_str).collect();
+    validate_enum("game_mode", game_mode, &allowed)
+}
+
+pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+/// Once the last online session of the player disconnects, the presence becomes idle and their VIP friends are notified.
+pub(crate) fn stdb_identity_disconnected(ctx: &ReducerContext) {
+    let Some(session) = ctx.find_session(ctx.sender) else {
+        return;
+    };
+
+    let other_online = ctx
+        .find_all_sessions_for_player(&session.player_id)
+        .iter()
+        .any(|other| other.session_id != ctx.sender && other.is_online);
+    if other_online {
+        return;
+    }
+
+    if let Err(error) = ctx.set_presence_idle(&session.player_id) {
+        warn!(
+            "failed to set disconnected player presence to idle: {:?}",
+            error.unwrap_chain()
+        );
+    }
+
+    for friend_id in ctx.find_friend_ids(&session.player_id) {
+        if let Err(error) = ctx.notify_player(friend_id, NotificationKindV1::FriendWentOffline, session.player_id.clone()) {
+            warn!("failed to notify friend of disconnected player: {:?}", error.unwrap_chain());
+        }
+    }
+}
+
+#[client_visibility_filter]
+const STDB_OWN_PLAYER_PRESENCE_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select p.*
+    from stdb_player_presence_v1 p
+    join stdb_own_player_session_v1 s
+        on s.player_id = p.player_id
+    where s.session_id = :sender
+"#,
+);
+
+#[client_visibility_filter]
+const STDB_FRIEND_PLAYER_PRESENCE_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select p.*
+    from stdb_player_presence_v1 p
+    join stdb_own_vip_v1 v
+        on v.receiver_id = p.player_id
+    join stdb_own_player_session_v1 s
+        on s.player_id = v.sender_id
+    where s.session_id = :sender
+        and v.status = 'Friends'
+"#,
+);
+
+/// Player presence table - what each player is currently doing.
+///
+/// Only visible to the player and their VIP friends. Becomes idle when the player disconnects.
+#[table(name = stdb_player_presence_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbPlayerPresenceV1 {
+    #[primary_key]
+    pub player_id: Uuid,
+
+    pub activity: PresenceActivityV1,
+
+    /// Game defined mode, e.g. "ranked" or "casual". Empty when it doesn't apply.
+    pub game_mode: String,
+    pub party_id: Option<Uuid>,
+    pub updated_at: Timestamp,
+}
+
+/// Game mode table - the game modes players can report in their presence, see [`register_valid_game_modes`].
+///
+/// Only visible to the server.
+#[table(name = stdb_game_mode_v1)]
+#[derive(Debug, Clone)]
+pub struct StdbGameModeV1 {
+    #[primary_key]
+    pub game_mode: String,
+}
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, SpacetimeType)]
+pub enum PresenceActivityV1 {
+    Idle,
+    InLobby,
+    InMatch,
+    InMenu,
+}
+
+#[reducer]
+pub fn update_presence_v1(
+    ctx: &ReducerContext,
+    activity: PresenceActivityV1,
+    game_mode: String,
+    party_id: Option<Uuid>,
+) -> ServiceResult<()> {
+    let session = ctx.require_online_session()?;
+    ctx.update_presence(session.player_id, activity, game_mode, party_id)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_game_mode() {
+        assert!(validate_game_mode("anything", &[]).is_ok());
+
+        let game_modes = ["ranked".to_string(), "casual".to_string()];
+        assert!(validate_game_mode("ranked", &game_modes).is_ok());
+        assert!(validate_game_mode("", &game_modes).is_ok());
+        assert!(validate_game_mode("anything", &game_modes).is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use crate::presence::{
+    PresenceActivityV1, StdbPlayerPresenceV1, stdb_game_mode_v1, stdb_player_presence_v1, validate_game_mode,
+};
+use spacetimedb::{ReducerContext, Table};
+use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, validate_str, validate_uuid};
+
+/// Repository trait for managing player presence.
+pub trait PresenceRepository {
+    fn find_presence(&self, player_id: &Uuid) -> Option<StdbPlayerPresenceV1>;
+
+    /// Creates or replaces the presence of a player.
+    ///
+    /// Validates game mode (up to 32 chars, one of the registered modes if any) and the party ID, if any.
+    ///
+    /// # Errors
+    /// Returns error if validation fails or database operations fail.
+    fn update_presence(
+        &self,
+        player_id: Uuid,
+        activity: PresenceActivityV1,
+        game_mode: impl Into<String>,
+        party_id: Option<Uuid>,
+    ) -> ServiceResult<StdbPlayerPresenceV1>;
+
+    /// Sets the activity of a player to idle, keeping the game mode and party. Does nothing without a presence.
+    ///
+    /// # Errors
+    /// Returns error if database operations fail.
+    fn set_presence_idle(&self, player_id: &Uuid) -> ServiceResult<()>;
+
+    /// Removes the presence of a player, returning whether there was one.
+    fn clear_presence(&self, player_id: &Uuid) -> bool;
+}
+
+impl PresenceRepository for ReducerContext {
+    fn find_presence(&self, player_id: &Uuid) -> Option<StdbPlayerPresenceV1> {
+        self.db.stdb_player_presence_v1().player_id().find(player_id)
+    }
+
+    fn update_presence(
+        &self,
+        player_id: Uuid,
+        activity: PresenceActivityV1,
+        game_mode: impl Into<String>,
+        party_id: Option<Uuid>,
+    ) -> ServiceResult<StdbPlayerPresenceV1> {
+        let game_mode = game_mode.into();
+        validate_uuid("player_id", &player_id)?;
+        validate_str("game_mode", &game_mode, 0, 32)?;
+        let game_modes: Vec<_> = self.db.stdb_game_mode_v1().iter().map(|row| row.game_mode).collect();
+        validate_game_mode(&game_mode, &game_modes)?;
+        if let Some(party_id) = &party_id {
+            validate_uuid("party_id", party_id)?;
+        }
+
+        self.db
+            .stdb_player_presence_v1()
+            .player_id()
+            .try_insert_or_update(StdbPlayerPresenceV1 {
+                player_id,
+                activity,
+                game_mode,
+                party_id,
+                updated_at: self.timestamp,
+            })
+            .map_conflict_ctx("failed to upsert presence")
+    }
+
+    fn set_presence_idle(&self, player_id: &Uuid) -> ServiceResult<()> {
+        let Some(mut presence) = self.find_presence(player_id) else {
+            return Ok(());
+        };
+
+        presence.activity = PresenceActivityV1::Idle;
+        presence.updated_at = self.timestamp;
+        self.db
+            .stdb_player_presence_v1()
+            .player_id()
+            .try_insert_or_update(presence)
+            .map_conflict_ctx("failed to set presence to idle")?;
+        Ok(())
+    }
+
+    fn clear_presence(&self, player_id: &Uuid) -> bool {
+        self.db.stdb_player_presence_v1().player_id().delete(player_id)
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{prelude::PlayerExt, reward::repository::RewardRepository};
+use spacetimedb::{Filter, ReducerContext, SpacetimeType, Timestamp, client_visibility_filter, reducer, table};
+use std::time::Duration;
+use stdb_common::prelude::{ServiceResult, Uuid, ValidateExt};
+
+pub mod repository;
+
+pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+/// Minimum time between two claims of the daily reward.
+pub const DAILY_REWARD_MIN_INTERVAL_HOURS: u64 = 20;
+
+/// Claims made after this window since the last claim reset the streak.
+pub const DAILY_REWARD_STREAK_WINDOW_HOURS: u64 = 48;
+
+/// Minimum time between two claim attempts, to avoid spamming the reducer.
+pub const CLAIM_DAILY_REWARD_COOLDOWN: Duration = Duration::from_secs(5);
+
+#[client_visibility_filter]
+const STDB_DAILY_REWARD_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select r.*
+    from stdb_daily_reward_v1 r
+    join stdb_own_player_session_v1 s
+        on s.player_id = r.player_id
+    where s.session_id = :sender
+"#,
+);
+
+/// Daily reward table - claim history and current streak of each player.
+#[table(name = stdb_daily_reward_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbDailyRewardV1 {
+    #[primary_key]
+    pub player_id: Uuid,
+
+    pub last_claimed_at: Timestamp,
+
+    /// Consecutive days the reward was claimed, starting at 1.
+    pub streak: u32,
+    pub total_claims: u32,
+}
+
+/// Reward definition table - what is granted on each streak day.
+///
+/// When the streak goes past the last defined day, the reward of the last day keeps being granted.
+#[table(name = stdb_reward_definition_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbRewardDefinitionV1 {
+    #[primary_key]
+    pub streak_day: u32,
+
+    pub reward_type: RewardTypeV1,
+    pub reward_amount: u32,
+}
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, SpacetimeType)]
+pub enum RewardTypeV1 {
+    /// Soft currency earned by playing
+    SoftCurrency,
+
+    /// Hard currency, usually bought with real money
+    HardCurrency,
+
+    /// Experience points
+    Experience,
+
+    /// Game specific item
+    Item,
+}
+
+#[reducer]
+pub fn claim_daily_reward_v1(ctx: &ReducerContext) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    let player = ctx.require_player(&session)?;
+    ctx.require_cooldown(&player.player_id, "claim_daily_reward", CLAIM_DAILY_REWARD_COOLDOWN)?;
+    ctx.claim_daily_reward(player.player_id)?;
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use crate::{
+    error::PlayerError,
+    reward::{
+        DAILY_REWARD_MIN_INTERVAL_HOURS, DAILY_REWARD_STREAK_WINDOW_HOURS, RewardTypeV1, StdbDailyRewardV1,
+        StdbRewardDefinitionV1, stdb_daily_reward_v1, stdb_reward_definition_v1,
+    },
+};
+use spacetimedb::{ReducerContext, Table};
+use std::time::Duration;
+use stdb_common::{
+    duration::DurationExt,
+    prelude::{ResultExt, ServiceResult, Uuid, validate_u32, validate_uuid},
+};
+
+/// Repository trait for managing daily login rewards.
+///
+/// A reward can be claimed once every [`DAILY_REWARD_MIN_INTERVAL_HOURS`]. Claiming within
+/// [`DAILY_REWARD_STREAK_WINDOW_HOURS`] of the previous claim extends the streak, otherwise it restarts at 1.
+pub trait RewardRepository {
+    /// Finds the daily reward state of a player.
+    ///
+    /// Returns `None` if the player never claimed a reward.
+    fn find_daily_reward(&self, player_id: &Uuid) -> Option<StdbDailyRewardV1>;
+
+    /// Finds the reward granted on a streak day.
+    ///
+    /// Falls back to the highest defined day below `streak_day`.
+    /// Returns `None` if no reward is defined up to that day.
+    fn find_reward_definition(&self, streak_day: u32) -> Option<StdbRewardDefinitionV1>;
+
+    /// Creates or updates the reward granted on a streak day.
+    ///
+    /// # Errors
+    /// Returns error if validation fails or database operations fail.
+    fn upsert_reward_definition(
+        &self,
+        streak_day: u32,
+        reward_type: RewardTypeV1,
+        reward_amount: u32,
+    ) -> ServiceResult<StdbRewardDefinitionV1>;
+
+    /// Claims the daily reward of a player and updates the streak.
+    ///
+    /// Returns the reward for the new streak day, if one is defined.
+    ///
+    /// # Errors
+    /// Returns `ServiceError::RateLimited` if the reward was already claimed today.
+    /// Returns error if validation fails or database operations fail.
+    fn claim_daily_reward(&self, player_id: Uuid) -> ServiceResult<Option<StdbRewardDefinitionV1>>;
+}
+
+impl RewardRepository for ReducerContext {
+    fn find_daily_reward(&self, player_id: &Uuid) -> Option<StdbDailyRewardV1> {
+        self.db.stdb_daily_reward_v1().player_id().find(player_id)
+    }
+
+    fn find_reward_definition(&self, streak_day: u32) -> Option<StdbRewardDefinitionV1> {
+        self.db
+            .stdb_reward_definition_v1()
+            .iter()
+            .filter(|definition| definition.streak_day <= streak_day)
+            .max_by_key(|definition| definition.streak_day)
+    }
+
+    fn upsert_reward_definition(
+        &self,
+        streak_day: u32,
+        reward_type: RewardTypeV1,
+        reward_amount: u32,
+    ) -> ServiceResult<StdbRewardDefinitionV1> {
+        validate_u32("streak_day", streak_day, 1, u32::MAX)?;
+
+        self.db
+            .stdb_reward_definition_v1()
+            .streak_day()
+            .try_insert_or_update(StdbRewardDefinitionV1 {
+                streak_day,
+                reward_type,
+                reward_amount,
+            })
+            .map_conflict_ctx("failed to insert or update reward definition")
+    }
+
+    fn claim_daily_reward(&self, player_id: Uuid) -> ServiceResult<Option<StdbRewardDefinitionV1>> {
+        validate_uuid("player_id", &player_id)?;
+
+        let reward = match self.find_daily_reward(&player_id) {
+            Some(mut reward) => {
+                let elapsed = self.timestamp.duration_since(reward.last_claimed_at).unwrap_or_default();
+                let Some(streak) = next_daily_streak(reward.streak, elapsed) else {
+                    return Err(PlayerError::daily_reward_already_claimed(player_id));
+                };
+
+                reward.streak = streak;
+                reward.total_claims = reward.total_claims.saturating_add(1);
+                reward.last_claimed_at = self.timestamp;
+                reward
+            },
+            None => StdbDailyRewardV1 {
+                player_id,
+                last_claimed_at: self.timestamp,
+                streak: 1,
+                total_claims: 1,
+            },
+        };
+
+        let reward = self
+            .db
+            .stdb_daily_reward_v1()
+            .player_id()
+            .try_insert_or_update(reward)
+            .map_conflict_ctx("failed to claim daily reward")?;
+
+        Ok(self.find_reward_definition(reward.streak))
+    }
+}
+
+/// Computes the streak after a claim made `elapsed` after the previous one.
+///
+/// Returns `None` when the previous claim is too recent to claim again.
+pub(crate) fn next_daily_streak(streak: u32, elapsed: Duration) -> Option<u32> {
+    if elapsed < Duration::from_hours_ext(DAILY_REWARD_MIN_INTERVAL_HOURS) {
+        None
+    } else if elapsed < Duration::from_hours_ext(DAILY_REWARD_STREAK_WINDOW_HOURS) {
+        Some(streak.saturating_add(1))
+    } else {
+        Some(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_daily_streak_too_soon() {
+        assert_eq!(next_daily_streak(3, Duration::from_hours_ext(19)), None);
+        assert_eq!(next_daily_streak(3, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn test_next_daily_streak_continues() {
+        assert_eq!(next_daily_streak(3, Duration::from_hours_ext(20)), Some(4));
+        assert_eq!(next_daily_streak(3, Duration::from_hours_ext(47)), Some(4));
+    }
+
+    #[test]
+    fn test_next_daily_streak_resets() {
+        assert_eq!(next_daily_streak(3, Duration::from_hours_ext(48)), Some(1));
+        assert_eq!(next_daily_streak(3, Duration::from_days_ext(10)), Some(1));
+    }
+}
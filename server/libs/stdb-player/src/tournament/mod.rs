@@ -0,0 +1,111 @@
+use crate::{prelude::PlayerExt, tournament::repository::TournamentRepository};
+use spacetimedb::{ReducerContext, Timestamp, reducer, table};
+use stdb_common::prelude::{ServiceResult, Uuid, ValidateExt};
+
+pub mod repository;
+
+/// Maximum number of participants of a single tournament.
+pub const TOURNAMENT_MAX_PARTICIPANTS: u32 = 1024;
+
+pub(crate) fn stdb_init(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()> {
+    Ok(())
+}
+
+pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
+
+/// Tournament table - single-elimination tournaments, visible to all players.
+///
+/// Players register until `starts_at`, the bracket can only be generated from then on. `ended_at` is set
+/// once the final is reported.
+#[table(name = stdb_tournament_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbTournamentV1 {
+    #[primary_key]
+    pub tournament_id: Uuid,
+
+    pub name: String,
+    pub max_participants: u32,
+    pub starts_at: Timestamp,
+    pub ended_at: Option<Timestamp>,
+}
+
+/// Tournament participant table - players registered in each tournament, visible to all players.
+///
+/// Seeds follow the registration order, starting at 1.
+#[table(
+    name = stdb_tournament_participant_v1,
+    public,
+    index(name = tournament_player_index, btree(columns = [tournament_id, player_id]))
+)]
+#[derive(Debug, Clone)]
+pub struct StdbTournamentParticipantV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub id: u64,
+
+    pub tournament_id: Uuid,
+
+    #[index(btree)]
+    pub player_id: Uuid,
+
+    pub seed: u32,
+    pub registered_at: Timestamp,
+}
+
+/// Tournament match table - the bracket of each tournament, visible to all players.
+///
+/// Within a round, matches ordered by `match_id` follow the bracket: the winners of matches 1 and 2 meet
+/// in the next round, then 3 and 4, and so on. Byes have a nil `player_b_id` and are won by `player_a_id`.
+#[table(name = stdb_tournament_match_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbTournamentMatchV1 {
+    #[auto_inc]
+    #[primary_key]
+    pub match_id: u64,
+
+    #[index(btree)]
+    pub tournament_id: Uuid,
+
+    /// Starts at 1.
+    pub round: u32,
+    pub player_a_id: Uuid,
+    pub player_b_id: Uuid,
+    pub winner_id: Option<Uuid>,
+}
+
+#[reducer]
+pub fn create_tournament_v1(
+    ctx: &ReducerContext,
+    name: String,
+    max_participants: u32,
+    starts_at: Timestamp,
+) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.create_tournament(name, max_participants, starts_at)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn register_tournament_v1(ctx: &ReducerContext, tournament_id: Uuid) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.register(&tournament_id, session.player_id)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn generate_tournament_bracket_v1(ctx: &ReducerContext, tournament_id: Uuid) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.generate_bracket(&tournament_id)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn report_tournament_match_v1(ctx: &ReducerContext, match_id: u64, winner_id: Uuid) -> ServiceResult<()> {
+    ctx.require_private_access()?;
+    ctx.report_match_result(match_id, winner_id)?;
+    Ok(())
+}
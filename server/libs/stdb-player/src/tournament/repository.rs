@@ -0,0 +1,516 @@
+use crate::{
+    error::PlayerError,
+    tournament::{
+        StdbTournamentMatchV1, StdbTournamentParticipantV1, StdbTournamentV1, TOURNAMENT_MAX_PARTICIPANTS,
+        stdb_tournament_match_v1, stdb_tournament_participant_v1, stdb_tournament_v1,
+    },
+};
+use spacetimedb::{ReducerContext, Table, Timestamp};
+use stdb_common::prelude::{
+    ResultExt, ServiceResult, TypedUuid, Uuid, UuidExt, ValidationError, validate_str, validate_u32, validate_uuid,
+};
+
+/// Repository trait for managing single-elimination tournaments.
+pub trait TournamentRepository {
+    fn find_tournament(&self, tournament_id: &Uuid) -> Option<StdbTournamentV1>;
+
+    /// Returns the participants of a tournament, ordered by seed.
+    fn find_participants(&self, tournament_id: &Uuid) -> Vec<StdbTournamentParticipantV1>;
+
+    /// Returns the matches of a tournament, ordered by round and bracket position.
+    fn find_tournament_matches(&self, tournament_id: &Uuid) -> Vec<StdbTournamentMatchV1>;
+
+    /// Creates a tournament.
+    ///
+    /// Validates name (1-64 chars) and max participants (2-1024).
+    ///
+    /// # Errors
+    /// Returns error if validation fails or database operations fail.
+    fn create_tournament(
+        &self,
+        name: impl Into<String>,
+        max_participants: u32,
+        starts_at: Timestamp,
+    ) -> ServiceResult<StdbTournamentV1>;
+
+    /// Registers a player, seeded after the players who registered before.
+    ///
+    /// # Errors
+    /// Returns error if the tournament doesn't exist, it already started, its bracket was already generated,
+    /// it's full, the player is already registered or database operations fail.
+    fn register(&self, tournament_id: &Uuid, player_id: Uuid) -> ServiceResult<StdbTournamentParticipantV1>;
+
+    /// Generates the first round of the bracket. Top seeds get byes when the participants
+    /// aren't a power of two.
+    ///
+    /// # Errors
+    /// Returns error if the tournament doesn't exist, it hasn't started yet, its bracket was already generated,
+    /// it has fewer than 2 participants or database operations fail.
+    fn generate_bracket(&self, tournament_id: &Uuid) -> ServiceResult<Vec<StdbTournamentMatchV1>>;
+
+    /// Records the winner of a match. Once every match of the round has a winner, the next round
+    /// is generated, or the tournament ends after the final.
+    ///
+    /// # Errors
+    /// Returns error if the match doesn't exist, already has a winner, the winner didn't play it
+    /// or database operations fail.
+    fn report_match_result(&self, match_id: u64, winner_id: Uuid) -> ServiceResult<StdbTournamentMatchV1>;
+}
+
+impl TournamentRepository for ReducerContext {
+    fn find_tournament(&self, tournament_id: &Uuid) -> Option<StdbTournamentV1> {
+        self.db.stdb_tournament_v1().tournament_id().find(tournament_id)
+    }
+
+    fn find_participants(&self, tournament_id: &Uuid) -> Vec<StdbTournamentParticipantV1> {
+        let mut participants: Vec<_> = self
+            .db
+            .stdb_tournament_participant_v1()
+            .tournament_player_index()
+            .filter(tournament_id)
+            .collect();
+        participants.sort_by_key(|participant| participant.seed);
+        participants
+    }
+
+    fn find_tournament_matches(&self, tournament_id: &Uuid) -> Vec<StdbTournamentMatchV1> {
+        let mut matches: Vec<_> = self
+            .db
+            .stdb_tournament_match_v1()
+            .tournament_id()
+            .filter(tournament_id)
+            .collect();
+        matches.sort_by_key(|tournament_match| (tournament_match.round, tournament_match.match_id));
+        matches
+    }
+
+    fn create_tournament(
+        &self,
+        name: impl Into<String>,
+        max_participants: u32,
+        starts_at: Timestamp,
+    ) -> ServiceResult<StdbTournamentV1> {
+        let name = name.into();
+        validate_str("name", &name, 1, 64)?;
+        validate_u32("max_participants", max_participants, 2, TOURNAMENT_MAX_PARTICIPANTS)?;
+
+        self.db
+            .stdb_tournament_v1()
+            .try_insert(StdbTournamentV1 {
+                tournament_id: self.new_uuid_v7(),
+                name,
+                max_participants,
+                starts_at,
+                ended_at: None,
+            })
+            .map_conflict_ctx("failed to insert tournament")
+    }
+
+    fn register(&self, tournament_id: &Uuid, player_id: Uuid) -> ServiceResult<StdbTournamentParticipantV1> {
+        validate_uuid("player_id", &player_id)?;
+        let tournament = require_open_tournament(self, tournament_id)?;
+        require_before_start(&tournament, self.timestamp)?;
+
+        let participants = self.find_participants(tournament_id);
+        if participants.iter().any(|participant| participant.player_id == player_id) {
+            return Err(PlayerError::already_in_tournament(player_id));
+        }
+        if participants.len() >= tournament.max_participants as usize {
+            return Err(PlayerError::tournament_full(tournament_id.clone()));
+        }
+
+        self.db
+            .stdb_tournament_participant_v1()
+            .try_insert(StdbTournamentParticipantV1 {
+                id: 0,
+                tournament_id: tournament_id.clone(),
+                player_id,
+                seed: participants.len() as u32 + 1,
+                registered_at: self.timestamp,
+            })
+            .map_conflict_ctx("failed to insert tournament participant")
+    }
+
+    fn generate_bracket(&self, tournament_id: &Uuid) -> ServiceResult<Vec<StdbTournamentMatchV1>> {
+        let tournament = require_open_tournament(self, tournament_id)?;
+        require_started(&tournament, self.timestamp)?;
+
+        let player_ids: Vec<_> = self
+            .find_participants(tournament_id)
+            .into_iter()
+            .map(|participant| participant.player_id)
+            .collect();
+        if player_ids.len() < 2 {
+            return Err(ValidationError::field_too_small("participants", 2));
+        }
+
+        let matches = first_round_pairings(player_ids.len())
+            .into_iter()
+            .map(|(seed_a, seed_b)| {
+                let player_a_id = player_ids[seed_a - 1].clone();
+                let player_b_id = seed_b.map(|seed| player_ids[seed - 1].clone());
+                insert_match(self, tournament_id, 1, player_a_id, player_b_id)
+            })
+            .collect::<ServiceResult<Vec<_>>>()?;
+
+        advance_round(self, tournament_id, 1)?;
+        Ok(matches)
+    }
+
+    fn report_match_result(&self, match_id: u64, winner_id: Uuid) -> ServiceResult<StdbTournamentMatchV1> {
+        let Some(mut tournament_match) = self.db.stdb_tournament_match_v1().match_id().find(match_id) else {
+            return Err(PlayerError::tournament_match_not_found(match_id));
+        };
+        if tournament_match.winner_id.is_some()
+            || (winner_id != tournament_match.player_a_id && winner_id != tournament_match.player_b_id)
+        {
+            return Err(PlayerError::invalid_match_result(match_id));
+        }
+
+        tournament_match.winner_id = Some(winner_id);
+        let tournament_match = self
+            .db
+            .stdb_tournament_match_v1()
+            .match_id()
+            .try_insert_or_update(tournament_match)
+            .map_conflict_ctx("failed to report tournament match")?;
+
+        advance_round(self, &tournament_match.tournament_id, tournament_match.round)?;
+        Ok(tournament_match)
+    }
+}
+
+/// Finds a tournament that still accepts registrations, i.e. without a bracket.
+fn require_open_tournament(ctx: &ReducerContext, tournament_id: &Uuid) -> ServiceResult<StdbTournamentV1> {
+    let Some(tournament) = ctx.find_tournament(tournament_id) else {
+        return Err(PlayerError::tournament_not_found(tournament_id.clone()));
+    };
+
+    let has_bracket = ctx
+        .db
+        .stdb_tournament_match_v1()
+        .tournament_id()
+        .filter(tournament_id)
+        .next()
+        .is_some();
+    if has_bracket || tournament.ended_at.is_some() {
+        return Err(PlayerError::tournament_closed(tournament_id.clone()));
+    }
+
+    Ok(tournament)
+}
+
+/// Generates the next round once every match of `round` has a winner, or ends the tournament after the final.
+fn advance_round(ctx: &ReducerContext, tournament_id: &Uuid, round: u32) -> ServiceResult<()> {
+    let matches: Vec<_> = ctx
+        .find_tournament_matches(tournament_id)
+        .into_iter()
+        .filter(|tournament_match| tournament_match.round == round)
+        .collect();
+
+    match round_outcome(&matches) {
+        RoundOutcome::InProgress => Ok(()),
+        RoundOutcome::Champion(_) => {
+            let Some(mut tournament) = ctx.find_tournament(tournament_id) else {
+                return Err(PlayerError::tournament_not_found(tournament_id.clone()));
+            };
+            tournament.ended_at = Some(ctx.timestamp);
+            ctx.db
+                .stdb_tournament_v1()
+                .tournament_id()
+                .try_insert_or_update(tournament)
+                .map_conflict_ctx("failed to end tournament")?;
+            Ok(())
+        },
+        RoundOutcome::NextRound(pairings) => {
+            for (player_a_id, player_b_id) in pairings {
+                insert_match(ctx, tournament_id, round + 1, player_a_id, player_b_id)?;
+            }
+
+            // Only completes right away if the next round is made of byes
+            advance_round(ctx, tournament_id, round + 1)
+        },
+    }
+}
+
+/// Inserts a match, a missing `player_b_id` is a bye won by `player_a_id`.
+///
+/// Doesn't advance the round, callers do once the whole round is inserted.
+fn insert_match(
+    ctx: &ReducerContext,
+    tournament_id: &Uuid,
+    round: u32,
+    player_a_id: Uuid,
+    player_b_id: Option<Uuid>,
+) -> ServiceResult<StdbTournamentMatchV1> {
+    ctx.db
+        .stdb_tournament_match_v1()
+        .try_insert(new_match(tournament_id, round, player_a_id, player_b_id))
+        .map_conflict_ctx("failed to insert tournament match")
+}
+
+/// Builds a match row, a missing `player_b_id` is a bye won by `player_a_id`.
+pub(crate) fn new_match(
+    tournament_id: &Uuid,
+    round: u32,
+    player_a_id: Uuid,
+    player_b_id: Option<Uuid>,
+) -> StdbTournamentMatchV1 {
+    let (player_b_id, winner_id) = match player_b_id {
+        Some(player_b_id) => (player_b_id, None),
+        None => (TypedUuid::nil().into(), Some(player_a_id.clone())),
+    };
+
+    StdbTournamentMatchV1 {
+        match_id: 0,
+        tournament_id: tournament_id.clone(),
+        round,
+        player_a_id,
+        player_b_id,
+        winner_id,
+    }
+}
+
+/// Fails once the tournament started, players can only register before.
+pub(crate) fn require_before_start(tournament: &StdbTournamentV1, now: Timestamp) -> ServiceResult<()> {
+    if now >= tournament.starts_at {
+        return Err(PlayerError::tournament_closed(tournament.tournament_id.clone()));
+    }
+    Ok(())
+}
+
+/// Fails until the tournament starts, the bracket can't be generated before.
+pub(crate) fn require_started(tournament: &StdbTournamentV1, now: Timestamp) -> ServiceResult<()> {
+    if now < tournament.starts_at {
+        return Err(PlayerError::tournament_not_started(tournament.tournament_id.clone()));
+    }
+    Ok(())
+}
+
+/// What follows a round of the bracket.
+#[derive(Debug, PartialEq)]
+pub(crate) enum RoundOutcome {
+    /// Some matches of the round don't have a winner yet.
+    InProgress,
+    /// The matches of the next round, the winners of matches 1 and 2 meet first. A missing opponent is a bye.
+    NextRound(Vec<(Uuid, Option<Uuid>)>),
+    /// The final was won.
+    Champion(Uuid),
+}
+
+/// The outcome of a round, `matches` must be the matches of that round in bracket order.
+pub(crate) fn round_outcome(matches: &[StdbTournamentMatchV1]) -> RoundOutcome {
+    let Some(mut winner_ids) = matches
+        .iter()
+        .map(|tournament_match| tournament_match.winner_id.clone())
+        .collect::<Option<Vec<_>>>()
+        .filter(|winner_ids| !winner_ids.is_empty())
+    else {
+        return RoundOutcome::InProgress;
+    };
+
+    if winner_ids.len() == 1 {
+        return RoundOutcome::Champion(winner_ids.remove(0));
+    }
+
+    let pairings = winner_ids
+        .chunks(2)
+        .map(|pair| (pair[0].clone(), pair.get(1).cloned()))
+        .collect();
+    RoundOutcome::NextRound(pairings)
+}
+
+/// Seeds in bracket order for a bracket of `size` slots, a power of two, so that seeds 1 and 2 can only meet in the final.
+pub(crate) fn bracket_order(size: usize) -> Vec<usize> {
+    let mut order = vec![1];
+    while order.len() < size {
+        let slots = order.len() * 2;
+        order = order.into_iter().flat_map(|seed| [seed, slots + 1 - seed]).collect();
+    }
+    order
+}
+
+/// First round matches of `participants` seeds, in bracket order. Seeds without an opponent get a bye.
+pub(crate) fn first_round_pairings(participants: usize) -> Vec<(usize, Option<usize>)> {
+    bracket_order(participants.next_power_of_two())
+        .chunks(2)
+        .map(|pair| (pair[0], Some(pair[1]).filter(|seed| *seed <= participants)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{at, uuid};
+
+    const TOURNAMENT_ID: &str = "01890a5d-ac96-774b-bcce-b302099a8070";
+
+    fn tournament(starts_at: i64) -> StdbTournamentV1 {
+        StdbTournamentV1 {
+            tournament_id: uuid(TOURNAMENT_ID),
+            name: "Weekly Cup".to_string(),
+            max_participants: 8,
+            starts_at: at(starts_at),
+            ended_at: None,
+        }
+    }
+
+    fn seed(player_id: &Uuid) -> usize {
+        player_id.strip_prefix("seed-").unwrap().parse().unwrap()
+    }
+
+    /// Plays the whole bracket of `participants` seeds, `winner` picks the winner of each played match.
+    /// Returns the rounds in order and the champion.
+    fn play_bracket(participants: usize, winner: impl Fn(&Uuid, &Uuid) -> Uuid) -> (Vec<Vec<StdbTournamentMatchV1>>, Uuid) {
+        let tournament_id = uuid(TOURNAMENT_ID);
+        let player_id = |seed: usize| format!("seed-{seed}");
+        let mut round: Vec<_> = first_round_pairings(participants)
+            .into_iter()
+            .map(|(seed_a, seed_b)| new_match(&tournament_id, 1, player_id(seed_a), seed_b.map(player_id)))
+            .collect();
+        let mut rounds = vec![];
+
+        loop {
+            if round.iter().any(|tournament_match| tournament_match.winner_id.is_none()) {
+                assert_eq!(round_outcome(&round), RoundOutcome::InProgress);
+            }
+            for tournament_match in round
+                .iter_mut()
+                .filter(|tournament_match| tournament_match.winner_id.is_none())
+            {
+                tournament_match.winner_id = Some(winner(&tournament_match.player_a_id, &tournament_match.player_b_id));
+            }
+
+            let outcome = round_outcome(&round);
+            rounds.push(round);
+            let next = rounds.len() as u32 + 1;
+            round = match outcome {
+                RoundOutcome::NextRound(pairings) => pairings
+                    .into_iter()
+                    .map(|(player_a_id, player_b_id)| new_match(&tournament_id, next, player_a_id, player_b_id))
+                    .collect(),
+                RoundOutcome::Champion(champion) => return (rounds, champion),
+                RoundOutcome::InProgress => unreachable!("every match has a winner"),
+            };
+        }
+    }
+
+    #[test]
+    fn test_require_before_start() {
+        let tournament = tournament(100);
+        assert!(require_before_start(&tournament, at(99)).is_ok());
+
+        let error = require_before_start(&tournament, at(100)).unwrap_err();
+        assert_eq!(error.message(), PlayerError::tournament_closed(uuid(TOURNAMENT_ID)).message());
+    }
+
+    #[test]
+    fn test_require_started() {
+        let tournament = tournament(100);
+        assert!(require_started(&tournament, at(100)).is_ok());
+        assert!(require_started(&tournament, at(101)).is_ok());
+
+        let error = require_started(&tournament, at(99)).unwrap_err();
+        assert_eq!(
+            error.message(),
+            PlayerError::tournament_not_started(uuid(TOURNAMENT_ID)).message()
+        );
+    }
+
+    #[test]
+    fn test_round_outcome_waits_for_every_match() {
+        let tournament_id = uuid(TOURNAMENT_ID);
+        let mut matches = vec![
+            new_match(&tournament_id, 1, uuid("seed-1"), Some(uuid("seed-4"))),
+            new_match(&tournament_id, 1, uuid("seed-2"), Some(uuid("seed-3"))),
+        ];
+        assert_eq!(round_outcome(&matches), RoundOutcome::InProgress);
+
+        matches[0].winner_id = Some(uuid("seed-4"));
+        assert_eq!(round_outcome(&matches), RoundOutcome::InProgress);
+
+        matches[1].winner_id = Some(uuid("seed-2"));
+        assert_eq!(
+            round_outcome(&matches),
+            RoundOutcome::NextRound(vec![(uuid("seed-4"), Some(uuid("seed-2")))])
+        );
+        assert_eq!(round_outcome(&[]), RoundOutcome::InProgress);
+    }
+
+    #[test]
+    fn test_full_bracket_with_byes() {
+        let top_seed = |player_a_id: &Uuid, player_b_id: &Uuid| {
+            if seed(player_a_id) < seed(player_b_id) {
+                player_a_id.clone()
+            } else {
+                player_b_id.clone()
+            }
+        };
+        let (rounds, champion) = play_bracket(6, top_seed);
+
+        let pairings: Vec<Vec<_>> = rounds
+            .iter()
+            .map(|round| {
+                round
+                    .iter()
+                    .map(|tournament_match| (tournament_match.player_a_id.as_str(), tournament_match.player_b_id.as_str()))
+                    .collect()
+            })
+            .collect();
+        let bye = TypedUuid::nil().to_string();
+        assert_eq!(
+            pairings,
+            [
+                vec![
+                    ("seed-1", bye.as_str()),
+                    ("seed-4", "seed-5"),
+                    ("seed-2", bye.as_str()),
+                    ("seed-3", "seed-6")
+                ],
+                vec![("seed-1", "seed-4"), ("seed-2", "seed-3")],
+                vec![("seed-1", "seed-2")],
+            ]
+        );
+        assert!(rounds.iter().enumerate().all(|(index, round)| {
+            round
+                .iter()
+                .all(|tournament_match| tournament_match.round == index as u32 + 1)
+        }));
+        assert_eq!(champion, "seed-1");
+    }
+
+    #[test]
+    fn test_full_bracket_with_upsets() {
+        let bottom_seed = |player_a_id: &Uuid, player_b_id: &Uuid| {
+            if seed(player_a_id) > seed(player_b_id) {
+                player_a_id.clone()
+            } else {
+                player_b_id.clone()
+            }
+        };
+        let (rounds, champion) = play_bracket(8, bottom_seed);
+
+        assert_eq!(rounds.iter().map(Vec::len).collect::<Vec<_>>(), [4, 2, 1]);
+        assert_eq!(champion, "seed-8");
+    }
+
+    #[test]
+    fn test_bracket_order() {
+        assert_eq!(bracket_order(1), [1]);
+        assert_eq!(bracket_order(2), [1, 2]);
+        assert_eq!(bracket_order(4), [1, 4, 2, 3]);
+        assert_eq!(bracket_order(8), [1, 8, 4, 5, 2, 7, 3, 6]);
+    }
+
+    #[test]
+    fn test_first_round_pairings() {
+        assert_eq!(first_round_pairings(2), [(1, Some(2))]);
+        assert_eq!(first_round_pairings(4), [(1, Some(4)), (2, Some(3))]);
+    }
+
+    #[test]
+    fn test_first_round_pairings_with_byes() {
+        assert_eq!(first_round_pairings(3), [(1, None), (2, Some(3))]);
+        assert_eq!(first_round_pairings(6), [(1, None), (4, Some(5)), (2, None), (3, Some(6))]);
+    }
+}
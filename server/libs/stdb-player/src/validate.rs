@@ -1,9 +1,57 @@
-use crate::player::{
-    StdbOwnPlayerSessionV1, StdbOwnPlayerV1,
-    repository::{PlayerRepository, PlayerSessionRepository},
+#[cfg(feature = "block")]
+use crate::block::repository::BlockRepository;
+#[cfg(feature = "event")]
+use crate::event::repository::EventRepository;
+use crate::{
+    error::PlayerError,
+    player::{
+        SESSION_EXPIRY, StdbOwnPlayerSessionV1, StdbOwnPlayerV1, StdbProfaneWordV1,
+        repository::{PlayerRepository, PlayerSessionRepository},
+        stdb_profane_word_v1,
+    },
 };
-use spacetimedb::ReducerContext;
-use stdb_common::prelude::{ServiceError, ServiceResult};
+use spacetimedb::{ReducerContext, Table};
+use std::time::Duration;
+use stdb_common::prelude::{ResultExt, ServiceError, ServiceResult, Uuid, ValidateExt, ValidationError, validate_str};
+
+/// Adds words display names are checked against, usually from the `stdb_init` of the game.
+///
+/// Words are stored lowercased in `stdb_profane_word_v1`, so they survive restarts and republishing the
+/// module. Registering a word twice is a no-op. Without words, display names are only checked for their length.
+///
+/// # Errors
+/// Returns error if a word is empty or longer than 64 chars, or database operations fail.
+pub fn register_profane_words(ctx: &ReducerContext, words: &[&str]) -> ServiceResult<()> {
+    for word in words {
+        validate_str("word", word, 1, 64)?;
+        ctx.db
+            .stdb_profane_word_v1()
+            .word()
+            .try_insert_or_update(StdbProfaneWordV1 {
+                word: word.to_lowercase(),
+            })
+            .map_conflict_ctx("failed to register profane word")?;
+    }
+    Ok(())
+}
+
+/// Rejects the display name if it contains any of the registered profane words.
+///
+/// # Errors
+/// Returns a validation error if the display name is profane.
+pub(crate) fn validate_display_name_content(ctx: &ReducerContext, display_name: &str) -> ServiceResult<()> {
+    let words = ctx.db.stdb_profane_word_v1().iter().map(|row| row.word);
+    if is_profane(display_name, words) {
+        return Err(ValidationError::field_error("display_name", "inappropriate content"));
+    }
+    Ok(())
+}
+
+/// Whether `display_name` contains any of the lowercased `words`, ignoring case.
+pub(crate) fn is_profane(display_name: &str, words: impl IntoIterator<Item = String>) -> bool {
+    let display_name = display_name.to_lowercase();
+    words.into_iter().any(|word| display_name.contains(&word))
+}
 
 /// Extension trait for player validation and authorization operations.
 ///
@@ -17,11 +65,23 @@ pub trait PlayerExt {
     /// This is typically used as the first step in player-related operations
     /// to ensure the request comes from an authenticated user.
     ///
+    /// Offline sessions whose player signed out more than [`SESSION_EXPIRY`] ago are rejected until
+    /// the player signs in again, the scheduled session cleanup marks them as expired.
+    ///
     /// # Errors
-    /// Returns `ServiceError::unauthorized()` if no session exists for the sender.
-    #[must_use]
+    /// Returns `ServiceError::unauthorized()` if no session exists for the sender, or
+    /// `ServiceError::Unauthorized` if the session expired.
     fn require_session(&self) -> ServiceResult<StdbOwnPlayerSessionV1>;
 
+    /// Requires that the current sender has a session that is currently online.
+    ///
+    /// Same as [`PlayerExt::require_session`], for reducers that should only run while
+    /// the player is actively connected, e.g. sending chat messages.
+    ///
+    /// # Errors
+    /// Returns `ServiceError::Unauthorized` if no session exists for the sender or it's offline.
+    fn require_online_session(&self) -> ServiceResult<StdbOwnPlayerSessionV1>;
+
     /// Requires that the session belongs to the current sender and has a valid player.
     ///
     /// Validates that:
@@ -34,13 +94,58 @@ pub trait PlayerExt {
     /// Returns `ServiceError::unauthorized()` if:
     /// - Session doesn't belong to the current sender
     /// - No player exists for the session's player ID
-    #[must_use]
     fn require_player(&self, session: &StdbOwnPlayerSessionV1) -> ServiceResult<StdbOwnPlayerV1>;
+
+    /// Requires that the player of the current session isn't blocked by `target_id`.
+    ///
+    /// Only checks blocks when the `block` feature is enabled, otherwise always succeeds.
+    ///
+    /// # Errors
+    /// Returns `ServiceError::Unauthorized` if the sender has no session, and
+    /// `ServiceError::Forbidden` if the target blocked the sender's player.
+    fn require_not_blocked(&self, target_id: &Uuid) -> ServiceResult<()>;
+
+    /// Requires that the player of the current session owns the resource being modified.
+    ///
+    /// # Errors
+    /// Returns `ServiceError::Unauthorized` if the sender has no session, and
+    /// `ServiceError::Forbidden` if the resource belongs to another player.
+    fn require_owner(&self, resource_owner_id: &Uuid) -> ServiceResult<StdbOwnPlayerSessionV1>;
+
+    /// Requires that the player of the current session didn't perform `action_key` in the last `duration`.
+    ///
+    /// Shortcut for [`PlayerExt::require_session`] followed by [`ValidateExt::require_cooldown`].
+    ///
+    /// # Errors
+    /// Returns `ServiceError::Unauthorized` if the sender has no session, and
+    /// `ServiceError::RateLimited` with a "try again in Xs" message if the cooldown is still active.
+    fn require_rate_limit(&self, action_key: &str, duration: Duration) -> ServiceResult<StdbOwnPlayerSessionV1>;
+
+    /// Requires that the seasonal event exists and is currently running.
+    ///
+    /// # Errors
+    /// Returns `ServiceError::Forbidden` if the event doesn't exist, didn't start yet or already ended.
+    #[cfg(feature = "event")]
+    fn require_event_active(&self, event_id: &Uuid) -> ServiceResult<()>;
 }
 
 impl PlayerExt for ReducerContext {
     fn require_session(&self) -> ServiceResult<StdbOwnPlayerSessionV1> {
-        self.find_session(self.sender).ok_or(ServiceError::unauthorized())
+        let session = self.find_session(self.sender).ok_or(ServiceError::unauthorized())?;
+        if self.is_session_expired(&session, SESSION_EXPIRY) {
+            return Err(PlayerError::session_expired(session.session_id));
+        }
+
+        Ok(session)
+    }
+
+    fn require_online_session(&self) -> ServiceResult<StdbOwnPlayerSessionV1> {
+        self.require_session().and_validate(|session| {
+            if !session.is_online {
+                return Err(ServiceError::Unauthorized("Session is not online".into()));
+            }
+            Ok(())
+        })
     }
 
     fn require_player(&self, session: &StdbOwnPlayerSessionV1) -> ServiceResult<StdbOwnPlayerV1> {
@@ -50,4 +155,58 @@ impl PlayerExt for ReducerContext {
 
         self.find_player(&session.player_id).ok_or(ServiceError::unauthorized())
     }
+
+    #[cfg(feature = "block")]
+    fn require_not_blocked(&self, target_id: &Uuid) -> ServiceResult<()> {
+        let session = self.require_session()?;
+        if self.is_blocked(target_id, &session.player_id) {
+            return Err(PlayerError::blocked_by_player(target_id.clone()));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "block"))]
+    fn require_not_blocked(&self, _target_id: &Uuid) -> ServiceResult<()> {
+        Ok(())
+    }
+
+    fn require_owner(&self, resource_owner_id: &Uuid) -> ServiceResult<StdbOwnPlayerSessionV1> {
+        let session = self.require_session()?;
+        if session.player_id != *resource_owner_id {
+            return Err(ServiceError::Forbidden("Resource belongs to another player".into()));
+        }
+
+        Ok(session)
+    }
+
+    fn require_rate_limit(&self, action_key: &str, duration: Duration) -> ServiceResult<StdbOwnPlayerSessionV1> {
+        let session = self.require_session()?;
+        self.require_cooldown(&session.player_id, action_key, duration)?;
+        Ok(session)
+    }
+
+    #[cfg(feature = "event")]
+    fn require_event_active(&self, event_id: &Uuid) -> ServiceResult<()> {
+        if !self.is_event_active(event_id) {
+            return Err(PlayerError::event_not_active(event_id.clone()));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_profane() {
+        assert!(!is_profane("Darn Wolf", Vec::new()));
+
+        let words = || vec!["darn".to_string()];
+        assert!(!is_profane("Swift Wolf", words()));
+        assert!(is_profane("Darn Wolf", words()));
+        assert!(is_profane("SwiftDARNWolf", words()));
+    }
 }
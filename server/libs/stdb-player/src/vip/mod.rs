@@ -1,6 +1,6 @@
 use crate::{prelude::PlayerExt, vip::repository::VipRepository};
 use spacetimedb::{Filter, ReducerContext, SpacetimeType, Timestamp, client_visibility_filter, reducer, table};
-use std::u64;
+use std::time::Duration;
 use stdb_common::prelude::{ServiceResult, Uuid};
 
 pub mod repository;
@@ -15,6 +15,15 @@ pub(crate) fn stdb_identity_connected(_ctx: &ReducerContext) -> ServiceResult<()
 
 pub(crate) fn stdb_identity_disconnected(_ctx: &ReducerContext) {}
 
+/// Maximum number of VIP friends per player, invites can't be sent or accepted past it.
+pub const VIP_FRIENDS_CAP: u32 = 300;
+
+/// Minimum time between two bulk accepts of the same player.
+pub const BULK_ACCEPT_VIP_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+
+/// Minimum time between two VIP invites sent by the same player.
+pub const INSERT_VIP_COOLDOWN: Duration = Duration::from_secs(5);
+
 #[client_visibility_filter]
 const STDB_OWN_VIP_LIST_V1_FILTER: Filter = Filter::Sql(
     r#"
@@ -22,6 +31,32 @@ const STDB_OWN_VIP_LIST_V1_FILTER: Filter = Filter::Sql(
     from stdb_own_vip_v1 v
     join stdb_own_player_session_v1 s
         on s.player_id = v.sender_id
+    where s.session_id = :sender
+"#,
+);
+
+/// Each invite has a row per player, the inviter's row is also shown to the invited player so the
+/// client can list incoming invites with the inviter's tag and creation time.
+#[client_visibility_filter]
+const STDB_INCOMING_VIP_INVITES_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select v.*
+    from stdb_own_vip_v1 v
+    join stdb_own_player_session_v1 s
+        on s.player_id = v.receiver_id
+    where s.session_id = :sender
+        and v.status = 'InviteSent'
+"#,
+);
+
+#[client_visibility_filter]
+const STDB_PLAYER_PRIVACY_V1_FILTER: Filter = Filter::Sql(
+    r#"
+    select p.*
+    from stdb_player_privacy_v1 p
+    join stdb_own_player_session_v1 s
+        on s.player_id = p.player_id
+    where s.session_id = :sender
 "#,
 );
 
@@ -29,6 +64,7 @@ const STDB_OWN_VIP_LIST_V1_FILTER: Filter = Filter::Sql(
     name = stdb_own_vip_v1,
     public,
     index(name = player_ids_index, btree(columns = [sender_id, receiver_id])),
+    index(name = sender_status_index, btree(columns = [sender_id, status])),
 )]
 #[derive(Debug, Clone)]
 pub struct StdbOwnVipV1 {
@@ -40,6 +76,7 @@ pub struct StdbOwnVipV1 {
     pub sender_id: Uuid,
 
     // TODO think about a request limit to avoid harassment, blocking, etc.
+    #[index(btree)]
     pub receiver_id: Uuid,
 
     /// Tags are used by the player to just categorize this VIP connection.
@@ -61,11 +98,63 @@ pub enum VipStatusV1 {
 
     /// Both players added each other as friends
     Friends,
+
+    /// The invite was declined, set on both rows. A new invite from either player starts over.
+    InviteDeclined,
+}
+
+/// Player privacy table - who is allowed to send VIP invites to the player.
+///
+/// Only visible to the owning player. Players without a row accept invites from everyone.
+#[table(name = stdb_player_privacy_v1, public)]
+#[derive(Debug, Clone)]
+pub struct StdbPlayerPrivacyV1 {
+    #[primary_key]
+    pub player_id: Uuid,
+
+    pub vip_invite_policy: VipInvitePolicyV1,
+}
+
+#[derive(Debug, Copy, Clone, Default, Hash, PartialEq, Eq, SpacetimeType)]
+pub enum VipInvitePolicyV1 {
+    /// Any player can send an invite
+    #[default]
+    Everyone,
+
+    /// Only friends of the player's friends can send an invite
+    FriendsOfFriends,
+
+    /// Nobody can send an invite, the player can still send their own
+    Nobody,
 }
 
 #[reducer]
 pub fn insert_vip_v1(ctx: &ReducerContext, receiver_id: Uuid, tag: String) -> ServiceResult<()> {
-    let session = ctx.require_session()?;
+    ctx.require_not_blocked(&receiver_id)?;
+    let session = ctx.require_rate_limit("insert_vip", INSERT_VIP_COOLDOWN)?;
     ctx.insert_vip(session.player_id, receiver_id, tag)?;
     Ok(())
 }
+
+/// Accepts every pending VIP invite of the current player.
+#[reducer]
+pub fn bulk_accept_vip_invites_v1(ctx: &ReducerContext) -> ServiceResult<()> {
+    let session = ctx.require_rate_limit("bulk_accept_vip", BULK_ACCEPT_VIP_COOLDOWN)?;
+    ctx.bulk_accept_invites(&session.player_id)?;
+    Ok(())
+}
+
+/// Declines the pending VIP invite sent by `sender_id` to the current player.
+#[reducer]
+pub fn decline_vip_invite_v1(ctx: &ReducerContext, sender_id: Uuid) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.decline_invite(&session.player_id, &sender_id)?;
+    Ok(())
+}
+
+#[reducer]
+pub fn update_privacy_settings_v1(ctx: &ReducerContext, vip_invite_policy: VipInvitePolicyV1) -> ServiceResult<()> {
+    let session = ctx.require_session()?;
+    ctx.upsert_privacy_settings(session.player_id, vip_invite_policy)?;
+    Ok(())
+}
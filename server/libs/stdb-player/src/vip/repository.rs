@@ -1,11 +1,76 @@
-use crate::vip::{StdbOwnVipV1, VipStatusV1, stdb_own_vip_v1};
+use crate::{
+    error::PlayerError,
+    notification::{NotificationKindV1, repository::NotificationRepository},
+    vip::{
+        StdbOwnVipV1, StdbPlayerPrivacyV1, VIP_FRIENDS_CAP, VipInvitePolicyV1, VipStatusV1, stdb_own_vip_v1,
+        stdb_player_privacy_v1,
+    },
+};
 use spacetimedb::ReducerContext;
-use stdb_common::prelude::{ResultExt, ServiceResult, Uuid, validate_str, validate_uuid};
+use stdb_common::prelude::{BoundedVec, ResultExt, ServiceResult, Uuid, ValidationError, validate_uuid};
 
 pub trait VipRepository {
     fn find_vip(&self, sender_id: &Uuid, receiver_id: &Uuid) -> Option<StdbOwnVipV1>;
 
+    /// Sends a VIP invite, or accepts the one the receiver already sent.
+    ///
+    /// # Errors
+    /// Returns error if the receiver's privacy settings block new invites from the sender, a player
+    /// would go past [`VIP_FRIENDS_CAP`] friends, validation fails or database operations fail.
     fn insert_vip(&self, sender_id: Uuid, receiver_id: Uuid, tag: String) -> ServiceResult<StdbOwnVipV1>;
+
+    /// Counts the VIP rows of the player as `(friends, pending_sent, pending_received)`.
+    fn count_vips_for_player(&self, player_id: &Uuid) -> (u32, u32, u32);
+
+    /// Declines the pending invite `sender_id` sent to the player, setting both rows to `InviteDeclined`
+    /// and notifying the sender. Returns the row of the player.
+    ///
+    /// # Errors
+    /// Returns error if there's no pending invite from the sender or database operations fail.
+    fn decline_invite(&self, player_id: &Uuid, sender_id: &Uuid) -> ServiceResult<StdbOwnVipV1>;
+
+    /// Returns the VIP rows of invites the player received and didn't accept yet.
+    ///
+    /// The rows belong to the player, so the inviter is the `receiver_id`.
+    fn find_pending_received(&self, player_id: &Uuid) -> Vec<StdbOwnVipV1>;
+
+    /// Accepts every pending invite of the player, returning how many were accepted.
+    ///
+    /// Stops once the player reaches [`VIP_FRIENDS_CAP`] friends, and skips invites from players who did.
+    ///
+    /// # Errors
+    /// Returns error if database operations fail.
+    fn bulk_accept_invites(&self, player_id: &Uuid) -> ServiceResult<u32>
+    where
+        Self: Sized,
+    {
+        let (friends, _, _) = self.count_vips_for_player(player_id);
+        let invites = invites_to_accept(self.find_pending_received(player_id), friends, |inviter_id| {
+            require_friend_slot(self, inviter_id).is_ok()
+        })?;
+
+        let accepted = invites.len() as u32;
+        for invite in invites {
+            self.insert_vip(player_id.clone(), invite.receiver_id, invite.tag)?;
+        }
+        Ok(accepted)
+    }
+
+    /// Returns the IDs of the players who are friends with the player.
+    fn find_friend_ids(&self, player_id: &Uuid) -> Vec<Uuid>;
+
+    /// Returns the privacy settings of a player, or the defaults when none were stored.
+    fn find_privacy_settings_or_default(&self, player_id: &Uuid) -> StdbPlayerPrivacyV1;
+
+    /// Creates or replaces the privacy settings of a player.
+    ///
+    /// # Errors
+    /// Returns error if database operations fail.
+    fn upsert_privacy_settings(
+        &self,
+        player_id: Uuid,
+        vip_invite_policy: VipInvitePolicyV1,
+    ) -> ServiceResult<StdbPlayerPrivacyV1>;
 }
 
 impl VipRepository for ReducerContext {
@@ -20,15 +85,24 @@ impl VipRepository for ReducerContext {
     fn insert_vip(&self, sender_id: Uuid, receiver_id: Uuid, tag: String) -> ServiceResult<StdbOwnVipV1> {
         validate_uuid("sender_id", &sender_id)?;
         validate_uuid("receiver_id", &receiver_id)?;
-        validate_str("tag", &tag, 0, 32)?;
+        validate_vip_tag(&tag)?;
 
         let sender = self.find_vip(&sender_id, &receiver_id);
         let receiver = self.find_vip(&receiver_id, &sender_id);
 
-        match (&sender, &receiver) {
-            (None, None) | (Some(_), None) => {
+        match accepted_vip_invite(receiver.as_ref()) {
+            None => {
                 // Neither players tried to add each other. Let's create Invite requests.
                 // Or the receiver doesn't have an invite yet
+                let policy = self.find_privacy_settings_or_default(&receiver_id).vip_invite_policy;
+                let allowed = is_vip_invite_allowed(policy, &sender_id, &self.find_friend_ids(&sender_id), || {
+                    self.find_friend_ids(&receiver_id)
+                });
+                if !allowed {
+                    return Err(PlayerError::vip_invites_disabled(receiver_id));
+                }
+                require_friend_slot(self, &sender_id)?;
+
                 upsert_vip(
                     self,
                     &receiver,
@@ -37,15 +111,197 @@ impl VipRepository for ReducerContext {
                     "".to_string(),
                     VipStatusV1::InviteReceived,
                 )?;
+                self.notify_player(receiver_id.clone(), NotificationKindV1::VipInviteReceived, sender_id.clone())?;
                 upsert_vip(self, &sender, &sender_id, &receiver_id, tag, VipStatusV1::InviteSent)
             },
-            (None, Some(r)) | (Some(_), Some(r)) => {
+            Some(r) => {
                 // Receiver had an invite and now the sender is adding the receiver
+                if r.status != VipStatusV1::Friends {
+                    require_friend_slot(self, &sender_id)?;
+                    require_friend_slot(self, &receiver_id)?;
+                }
                 upsert_vip(self, &receiver, &receiver_id, &sender_id, r.tag.clone(), VipStatusV1::Friends)?;
+                if r.status != VipStatusV1::Friends {
+                    self.notify_player(receiver_id.clone(), NotificationKindV1::VipAccepted, sender_id.clone())?;
+                }
                 upsert_vip(self, &sender, &sender_id, &receiver_id, tag, VipStatusV1::Friends)
             },
         }
     }
+
+    fn count_vips_for_player(&self, player_id: &Uuid) -> (u32, u32, u32) {
+        count_vip_statuses(self.db.stdb_own_vip_v1().sender_id().filter(player_id).map(|vip| vip.status))
+    }
+
+    fn decline_invite(&self, player_id: &Uuid, sender_id: &Uuid) -> ServiceResult<StdbOwnVipV1> {
+        let (received, sent) = decline_vip_rows(
+            sender_id,
+            self.find_vip(player_id, sender_id),
+            self.find_vip(sender_id, player_id),
+        )?;
+
+        self.db
+            .stdb_own_vip_v1()
+            .vip_id()
+            .try_insert_or_update(sent)
+            .map_conflict_ctx("failed to decline vip")?;
+        self.notify_player(sender_id.clone(), NotificationKindV1::VipDeclined, player_id.clone())?;
+
+        self.db
+            .stdb_own_vip_v1()
+            .vip_id()
+            .try_insert_or_update(received)
+            .map_conflict_ctx("failed to decline vip")
+    }
+
+    fn find_pending_received(&self, player_id: &Uuid) -> Vec<StdbOwnVipV1> {
+        self.db
+            .stdb_own_vip_v1()
+            .sender_status_index()
+            .filter((player_id, VipStatusV1::InviteReceived))
+            .filter(|vip| is_pending_received(vip, player_id))
+            .collect()
+    }
+
+    fn find_friend_ids(&self, player_id: &Uuid) -> Vec<Uuid> {
+        self.db
+            .stdb_own_vip_v1()
+            .sender_id()
+            .filter(player_id)
+            .filter(|vip| vip.status == VipStatusV1::Friends)
+            .map(|vip| vip.receiver_id)
+            .collect()
+    }
+
+    fn find_privacy_settings_or_default(&self, player_id: &Uuid) -> StdbPlayerPrivacyV1 {
+        self.db
+            .stdb_player_privacy_v1()
+            .player_id()
+            .find(player_id)
+            .unwrap_or(StdbPlayerPrivacyV1 {
+                player_id: player_id.clone(),
+                vip_invite_policy: VipInvitePolicyV1::default(),
+            })
+    }
+
+    fn upsert_privacy_settings(
+        &self,
+        player_id: Uuid,
+        vip_invite_policy: VipInvitePolicyV1,
+    ) -> ServiceResult<StdbPlayerPrivacyV1> {
+        validate_uuid("player_id", &player_id)?;
+
+        self.db
+            .stdb_player_privacy_v1()
+            .player_id()
+            .try_insert_or_update(StdbPlayerPrivacyV1 {
+                player_id,
+                vip_invite_policy,
+            })
+            .map_conflict_ctx("failed to insert or update privacy settings")
+    }
+}
+
+/// Fails if the player already has [`VIP_FRIENDS_CAP`] friends.
+pub(crate) fn require_friend_slot(repository: &impl VipRepository, player_id: &Uuid) -> ServiceResult<()> {
+    let (friends, _, _) = repository.count_vips_for_player(player_id);
+    require_below_friends_cap(player_id, friends)
+}
+
+/// Requires that a player with `friends` VIP friends is below [`VIP_FRIENDS_CAP`].
+pub(crate) fn require_below_friends_cap(player_id: &Uuid, friends: u32) -> ServiceResult<()> {
+    if friends >= VIP_FRIENDS_CAP {
+        return Err(PlayerError::vip_friend_limit_reached(player_id.clone()));
+    }
+    Ok(())
+}
+
+/// The pending invites a bulk accept goes through, in order, until the player with `friends` friends
+/// reaches [`VIP_FRIENDS_CAP`]. Invites from players without a free slot themselves are skipped.
+pub(crate) fn invites_to_accept(
+    invites: Vec<StdbOwnVipV1>,
+    friends: u32,
+    has_friend_slot: impl Fn(&Uuid) -> bool,
+) -> ServiceResult<BoundedVec<StdbOwnVipV1>> {
+    let mut accepted = BoundedVec::new(VIP_FRIENDS_CAP.saturating_sub(friends) as usize);
+    for invite in invites {
+        if accepted.is_full() {
+            break;
+        }
+        if has_friend_slot(&invite.receiver_id) {
+            accepted.push(invite)?;
+        }
+    }
+    Ok(accepted)
+}
+
+/// Whether `vip` is an invite `player_id` received and didn't answer yet.
+///
+/// Rows are stored from the point of view of `sender_id`, so received invites are the rows of the player
+/// with the `InviteReceived` status.
+pub(crate) fn is_pending_received(vip: &StdbOwnVipV1, player_id: &Uuid) -> bool {
+    vip.sender_id == *player_id && vip.status == VipStatusV1::InviteReceived
+}
+
+/// The receiver's row towards the sender if an invite from the sender makes both players friends,
+/// or `None` if it starts a new invite.
+///
+/// Declined invites don't count, a new invite starts over.
+pub(crate) fn accepted_vip_invite(receiver: Option<&StdbOwnVipV1>) -> Option<&StdbOwnVipV1> {
+    receiver.filter(|receiver| receiver.status != VipStatusV1::InviteDeclined)
+}
+
+/// Moves a pending invite to `InviteDeclined`, returning the `(received, sent)` rows to store.
+///
+/// `received` is the row of the invited player and `sent` the row of `sender_id`, both keep their tags.
+///
+/// # Errors
+/// Returns `PlayerError::VipInviteNotFound` if either row is missing or the invite isn't pending,
+/// including invites that were already declined or accepted.
+pub(crate) fn decline_vip_rows(
+    sender_id: &Uuid,
+    received: Option<StdbOwnVipV1>,
+    sent: Option<StdbOwnVipV1>,
+) -> ServiceResult<(StdbOwnVipV1, StdbOwnVipV1)> {
+    let (Some(mut received), Some(mut sent)) = (received, sent) else {
+        return Err(PlayerError::vip_invite_not_found(sender_id.clone()));
+    };
+    if received.status != VipStatusV1::InviteReceived {
+        return Err(PlayerError::vip_invite_not_found(sender_id.clone()));
+    }
+
+    received.status = VipStatusV1::InviteDeclined;
+    sent.status = VipStatusV1::InviteDeclined;
+    Ok((received, sent))
+}
+
+/// Counts statuses as `(friends, pending_sent, pending_received)`.
+pub(crate) fn count_vip_statuses(statuses: impl Iterator<Item = VipStatusV1>) -> (u32, u32, u32) {
+    statuses.fold((0, 0, 0), |(friends, sent, received), status| match status {
+        VipStatusV1::Friends => (friends + 1, sent, received),
+        VipStatusV1::InviteSent => (friends, sent + 1, received),
+        VipStatusV1::InviteReceived => (friends, sent, received + 1),
+        VipStatusV1::InviteDeclined => (friends, sent, received),
+    })
+}
+
+/// Checks whether the receiver's policy lets the sender start a new VIP invite.
+///
+/// The receiver's friends are only loaded for `FriendsOfFriends`.
+pub(crate) fn is_vip_invite_allowed(
+    policy: VipInvitePolicyV1,
+    sender_id: &Uuid,
+    sender_friend_ids: &[Uuid],
+    receiver_friend_ids: impl FnOnce() -> Vec<Uuid>,
+) -> bool {
+    match policy {
+        VipInvitePolicyV1::Everyone => true,
+        VipInvitePolicyV1::Nobody => false,
+        VipInvitePolicyV1::FriendsOfFriends => {
+            let receiver_friend_ids = receiver_friend_ids();
+            receiver_friend_ids.contains(sender_id) || sender_friend_ids.iter().any(|id| receiver_friend_ids.contains(id))
+        },
+    }
 }
 
 fn upsert_vip(
@@ -79,3 +335,242 @@ fn upsert_vip(
         .try_insert_or_update(new_row)
         .map_conflict_ctx("failed to insert vip")
 }
+
+/// Maximum number of Unicode scalar values of a VIP tag.
+const VIP_TAG_MAX_CHARS: usize = 8;
+
+/// Validates the tag a player gives to a VIP, e.g. `"⭐"` or `"Duo1"`.
+///
+/// Empty tags are allowed. Otherwise the tag must be 1-8 Unicode scalar values, each a letter,
+/// a number or part of an emoji.
+///
+/// # Errors
+/// Returns a validation error on the `tag` field otherwise.
+pub fn validate_vip_tag(tag: &str) -> ServiceResult<()> {
+    if tag.chars().count() > VIP_TAG_MAX_CHARS {
+        return Err(ValidationError::field_error(
+            "tag",
+            format!("must be at most {VIP_TAG_MAX_CHARS} characters"),
+        ));
+    }
+    if !tag.chars().all(|ch| ch.is_alphanumeric() || is_emoji_char(ch)) {
+        return Err(ValidationError::field_error(
+            "tag",
+            "must only contain letters, numbers and emoji",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Whether the char belongs to the blocks emoji are drawn from, or is used to compose them
+/// (zero width joiner, variation selector, keycap and tag characters).
+///
+/// std has no emoji property, so this is an approximation: a few symbols of these blocks aren't emoji.
+pub(crate) fn is_emoji_char(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{00A9}' | '\u{00AE}' | '\u{203C}' | '\u{2049}' | '\u{2122}' | '\u{2139}' | '\u{24C2}' | '\u{3030}' | '\u{303D}'
+            | '\u{3297}' | '\u{3299}'
+            | '\u{200D}'
+            | '\u{FE0F}'
+            | '\u{20E3}'
+            | '\u{2194}'..='\u{21AA}'
+            | '\u{2300}'..='\u{23FF}'
+            | '\u{25AA}'..='\u{25FE}'
+            | '\u{2600}'..='\u{27BF}'
+            | '\u{2900}'..='\u{2935}'
+            | '\u{2B00}'..='\u{2BFF}'
+            | '\u{1F000}'..='\u{1FAFF}'
+            | '\u{E0020}'..='\u{E007F}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spacetimedb::Timestamp;
+
+    const SENDER: &str = "01890a5d-ac96-774b-bcce-b302099a8057";
+    const RECEIVER: &str = "01890a5d-ac96-774b-bcce-b302099a8058";
+
+    #[test]
+    fn test_validate_vip_tag_valid() {
+        assert!(validate_vip_tag("").is_ok());
+        assert!(validate_vip_tag("⭐").is_ok());
+        assert!(validate_vip_tag("❤\u{FE0F}🔥").is_ok());
+        assert!(validate_vip_tag("👍🏽").is_ok());
+        assert!(validate_vip_tag("🇧🇷").is_ok());
+        assert!(validate_vip_tag("👩\u{200D}💻").is_ok());
+        assert!(validate_vip_tag("Duo1").is_ok());
+        assert!(validate_vip_tag("Ñandú").is_ok());
+        assert!(validate_vip_tag("친구").is_ok());
+        assert!(validate_vip_tag("12345678").is_ok());
+    }
+
+    #[test]
+    fn test_validate_vip_tag_invalid() {
+        assert!(validate_vip_tag("123456789").is_err());
+        assert!(validate_vip_tag("⭐⭐⭐⭐⭐⭐⭐⭐⭐").is_err());
+        assert!(validate_vip_tag("best bud").is_err());
+        assert!(validate_vip_tag("a\0").is_err());
+        assert!(validate_vip_tag("a\n").is_err());
+        assert!(validate_vip_tag("\u{202E}abc").is_err());
+        assert!(validate_vip_tag("<b>").is_err());
+
+        let error = validate_vip_tag("a\u{7}").unwrap_err();
+        assert_eq!(
+            error.message(),
+            "Field 'tag' is invalid: must only contain letters, numbers and emoji"
+        );
+    }
+
+    fn ids(values: &[&str]) -> Vec<Uuid> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_vip_invite_allowed_everyone() {
+        assert!(is_vip_invite_allowed(
+            VipInvitePolicyV1::Everyone,
+            &"a".to_string(),
+            &[],
+            Vec::new
+        ));
+    }
+
+    #[test]
+    fn test_vip_invite_allowed_nobody() {
+        assert!(!is_vip_invite_allowed(
+            VipInvitePolicyV1::Nobody,
+            &"a".to_string(),
+            &ids(&["c"]),
+            || ids(&["c"])
+        ));
+    }
+
+    #[test]
+    fn test_vip_invite_allowed_friends_of_friends() {
+        let policy = VipInvitePolicyV1::FriendsOfFriends;
+        let sender_id = "a".to_string();
+
+        assert!(is_vip_invite_allowed(policy, &sender_id, &ids(&["b", "c"]), || ids(&[
+            "c", "d"
+        ])));
+        assert!(is_vip_invite_allowed(policy, &sender_id, &[], || ids(&["a"])));
+        assert!(!is_vip_invite_allowed(policy, &sender_id, &ids(&["b"]), || ids(&["d"])));
+        assert!(!is_vip_invite_allowed(policy, &sender_id, &[], Vec::new));
+    }
+
+    #[test]
+    fn test_require_below_friends_cap() {
+        let player_id = SENDER.to_string();
+        assert!(require_below_friends_cap(&player_id, 0).is_ok());
+        assert!(require_below_friends_cap(&player_id, VIP_FRIENDS_CAP - 1).is_ok());
+        assert!(require_below_friends_cap(&player_id, VIP_FRIENDS_CAP).is_err());
+        assert!(require_below_friends_cap(&player_id, VIP_FRIENDS_CAP + 1).is_err());
+    }
+
+    fn vip(sender_id: &str, receiver_id: &str, tag: &str, status: VipStatusV1) -> StdbOwnVipV1 {
+        StdbOwnVipV1 {
+            vip_id: 1,
+            sender_id: sender_id.to_string(),
+            receiver_id: receiver_id.to_string(),
+            tag: tag.to_string(),
+            status,
+            created_at: Timestamp::UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn test_invites_to_accept_stops_at_friends_cap() {
+        let invites: Vec<_> = ["a", "b", "c", "d"]
+            .into_iter()
+            .map(|inviter| vip(RECEIVER, inviter, "", VipStatusV1::InviteReceived))
+            .collect();
+        let inviters =
+            |accepted: BoundedVec<StdbOwnVipV1>| -> Vec<_> { accepted.into_iter().map(|invite| invite.receiver_id).collect() };
+
+        let accepted = invites_to_accept(invites.clone(), 0, |_| true).unwrap();
+        assert_eq!(inviters(accepted), ["a", "b", "c", "d"]);
+
+        // Inviters without a free slot are skipped without using one of the player's slots
+        let accepted = invites_to_accept(invites.clone(), VIP_FRIENDS_CAP - 2, |inviter_id| inviter_id != "a").unwrap();
+        assert_eq!(inviters(accepted), ["b", "c"]);
+
+        assert!(invites_to_accept(invites, VIP_FRIENDS_CAP, |_| true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_is_pending_received() {
+        let player_id = RECEIVER.to_string();
+        assert!(is_pending_received(
+            &vip(RECEIVER, SENDER, "", VipStatusV1::InviteReceived),
+            &player_id
+        ));
+
+        // Rows of another player, or invites that aren't pending anymore
+        assert!(!is_pending_received(
+            &vip(SENDER, RECEIVER, "", VipStatusV1::InviteReceived),
+            &player_id
+        ));
+        for status in [VipStatusV1::InviteSent, VipStatusV1::InviteDeclined, VipStatusV1::Friends] {
+            assert!(!is_pending_received(&vip(RECEIVER, SENDER, "", status), &player_id));
+        }
+    }
+
+    #[test]
+    fn test_accepted_vip_invite() {
+        assert!(accepted_vip_invite(None).is_none());
+        assert!(accepted_vip_invite(Some(&vip(RECEIVER, SENDER, "", VipStatusV1::InviteDeclined))).is_none());
+
+        let pending = vip(RECEIVER, SENDER, "⭐", VipStatusV1::InviteSent);
+        assert_eq!(accepted_vip_invite(Some(&pending)).unwrap().tag, "⭐");
+        assert!(accepted_vip_invite(Some(&vip(RECEIVER, SENDER, "", VipStatusV1::Friends))).is_some());
+    }
+
+    #[test]
+    fn test_decline_vip_rows() {
+        let received = vip(RECEIVER, SENDER, "", VipStatusV1::InviteReceived);
+        let sent = vip(SENDER, RECEIVER, "⭐", VipStatusV1::InviteSent);
+
+        let (received, sent) = decline_vip_rows(&SENDER.to_string(), Some(received), Some(sent)).unwrap();
+        assert_eq!(received.status, VipStatusV1::InviteDeclined);
+        assert_eq!(sent.status, VipStatusV1::InviteDeclined);
+        assert_eq!(sent.tag, "⭐");
+        assert_eq!(sent.sender_id, SENDER);
+    }
+
+    #[test]
+    fn test_decline_vip_rows_not_pending() {
+        let sender_id = SENDER.to_string();
+        let sent = vip(SENDER, RECEIVER, "", VipStatusV1::InviteSent);
+        assert!(decline_vip_rows(&sender_id, None, None).is_err());
+        assert!(decline_vip_rows(&sender_id, None, Some(sent.clone())).is_err());
+        assert!(decline_vip_rows(&sender_id, Some(vip(RECEIVER, SENDER, "", VipStatusV1::InviteReceived)), None).is_err());
+
+        for status in [VipStatusV1::InviteDeclined, VipStatusV1::Friends, VipStatusV1::InviteSent] {
+            let received = vip(RECEIVER, SENDER, "", status);
+            let error = decline_vip_rows(&sender_id, Some(received), Some(sent.clone())).unwrap_err();
+            assert_eq!(
+                error.message(),
+                PlayerError::vip_invite_not_found(sender_id.clone()).message()
+            );
+        }
+    }
+
+    #[test]
+    fn test_count_vip_statuses() {
+        let statuses = [
+            VipStatusV1::Friends,
+            VipStatusV1::InviteSent,
+            VipStatusV1::Friends,
+            VipStatusV1::InviteReceived,
+            VipStatusV1::InviteSent,
+            VipStatusV1::InviteDeclined,
+        ];
+
+        assert_eq!(count_vip_statuses(statuses.into_iter()), (2, 2, 1));
+        assert_eq!(count_vip_statuses(std::iter::empty()), (0, 0, 0));
+    }
+}